@@ -0,0 +1,71 @@
+//! 掩码解码基准：逐像素的朴素实现、逐行累加的单线程实现和按检测并行的实现
+//!
+//! 运行：`cargo bench --bench mask_decode`；并行版本的加速比取决于 CPU 核心数
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use yolo11s_tensorrt_rs::decode::{decode_masks, Proto, MASK_COEFFICIENTS, PROTO_STRIDE};
+use yolo11s_tensorrt_rs::RawCandidate;
+
+/// 优化前的逐像素实现，与测试共用
+#[path = "../tests/common/reference.rs"]
+mod reference;
+
+const PROTO: usize = 160;
+
+/// 固定种子的伪随机数，保证每次运行的输入一致
+fn pseudo_random(len: usize, seed: u32) -> Vec<f32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+        })
+        .collect()
+}
+
+fn candidates(count: usize) -> Vec<RawCandidate> {
+    let coefficients = pseudo_random(count * MASK_COEFFICIENTS, 7);
+    (0..count)
+        .map(|i| {
+            // 80~240 像素的框散布在画面中
+            let x = (i * 97 % 400) as f32;
+            let y = (i * 53 % 400) as f32;
+            let size = 80.0 + (i * 31 % 160) as f32;
+            let mut candidate = RawCandidate::new([x, y, x + size, y + size], 0.9, 0);
+            candidate
+                .mask_coefficients
+                .copy_from_slice(&coefficients[i * MASK_COEFFICIENTS..(i + 1) * MASK_COEFFICIENTS]);
+            candidate
+        })
+        .collect()
+}
+
+fn bench_mask_decode(c: &mut Criterion) {
+    let data = pseudo_random(MASK_COEFFICIENTS * PROTO * PROTO, 1);
+    let proto = Proto::new(&data, PROTO, PROTO).unwrap();
+
+    let mut group = c.benchmark_group("mask_decode");
+    group.sample_size(20);
+    for count in [1, 10, 50] {
+        let candidates = candidates(count);
+        group.bench_with_input(BenchmarkId::new("naive", count), &candidates, |b, cs| {
+            b.iter(|| {
+                cs.iter()
+                    .map(|c| reference::reference_mask(&data, PROTO, PROTO_STRIDE, c))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &candidates,
+            |b, cs| b.iter(|| cs.iter().map(|c| proto.decode_mask(c)).collect::<Vec<_>>()),
+        );
+        group.bench_with_input(BenchmarkId::new("parallel", count), &candidates, |b, cs| {
+            b.iter(|| decode_masks(black_box(cs), &proto))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mask_decode);
+criterion_main!(benches);
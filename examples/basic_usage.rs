@@ -1,5 +1,5 @@
 use std::env;
-use yolo11s_tensorrt_rs::{Config, Yolo, YoloError};
+use yolo11s_tensorrt_rs::{Benchmark, Config, Yolo, YoloError};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 解析命令行参数
@@ -41,6 +41,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  TensorRT推理: {:.2}ms", perf.tensorrt_time_ms);
     println!("  后处理: {:.2}ms", perf.postprocess_time_ms);
     println!("  结果复制: {:.2}ms", perf.result_copy_time_ms);
+    println!(
+        "  GPU 执行: 预处理 {:.2}ms, 推理 {:.2}ms, 拷贝 {:.2}ms",
+        perf.gpu_preprocess_time_ms, perf.gpu_tensorrt_time_ms, perf.gpu_copy_time_ms
+    );
     println!("  FPS: {:.1}", perf.fps());
     println!("  TensorRT占比: {:.1}%", perf.tensorrt_percentage());
 
@@ -70,17 +74,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     yolo.save_result_image(&image_path, &result, &output_path)?;
     println!("✅ 结果图片已保存: {}", output_path);
 
-    // 执行批量推理测试
-    println!("\n⚡ 执行批量推理测试...");
-    match yolo.batch_inference_test(&image_path, 1000) {
-        Ok(stats) => {
-            println!("📊 批量推理结果 (1000 次):");
-            println!("  平均时间: {:.2}ms", stats.total_time_ms);
-            println!("  FPS: {:.1}", stats.fps());
-            println!("  TensorRT推理占比: {:.1}%", stats.tensorrt_percentage());
+    // 执行基准测试
+    println!("\n⚡ 执行基准测试...");
+    match Benchmark::new(&yolo)
+        .with_images([image_path.as_str()])
+        .with_iterations(1000)
+        .run()
+    {
+        Ok(report) => {
+            println!("📊 基准测试结果 ({} 次):", report.iterations);
+            println!("  平均时间: {:.2}ms", report.latency.mean_ms);
+            println!("  p99 时间: {:.2}ms", report.latency.p99_ms);
+            println!("  FPS: {:.1}", report.throughput_fps());
         }
         Err(e) => {
-            println!("⚠️  批量推理测试失败: {}", e);
+            println!("⚠️  基准测试失败: {}", e);
         }
     }
 
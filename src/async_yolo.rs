@@ -0,0 +1,162 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::backend::InferenceBackend;
+use crate::error::{YoloError, YoloResult};
+use crate::pool::{Job, PoolConfig, YoloPool};
+use crate::types::{Config, InferenceResult};
+
+/// 异步推理接口
+///
+/// 在专用的阻塞工作线程（[`YoloPool`]）上执行推理，通过 future 返回结果，
+/// 不会占用异步运行时的线程。不依赖特定的运行时，可直接在 tokio 等执行器中 `.await`。
+///
+/// 队列已满时 future 会挂起等待空位，而不是阻塞线程。
+/// 在任务开始执行前丢弃 future 会取消该任务；已开始的推理会执行完毕，结果被丢弃。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{AsyncYolo, Config, PoolConfig};
+///
+/// async fn run() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = AsyncYolo::new(Config::new("models/yolo11s-seg.engine"), PoolConfig::new(2))?;
+///     let result = yolo.infer("images/test.jpg").await?;
+///     println!("检测到 {} 个目标", result.detection_count());
+///     Ok(())
+/// }
+/// ```
+pub struct AsyncYolo {
+    pool: YoloPool,
+}
+
+impl AsyncYolo {
+    /// 按配置创建 `pool_config.workers` 个推理器
+    pub fn new(config: Config, pool_config: PoolConfig) -> YoloResult<Self> {
+        Ok(Self::from_pool(YoloPool::new(config, pool_config)?))
+    }
+
+    /// 用已有的后端创建异步推理接口
+    pub fn from_backends<B: InferenceBackend + 'static>(
+        backends: Vec<B>,
+        pool_config: PoolConfig,
+    ) -> YoloResult<Self> {
+        Ok(Self::from_pool(YoloPool::from_backends(
+            backends,
+            pool_config,
+        )?))
+    }
+
+    /// 用已有的工作池创建异步推理接口
+    pub fn from_pool(pool: YoloPool) -> Self {
+        Self { pool }
+    }
+
+    /// 异步执行推理
+    pub async fn infer(&self, image_path: impl Into<String>) -> YoloResult<InferenceResult> {
+        InferFuture {
+            pool: &self.pool,
+            image_path: Some(image_path.into()),
+            slot: Arc::new(Slot::default()),
+        }
+        .await
+    }
+
+    /// 底层工作池
+    pub fn pool(&self) -> &YoloPool {
+        &self.pool
+    }
+
+    /// 关闭并等待所有已提交的任务处理完毕
+    pub fn shutdown(self) {
+        self.pool.shutdown();
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct SlotState {
+    result: Option<YoloResult<InferenceResult>>,
+    waker: Option<Waker>,
+}
+
+impl Slot {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SlotState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn complete(&self, result: YoloResult<InferenceResult>) {
+        let waker = {
+            let mut state = self.lock();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+struct InferFuture<'a> {
+    pool: &'a YoloPool,
+    /// 提交成功前保存待推理的图片路径
+    image_path: Option<String>,
+    slot: Arc<Slot>,
+}
+
+impl InferFuture<'_> {
+    fn try_submit(&self, image_path: &str) -> YoloResult<()> {
+        let slot = Arc::clone(&self.slot);
+        let job = Job::new(image_path.to_string(), move |result| slot.complete(result))
+            .with_cancel_flag(Arc::clone(&self.slot.cancelled));
+        self.pool.try_submit_job(job)
+    }
+}
+
+impl Future for InferFuture<'_> {
+    type Output = YoloResult<InferenceResult>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(image_path) = self.image_path.take() {
+            let mut registered = false;
+            loop {
+                match self.try_submit(&image_path) {
+                    Ok(()) => break,
+                    // 队列满时才登记等待空位，登记后再试一次，避免在两步之间错过工作线程的唤醒
+                    Err(YoloError::QueueFull(_)) if !registered => {
+                        self.pool.register_capacity_waker(cx.waker());
+                        registered = true;
+                    }
+                    Err(YoloError::QueueFull(_)) => {
+                        self.image_path = Some(image_path);
+                        return Poll::Pending;
+                    }
+                    Err(e) => return Poll::Ready(Err(e)),
+                }
+            }
+        }
+
+        let mut state = self.slot.lock();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for InferFuture<'_> {
+    fn drop(&mut self) {
+        self.slot.cancelled.store(true, Ordering::SeqCst);
+    }
+}
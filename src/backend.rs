@@ -0,0 +1,111 @@
+use crate::error::{YoloError, YoloResult};
+use crate::types::{Frame, InferenceResult};
+use crate::yolo::Yolo;
+
+/// 推理后端抽象
+///
+/// [`Yolo`] 是默认实现。`SharedYolo` 等并发封装只依赖这个 trait，
+/// 因此可以在没有 GPU 的环境中用 mock 后端替代真实的 TensorRT 推理器进行测试。
+///
+/// 推理需要独占执行上下文，所以方法接收 `&mut self`；
+/// 实现必须是 `Send`，以便移动到工作线程中使用。
+pub trait InferenceBackend: Send {
+    /// 对图片执行推理
+    fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult>;
+
+    /// 对在队列中等待过的图片执行推理
+    ///
+    /// `queue_wait_ms` 是任务的排队时间，实现应在记录性能统计之前把它写入结果。
+    /// 默认实现在 [`inference`](Self::inference) 返回后再填入该字段。
+    fn inference_queued(
+        &mut self,
+        image_path: &str,
+        queue_wait_ms: f64,
+    ) -> YoloResult<InferenceResult> {
+        self.inference(image_path).map(|mut result| {
+            result.queue_wait_ms = queue_wait_ms;
+            result
+        })
+    }
+
+    /// 一次执行整个批次的推理，结果顺序与 `frames` 一致
+    ///
+    /// 默认实现返回错误，表示后端不支持批量推理。
+    fn inference_batch(&mut self, frames: &[Frame]) -> YoloResult<Vec<InferenceResult>> {
+        let _ = frames;
+        Err(YoloError::InvalidParameter(
+            "该后端不支持批量推理".to_string(),
+        ))
+    }
+
+    /// 对在队列中等待过的批次执行推理，`queue_wait_ms` 与 `frames` 一一对应
+    ///
+    /// 默认实现在 [`inference_batch`](Self::inference_batch) 返回后再填入排队时间。
+    fn inference_batch_queued(
+        &mut self,
+        frames: &[Frame],
+        queue_wait_ms: &[f64],
+    ) -> YoloResult<Vec<InferenceResult>> {
+        self.inference_batch(frames).map(|mut results| {
+            for (result, &wait) in results.iter_mut().zip(queue_wait_ms) {
+                result.queue_wait_ms = wait;
+            }
+            results
+        })
+    }
+
+    /// 单个批次的最大图片数
+    fn max_batch_size(&self) -> usize {
+        1
+    }
+
+    /// 后端是否因 CUDA 错误失效，失效后需要 [`recover`](Self::recover) 才能继续推理
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+
+    /// 重建执行上下文，使失效的后端恢复可用
+    ///
+    /// 默认实现返回错误，表示后端不支持恢复。
+    fn recover(&mut self) -> YoloResult<()> {
+        Err(YoloError::InvalidParameter("该后端不支持恢复".to_string()))
+    }
+}
+
+impl InferenceBackend for Yolo {
+    fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        Yolo::inference(self, image_path)
+    }
+
+    fn inference_batch(&mut self, frames: &[Frame]) -> YoloResult<Vec<InferenceResult>> {
+        Yolo::inference_batch(self, frames)
+    }
+
+    fn inference_queued(
+        &mut self,
+        image_path: &str,
+        queue_wait_ms: f64,
+    ) -> YoloResult<InferenceResult> {
+        Yolo::inference_queued(self, image_path, queue_wait_ms)
+    }
+
+    fn inference_batch_queued(
+        &mut self,
+        frames: &[Frame],
+        queue_wait_ms: &[f64],
+    ) -> YoloResult<Vec<InferenceResult>> {
+        Yolo::inference_batch_queued(self, frames, queue_wait_ms)
+    }
+
+    fn max_batch_size(&self) -> usize {
+        Yolo::max_batch_size(self)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        Yolo::is_poisoned(self)
+    }
+
+    fn recover(&mut self) -> YoloResult<()> {
+        Yolo::recover(self)
+    }
+}
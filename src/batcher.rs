@@ -0,0 +1,306 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::backend::InferenceBackend;
+use crate::error::{YoloError, YoloResult};
+use crate::pool::JobHandle;
+use crate::types::{Config, Frame, InferenceResult};
+use crate::yolo::Yolo;
+
+/// 动态批处理配置
+#[derive(Debug, Clone)]
+pub struct BatcherConfig {
+    /// 单个批次的最大请求数，`None` 表示使用后端支持的最大批次
+    pub max_batch_size: Option<usize>,
+    /// 第一个请求到达后最多等待多久凑满批次
+    pub max_wait: Duration,
+    /// 等待组批的请求队列容量
+    pub queue_capacity: usize,
+}
+
+impl Default for BatcherConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: None,
+            max_wait: Duration::from_millis(5),
+            queue_capacity: 64,
+        }
+    }
+}
+
+impl BatcherConfig {
+    /// 设置单个批次的最大请求数
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    /// 设置最大等待时间
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// 设置请求队列容量
+    pub fn with_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+}
+
+/// 批处理统计
+#[derive(Debug, Clone, Default)]
+pub struct BatchMetrics {
+    /// 已执行的批次数
+    pub batches: u64,
+    /// 已处理的请求数
+    pub requests: u64,
+    /// 批次大小分布，下标为批次大小
+    pub batch_size_histogram: Vec<u64>,
+}
+
+impl BatchMetrics {
+    /// 平均批次大小
+    pub fn mean_batch_size(&self) -> f64 {
+        if self.batches > 0 {
+            self.requests as f64 / self.batches as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// 出现过的最大批次大小
+    pub fn max_batch_size(&self) -> usize {
+        self.batch_size_histogram
+            .iter()
+            .rposition(|&count| count > 0)
+            .unwrap_or(0)
+    }
+
+    fn record(&mut self, batch_size: usize) {
+        if self.batch_size_histogram.len() <= batch_size {
+            self.batch_size_histogram.resize(batch_size + 1, 0);
+        }
+        self.batch_size_histogram[batch_size] += 1;
+        self.batches += 1;
+        self.requests += batch_size as u64;
+    }
+}
+
+struct Request {
+    frame: Frame,
+    submitted: Instant,
+    reply: mpsc::Sender<YoloResult<InferenceResult>>,
+}
+
+/// 动态批处理器
+///
+/// 并发调用者各自提交单张图片，批处理器在后台线程中把请求收集成批：
+/// 达到最大批次或第一个请求等待超过 `max_wait` 时，以一次引擎调用执行整个批次，
+/// 再把结果分发回各个调用者。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use yolo11s_tensorrt_rs::{BatcherConfig, Config, DynamicBatcher, Frame};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let batcher = DynamicBatcher::new(
+///         Config::new("models/yolo11s-seg.engine"),
+///         BatcherConfig::default().with_max_wait(Duration::from_millis(2)),
+///     )?;
+///
+///     let result = batcher.infer(Frame::open("images/test.jpg")?)?;
+///     println!("检测到 {} 个目标", result.detection_count());
+///     println!("平均批次大小: {:.2}", batcher.metrics().mean_batch_size());
+///     Ok(())
+/// }
+/// ```
+pub struct DynamicBatcher {
+    sender: Option<SyncSender<Request>>,
+    metrics: Arc<Mutex<BatchMetrics>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DynamicBatcher {
+    /// 按配置创建推理器和批处理器
+    pub fn new(config: Config, batcher_config: BatcherConfig) -> YoloResult<Self> {
+        Self::from_backend(Yolo::new(config)?, batcher_config)
+    }
+
+    /// 用已有的后端创建批处理器
+    pub fn from_backend<B: InferenceBackend + 'static>(
+        backend: B,
+        batcher_config: BatcherConfig,
+    ) -> YoloResult<Self> {
+        let max_batch_size = batcher_config
+            .max_batch_size
+            .unwrap_or_else(|| backend.max_batch_size())
+            .min(backend.max_batch_size());
+        if max_batch_size == 0 {
+            return Err(YoloError::InvalidParameter(
+                "最大批次大小必须大于 0".to_string(),
+            ));
+        }
+        if batcher_config.queue_capacity == 0 {
+            return Err(YoloError::InvalidParameter(
+                "队列容量必须大于 0".to_string(),
+            ));
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(batcher_config.queue_capacity);
+        let metrics = Arc::new(Mutex::new(BatchMetrics::default()));
+        let thread = {
+            let metrics = Arc::clone(&metrics);
+            let max_wait = batcher_config.max_wait;
+            thread::Builder::new()
+                .name("yolo-batcher".to_string())
+                .spawn(move || run_batches(backend, receiver, max_batch_size, max_wait, metrics))?
+        };
+
+        Ok(Self {
+            sender: Some(sender),
+            metrics,
+            thread: Some(thread),
+        })
+    }
+
+    /// 提交一张图片，返回用于等待结果的句柄
+    ///
+    /// 请求队列已满时阻塞。
+    pub fn submit(&self, frame: Frame) -> YoloResult<JobHandle> {
+        let (reply, receiver) = mpsc::channel();
+        self.sender()?
+            .send(Request {
+                frame,
+                submitted: Instant::now(),
+                reply,
+            })
+            .map_err(|_| YoloError::ShutDown("批处理线程已退出".to_string()))?;
+        Ok(JobHandle::new(receiver))
+    }
+
+    /// 尝试提交一张图片，队列已满时立即返回 [`YoloError::QueueFull`]
+    pub fn try_submit(&self, frame: Frame) -> YoloResult<JobHandle> {
+        let (reply, receiver) = mpsc::channel();
+        self.sender()?
+            .try_send(Request {
+                frame,
+                submitted: Instant::now(),
+                reply,
+            })
+            .map_err(|e| match e {
+                TrySendError::Full(_) => YoloError::QueueFull("批处理队列已满".to_string()),
+                TrySendError::Disconnected(_) => {
+                    YoloError::ShutDown("批处理线程已退出".to_string())
+                }
+            })?;
+        Ok(JobHandle::new(receiver))
+    }
+
+    /// 提交一张图片并阻塞等待结果
+    pub fn infer(&self, frame: Frame) -> YoloResult<InferenceResult> {
+        self.submit(frame)?.wait()
+    }
+
+    /// 批处理统计快照
+    pub fn metrics(&self) -> BatchMetrics {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 关闭批处理器，处理完所有已提交的请求后返回
+    pub fn shutdown(mut self) {
+        self.close();
+    }
+
+    fn sender(&self) -> YoloResult<&SyncSender<Request>> {
+        self.sender
+            .as_ref()
+            .ok_or_else(|| YoloError::ShutDown("批处理器已关闭".to_string()))
+    }
+
+    fn close(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for DynamicBatcher {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn run_batches<B: InferenceBackend>(
+    mut backend: B,
+    receiver: Receiver<Request>,
+    max_batch_size: usize,
+    max_wait: Duration,
+    metrics: Arc<Mutex<BatchMetrics>>,
+) {
+    let mut batch = Vec::with_capacity(max_batch_size);
+    // 所有发送端关闭后，recv 会在队列清空后才返回错误
+    while let Ok(first) = receiver.recv() {
+        let deadline = Instant::now() + max_wait;
+        batch.push(first);
+
+        while batch.len() < max_batch_size {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(timeout) {
+                Ok(request) => batch.push(request),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let started = Instant::now();
+        // 图片按值移出请求，只保留等待时间和回复通道
+        let (frames, (queue_wait_ms, replies)): (Vec<Frame>, (Vec<f64>, Vec<_>)) = batch
+            .drain(..)
+            .map(|r| {
+                let wait_ms = started.duration_since(r.submitted).as_secs_f64() * 1000.0;
+                (r.frame, (wait_ms, r.reply))
+            })
+            .unzip();
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            backend.inference_batch_queued(&frames, &queue_wait_ms)
+        }))
+        .unwrap_or_else(|_| Err(YoloError::Inference("批量推理 panic".to_string())));
+
+        metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(replies.len());
+
+        match outcome {
+            Ok(results) if results.len() == replies.len() => {
+                for (reply, result) in replies.into_iter().zip(results) {
+                    let _ = reply.send(Ok(result));
+                }
+            }
+            Ok(results) => {
+                let error = YoloError::Inference(format!(
+                    "批量推理返回 {} 个结果，期望 {} 个",
+                    results.len(),
+                    replies.len()
+                ));
+                for reply in replies {
+                    let _ = reply.send(Err(error.clone()));
+                }
+            }
+            Err(error) => {
+                for reply in replies {
+                    let _ = reply.send(Err(error.clone()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt::Write as _;
+use std::os::raw::c_void;
+use std::time::{Duration, Instant};
+
+use crate::error::{YoloError, YoloResult};
+use crate::types::{Frame, InferenceResult, TensorRtBuffers};
+use crate::yolo::Yolo;
+
+/// 基准测试覆盖的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkMode {
+    /// 完整流水线：读取图片、预处理、推理、后处理
+    EndToEnd,
+    /// 从内存中已解码的图片开始，不包含图片读取
+    FromMemory,
+    /// 只执行 TensorRT 引擎，每次迭代都同步 CUDA 流
+    EngineOnly,
+}
+
+impl BenchmarkMode {
+    /// 模式名称，用于报告输出
+    pub fn name(&self) -> &'static str {
+        match self {
+            BenchmarkMode::EndToEnd => "end_to_end",
+            BenchmarkMode::FromMemory => "from_memory",
+            BenchmarkMode::EngineOnly => "engine_only",
+        }
+    }
+}
+
+/// 延迟统计（毫秒）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyStats {
+    /// 样本数量
+    pub samples: usize,
+    /// 最小值
+    pub min_ms: f64,
+    /// 平均值
+    pub mean_ms: f64,
+    /// 中位数
+    pub p50_ms: f64,
+    /// 95 分位
+    pub p95_ms: f64,
+    /// 99 分位
+    pub p99_ms: f64,
+    /// 最大值
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    /// 从样本计算统计量，分位数采用最近秩法
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+
+        Self {
+            samples: sorted.len(),
+            min_ms: sorted[0],
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(
+            out,
+            "{{\"samples\":{},\"min_ms\":{:.4},\"mean_ms\":{:.4},\"p50_ms\":{:.4},\
+             \"p95_ms\":{:.4},\"p99_ms\":{:.4},\"max_ms\":{:.4}}}",
+            self.samples,
+            self.min_ms,
+            self.mean_ms,
+            self.p50_ms,
+            self.p95_ms,
+            self.p99_ms,
+            self.max_ms
+        );
+    }
+}
+
+/// 基准测试报告
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    /// 引擎文件路径
+    pub engine_path: String,
+    /// 测试模式
+    pub mode: BenchmarkMode,
+    /// 预热次数
+    pub warmup: usize,
+    /// 计时迭代次数
+    pub iterations: usize,
+    /// 参与测试的图片数量
+    pub images: usize,
+    /// 计时阶段的总耗时
+    pub elapsed: Duration,
+    /// 每次迭代的端到端延迟（主机侧墙钟时间，包含同步）
+    pub latency: LatencyStats,
+    /// 各阶段延迟，按流水线顺序排列
+    pub stages: Vec<(String, LatencyStats)>,
+}
+
+impl BenchmarkReport {
+    /// 吞吐量（每秒处理的图片数）
+    pub fn throughput_fps(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.iterations as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// 按名称查找阶段统计
+    pub fn stage(&self, name: &str) -> Option<&LatencyStats> {
+        self.stages
+            .iter()
+            .find(|(stage, _)| stage == name)
+            .map(|(_, stats)| stats)
+    }
+
+    /// 输出为 JSON，便于跨版本、跨引擎比较
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"engine_path\":");
+        write_json_string(&mut out, &self.engine_path);
+        let _ = write!(
+            out,
+            ",\"mode\":\"{}\",\"warmup\":{},\"iterations\":{},\"images\":{},\
+             \"elapsed_ms\":{:.4},\"throughput_fps\":{:.4},\"latency\":",
+            self.mode.name(),
+            self.warmup,
+            self.iterations,
+            self.images,
+            self.elapsed.as_secs_f64() * 1000.0,
+            self.throughput_fps()
+        );
+        self.latency.write_json(&mut out);
+        out.push_str(",\"stages\":{");
+        for (i, (name, stats)) in self.stages.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_string(&mut out, name);
+            out.push(':');
+            stats.write_json(&mut out);
+        }
+        out.push_str("}}");
+        out
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// 从推理结果中读取某个阶段的耗时
+type StageTime = fn(&InferenceResult) -> f64;
+
+/// 原生推理结果中各阶段的耗时，按流水线顺序
+const RESULT_STAGES: [(&str, StageTime); 9] = [
+    ("image_read", |r| r.image_read_time_ms),
+    ("preprocess", |r| r.preprocess_time_ms),
+    ("tensorrt", |r| r.tensorrt_time_ms),
+    ("postprocess", |r| r.postprocess_time_ms),
+    ("result_copy", |r| r.result_copy_time_ms),
+    ("gpu_preprocess", |r| r.gpu_preprocess_time_ms),
+    ("gpu_tensorrt", |r| r.gpu_tensorrt_time_ms),
+    ("gpu_copy", |r| r.gpu_copy_time_ms),
+    ("native_total", |r| r.total_time_ms),
+];
+
+/// 推理基准测试
+///
+/// 对真实图片运行完整流水线（或指定阶段），先预热再计时，
+/// 每次迭代都等待 GPU 完成后才记录时间。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{Benchmark, BenchmarkMode, Config, Yolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine"))?;
+///
+///     let report = Benchmark::new(&yolo)
+///         .with_images(["images/test1.jpg", "images/test2.jpg"])
+///         .with_mode(BenchmarkMode::FromMemory)
+///         .with_warmup(20)
+///         .with_iterations(500)
+///         .run()?;
+///
+///     println!("p99: {:.2}ms, FPS: {:.1}", report.latency.p99_ms, report.throughput_fps());
+///     println!("{}", report.to_json());
+///     Ok(())
+/// }
+/// ```
+pub struct Benchmark<'a> {
+    yolo: &'a Yolo,
+    images: Vec<String>,
+    mode: BenchmarkMode,
+    warmup: usize,
+    iterations: usize,
+}
+
+impl<'a> Benchmark<'a> {
+    /// 创建基准测试，默认完整流水线、预热 10 次、计时 100 次
+    pub fn new(yolo: &'a Yolo) -> Self {
+        Self {
+            yolo,
+            images: Vec::new(),
+            mode: BenchmarkMode::EndToEnd,
+            warmup: 10,
+            iterations: 100,
+        }
+    }
+
+    /// 设置测试图片，迭代时依次循环使用
+    pub fn with_images<I, S>(mut self, images: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.images = images.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 设置测试模式
+    pub fn with_mode(mut self, mode: BenchmarkMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置预热次数
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// 设置计时迭代次数
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// 运行基准测试
+    pub fn run(&self) -> YoloResult<BenchmarkReport> {
+        if self.iterations == 0 {
+            return Err(YoloError::InvalidParameter(
+                "迭代次数必须大于 0".to_string(),
+            ));
+        }
+        if self.mode != BenchmarkMode::EngineOnly && self.images.is_empty() {
+            return Err(YoloError::InvalidParameter(
+                "该模式需要至少一张测试图片".to_string(),
+            ));
+        }
+
+        let frames = match self.mode {
+            BenchmarkMode::FromMemory => self
+                .images
+                .iter()
+                .map(|path| Frame::open(path))
+                .collect::<YoloResult<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+        let engine = match self.mode {
+            BenchmarkMode::EngineOnly => Some((
+                self.yolo.get_tensorrt_buffers()?,
+                self.yolo.get_cuda_stream()?,
+            )),
+            _ => None,
+        };
+
+        for i in 0..self.warmup {
+            self.run_once(i, &frames, engine.as_ref())?;
+        }
+
+        let mut latencies = Vec::with_capacity(self.iterations);
+        let mut results = Vec::with_capacity(self.iterations);
+        let start = Instant::now();
+        for i in 0..self.iterations {
+            let iteration_start = Instant::now();
+            let result = self.run_once(i, &frames, engine.as_ref())?;
+            latencies.push(iteration_start.elapsed().as_secs_f64() * 1000.0);
+            results.extend(result);
+        }
+        let elapsed = start.elapsed();
+
+        let stages = if self.mode == BenchmarkMode::EngineOnly {
+            vec![(
+                "tensorrt".to_string(),
+                LatencyStats::from_samples(&latencies),
+            )]
+        } else {
+            RESULT_STAGES
+                .iter()
+                .filter(|(name, _)| self.mode == BenchmarkMode::EndToEnd || *name != "image_read")
+                .map(|(name, field)| {
+                    let samples: Vec<f64> = results.iter().map(field).collect();
+                    (name.to_string(), LatencyStats::from_samples(&samples))
+                })
+                .collect()
+        };
+
+        Ok(BenchmarkReport {
+            engine_path: self.yolo.config().engine_path.clone(),
+            mode: self.mode,
+            warmup: self.warmup,
+            iterations: self.iterations,
+            images: self.images.len(),
+            elapsed,
+            latency: LatencyStats::from_samples(&latencies),
+            stages,
+        })
+    }
+
+    fn run_once(
+        &self,
+        iteration: usize,
+        frames: &[Frame],
+        engine: Option<&(TensorRtBuffers, *mut c_void)>,
+    ) -> YoloResult<Option<InferenceResult>> {
+        match self.mode {
+            BenchmarkMode::EndToEnd => {
+                let path = &self.images[iteration % self.images.len()];
+                self.yolo.inference(path).map(Some)
+            }
+            BenchmarkMode::FromMemory => {
+                let frame = &frames[iteration % frames.len()];
+                self.yolo.inference_frame(frame).map(Some)
+            }
+            BenchmarkMode::EngineOnly => {
+                let (buffers, stream) = engine.expect("纯引擎模式需要缓冲区");
+                self.yolo.tensorrt_inference_only(
+                    buffers.input_buffer,
+                    buffers.output_buffer,
+                    buffers.output_seg_buffer,
+                    *stream,
+                )?;
+                self.yolo.synchronize()?;
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! 按类别的过滤规则
+//!
+//! 在 Rust 后处理中、NMS 之前按类别过滤候选框：允许/拒绝列表、类别重映射与合并，
+//! 以及按类别的置信度阈值。类别可以用 ID 或名称引用，名称按 [`ClassFilter::with_names`]
+//! 给出的列表解析为 ID。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::decode::{Proto, RawCandidate};
+use crate::error::{YoloError, YoloResult};
+use crate::nms::{limit, nms, nms_with_proto, NmsConfig};
+
+/// 类别的 ID 或名称
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClassRef {
+    /// 类别 ID
+    Id(i32),
+    /// 类别名称
+    Name(String),
+}
+
+impl From<i32> for ClassRef {
+    fn from(id: i32) -> Self {
+        ClassRef::Id(id)
+    }
+}
+
+impl From<&str> for ClassRef {
+    fn from(name: &str) -> Self {
+        ClassRef::Name(name.to_string())
+    }
+}
+
+impl From<String> for ClassRef {
+    fn from(name: String) -> Self {
+        ClassRef::Name(name)
+    }
+}
+
+/// 类别过滤规则
+///
+/// 处理顺序：
+///
+/// 1. 按引擎输出的原始类别检查允许/拒绝列表，拒绝优先；
+/// 2. 按重映射表改写类别，多个类别映射到同一目标即合并，合并后的类别之间互相抑制；
+/// 3. 按重映射后的类别检查置信度阈值，未设置的类别使用 [`NmsConfig::confidence_threshold`]；
+/// 4. 执行 NMS，Soft-NMS 衰减后的置信度同样按各类别的阈值过滤；
+/// 5. 最后应用 [`NmsConfig::max_per_class`] 和 [`NmsConfig::max_detections`]。
+///
+/// 名称在 [`resolve`](Self::resolve) 时解析为 ID，[`Yolo`](crate::Yolo) 创建时解析一次并保存结果。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::{ClassFilter, Config};
+///
+/// let classes = ClassFilter::new()
+///     .with_names(["crack", "crack_long", "crack_transverse", "stain", "scratch"])
+///     .with_denied("stain")
+///     .with_remap("crack_long", "crack")
+///     .with_remap("crack_transverse", "crack")
+///     .with_threshold("crack", 0.2)
+///     .with_threshold(4, 0.7);
+/// let config = Config::new("models/yolo11s-seg.engine").with_classes(classes);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassFilter {
+    /// 类别名称，下标即类别 ID
+    pub names: Vec<String>,
+    /// 只保留这些类别，为空时保留全部
+    pub allowed: Vec<ClassRef>,
+    /// 丢弃这些类别
+    pub denied: Vec<ClassRef>,
+    /// 类别重映射 `(原类别, 目标类别)`
+    pub remap: Vec<(ClassRef, ClassRef)>,
+    /// 按类别的置信度阈值，作用于重映射后的类别
+    pub thresholds: Vec<(ClassRef, f32)>,
+}
+
+impl ClassFilter {
+    /// 创建不做任何过滤的规则
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置类别名称，下标即类别 ID
+    pub fn with_names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 只保留该类别（可多次调用）
+    pub fn with_allowed(mut self, class: impl Into<ClassRef>) -> Self {
+        self.allowed.push(class.into());
+        self
+    }
+
+    /// 丢弃该类别（可多次调用）
+    pub fn with_denied(mut self, class: impl Into<ClassRef>) -> Self {
+        self.denied.push(class.into());
+        self
+    }
+
+    /// 把类别 `from` 改写为 `to`
+    pub fn with_remap(mut self, from: impl Into<ClassRef>, to: impl Into<ClassRef>) -> Self {
+        self.remap.push((from.into(), to.into()));
+        self
+    }
+
+    /// 设置类别的置信度阈值
+    pub fn with_threshold(mut self, class: impl Into<ClassRef>, threshold: f32) -> Self {
+        self.thresholds.push((class.into(), threshold));
+        self
+    }
+
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty()
+            && self.denied.is_empty()
+            && self.remap.is_empty()
+            && self.thresholds.is_empty()
+    }
+
+    /// 检查所有类别名称都能解析
+    pub fn validate(&self) -> YoloResult<()> {
+        self.resolve().map(|_| ())
+    }
+
+    /// 按规则过滤和重映射候选框，再执行 NMS
+    ///
+    /// 每次调用都会重新解析类别名称，反复使用同一规则时先调用 [`resolve`](Self::resolve)。
+    pub fn apply(
+        &self,
+        candidates: Vec<RawCandidate>,
+        proto: Option<&Proto<'_>>,
+        config: &NmsConfig,
+    ) -> YoloResult<Vec<RawCandidate>> {
+        Ok(self.resolve()?.apply(candidates, proto, config))
+    }
+
+    /// 把类别名称解析为 ID，名称未知时返回错误
+    pub fn resolve(&self) -> YoloResult<ResolvedClasses> {
+        let id = |class: &ClassRef| match class {
+            ClassRef::Id(id) => Ok(*id),
+            ClassRef::Name(name) => self
+                .names
+                .iter()
+                .position(|n| n == name)
+                .map(|id| id as i32)
+                .ok_or_else(|| YoloError::InvalidParameter(format!("未知的类别名称: {}", name))),
+        };
+        Ok(ResolvedClasses {
+            allowed: self.allowed.iter().map(id).collect::<YoloResult<_>>()?,
+            denied: self.denied.iter().map(id).collect::<YoloResult<_>>()?,
+            remap: self
+                .remap
+                .iter()
+                .map(|(from, to)| Ok((id(from)?, id(to)?)))
+                .collect::<YoloResult<_>>()?,
+            thresholds: self
+                .thresholds
+                .iter()
+                .map(|(class, threshold)| Ok((id(class)?, *threshold)))
+                .collect::<YoloResult<_>>()?,
+        })
+    }
+}
+
+/// 名称已解析为 ID 的类别过滤规则，由 [`ClassFilter::resolve`] 创建
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedClasses {
+    allowed: HashSet<i32>,
+    denied: HashSet<i32>,
+    remap: HashMap<i32, i32>,
+    thresholds: HashMap<i32, f32>,
+}
+
+impl ResolvedClasses {
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty()
+            && self.denied.is_empty()
+            && self.remap.is_empty()
+            && self.thresholds.is_empty()
+    }
+
+    /// 按规则过滤和重映射候选框，再执行 NMS
+    ///
+    /// 提供 `proto` 时使用 [`nms_with_proto`]，否则使用 [`nms`]。
+    pub fn apply(
+        &self,
+        candidates: Vec<RawCandidate>,
+        proto: Option<&Proto<'_>>,
+        config: &NmsConfig,
+    ) -> Vec<RawCandidate> {
+        let suppress = |candidates, config: &NmsConfig| match proto {
+            Some(proto) => nms_with_proto(candidates, proto, config),
+            None => nms(candidates, config),
+        };
+        if self.is_empty() {
+            return suppress(candidates, config);
+        }
+
+        let default = config.confidence_threshold;
+        let candidates = candidates
+            .into_iter()
+            .filter_map(|mut candidate| {
+                if !self.allows(candidate.class_id) {
+                    return None;
+                }
+                if let Some(&to) = self.remap.get(&candidate.class_id) {
+                    candidate.class_id = to;
+                }
+                (candidate.confidence > self.threshold(candidate.class_id, default))
+                    .then_some(candidate)
+            })
+            .collect();
+
+        // 候选框已按各自的阈值过滤，NMS 使用最低的阈值以免再次丢弃低阈值类别；
+        // 数量上限要等按类别的阈值过滤之后再应用，否则会被随后丢弃的候选框占用名额
+        let lowest = self.thresholds.values().fold(default, |a, &b| a.min(b));
+        let mut unlimited = config.clone().with_confidence_threshold(lowest);
+        unlimited.max_per_class = None;
+        unlimited.max_detections = None;
+        let mut kept = suppress(candidates, &unlimited);
+        kept.retain(|c| c.confidence > self.threshold(c.class_id, default));
+        limit(&mut kept, config);
+        kept
+    }
+
+    fn allows(&self, class_id: i32) -> bool {
+        !self.denied.contains(&class_id)
+            && (self.allowed.is_empty() || self.allowed.contains(&class_id))
+    }
+
+    fn threshold(&self, class_id: i32, default: f32) -> f32 {
+        self.thresholds.get(&class_id).copied().unwrap_or(default)
+    }
+}
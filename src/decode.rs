@@ -0,0 +1,497 @@
+//! 引擎原始检测输出的解析
+//!
+//! `output` 张量的布局与 C++ 核心的 `yolo/types.h` 一致：第一个 float 是候选框数量，
+//! 之后是最多 [`MAX_NUM_OUTPUT_BBOX`] 个紧密排列的 `Detection` 结构体，
+//! 每个 [`DETECTION_STRIDE`] 个 float。批量推理时每张图片占 [`OUTPUT_SIZE`] 个 float。
+//!
+//! 这些常量对应默认的 640×640 引擎，实际引擎的容量和尺寸见 [`ModelInfo`](crate::ModelInfo)。
+
+use rayon::prelude::*;
+
+use crate::error::{YoloError, YoloResult};
+use crate::types::{Keypoint, ModelInfo, RotatedBox, Task};
+
+/// 分割掩码系数的数量
+pub const MASK_COEFFICIENTS: usize = 32;
+
+/// 关键点数量
+pub const NUM_KEYPOINTS: usize = 17;
+
+/// 单个 `Detection` 结构体占用的 float 数：bbox(4) + conf + class_id + 掩码系数 + 关键点 + angle
+pub const DETECTION_STRIDE: usize = 4 + 1 + 1 + MASK_COEFFICIENTS + NUM_KEYPOINTS * 3 + 1;
+
+/// 输出缓冲区最多容纳的候选框数量，与 `kMaxNumOutputBbox` 一致
+pub const MAX_NUM_OUTPUT_BBOX: usize = 1000;
+
+/// 单张图片的输出缓冲区长度（float 数）
+pub const OUTPUT_SIZE: usize = MAX_NUM_OUTPUT_BBOX * DETECTION_STRIDE + 1;
+
+/// 分割原型相对网络输入的默认下采样倍数
+///
+/// 只在不知道引擎尺寸时使用，实际倍数见 [`ModelInfo::proto_stride`]。
+pub const PROTO_STRIDE: usize = 4;
+
+const CONF_OFFSET: usize = 4;
+const CLASS_OFFSET: usize = 5;
+const MASK_OFFSET: usize = 6;
+const KEYPOINT_OFFSET: usize = MASK_OFFSET + MASK_COEFFICIENTS;
+const ANGLE_OFFSET: usize = KEYPOINT_OFFSET + NUM_KEYPOINTS * 3;
+
+/// NMS 之前的候选框
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCandidate {
+    /// 边界框，网络输入坐标系下的 `[x1, y1, x2, y2]`；旋转框模型为 `[cx, cy, w, h]`
+    pub bbox: [f32; 4],
+    /// 置信度
+    pub confidence: f32,
+    /// 类别 ID
+    pub class_id: i32,
+    /// 分割掩码系数
+    pub mask_coefficients: [f32; MASK_COEFFICIENTS],
+    /// 关键点 `[x, y, 置信度]`，无效的关键点为 `-1`
+    pub keypoints: [[f32; 3]; NUM_KEYPOINTS],
+    /// 旋转框角度（弧度）
+    pub angle: f32,
+}
+
+impl RawCandidate {
+    /// 创建只有边界框、置信度和类别的候选框，掩码系数为 0，关键点无效
+    pub fn new(bbox: [f32; 4], confidence: f32, class_id: i32) -> Self {
+        Self {
+            bbox,
+            confidence,
+            class_id,
+            mask_coefficients: [0.0; MASK_COEFFICIENTS],
+            keypoints: [[-1.0; 3]; NUM_KEYPOINTS],
+            angle: 0.0,
+        }
+    }
+
+    /// 旋转框模型的旋转框，`bbox` 按 `[cx, cy, w, h]` 解释
+    pub fn rotated_box(&self) -> RotatedBox {
+        let [cx, cy, width, height] = self.bbox;
+        RotatedBox {
+            cx,
+            cy,
+            width,
+            height,
+            angle: self.angle,
+        }
+    }
+
+    /// 姿态模型的关键点
+    pub fn keypoints(&self) -> Vec<Keypoint> {
+        self.keypoints
+            .iter()
+            .copied()
+            .map(Keypoint::from_raw)
+            .collect()
+    }
+
+    /// 从一个 `Detection` 结构体解析，拒绝 NaN、无穷大和越界的置信度或类别
+    ///
+    /// 插件只写入当前任务用到的字段（分割的掩码系数、姿态的关键点、旋转框的角度），
+    /// 其余字段是未初始化的显存。指定 `task` 时只校验并保留该任务用到的字段，
+    /// 其余字段取 [`new`](Self::new) 的默认值；不指定时原样保留，只校验边界框、置信度和类别。
+    fn parse(index: usize, values: &[f32], task: Option<Task>) -> YoloResult<Self> {
+        let (masks, keypoints, angle) = match task {
+            None => (false, false, false),
+            Some(task) => (task == Task::Segment, task == Task::Pose, task == Task::Obb),
+        };
+        let checked = [
+            Some(0..MASK_OFFSET),
+            masks.then_some(MASK_OFFSET..KEYPOINT_OFFSET),
+            keypoints.then_some(KEYPOINT_OFFSET..ANGLE_OFFSET),
+            angle.then_some(ANGLE_OFFSET..DETECTION_STRIDE),
+        ];
+        if let Some(offset) = checked
+            .into_iter()
+            .flatten()
+            .flatten()
+            .find(|&offset| !values[offset].is_finite())
+        {
+            return Err(YoloError::Decode(format!(
+                "候选框 {} 的第 {} 个值不是有限数: {}",
+                index, offset, values[offset]
+            )));
+        }
+
+        let confidence = values[CONF_OFFSET];
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(YoloError::Decode(format!(
+                "候选框 {} 的置信度越界: {}",
+                index, confidence
+            )));
+        }
+
+        let class_id = values[CLASS_OFFSET];
+        if class_id < 0.0 || class_id.fract() != 0.0 || class_id > i32::MAX as f32 {
+            return Err(YoloError::Decode(format!(
+                "候选框 {} 的类别无效: {}",
+                index, class_id
+            )));
+        }
+
+        let mut candidate = Self::new(
+            [values[0], values[1], values[2], values[3]],
+            confidence,
+            class_id as i32,
+        );
+        if task.is_none() || masks {
+            candidate
+                .mask_coefficients
+                .copy_from_slice(&values[MASK_OFFSET..KEYPOINT_OFFSET]);
+        }
+        if task.is_none() || keypoints {
+            for (keypoint, chunk) in candidate
+                .keypoints
+                .iter_mut()
+                .zip(values[KEYPOINT_OFFSET..ANGLE_OFFSET].chunks_exact(3))
+            {
+                keypoint.copy_from_slice(chunk);
+            }
+        }
+        if task.is_none() || angle {
+            candidate.angle = values[ANGLE_OFFSET];
+        }
+        Ok(candidate)
+    }
+}
+
+/// 单张图片的原始检测输出
+///
+/// 只借用输出缓冲区，候选框在访问时才解析和校验。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::decode::{RawOutput, OUTPUT_SIZE};
+///
+/// // 例如从 `get_tensorrt_buffers` 的输出缓冲区拷贝回来，或回放保存的张量
+/// let buffer = vec![0.0f32; OUTPUT_SIZE];
+/// let output = RawOutput::parse(&buffer)?;
+/// for candidate in output.candidates()? {
+///     println!("{:?} {:.2}", candidate.bbox, candidate.confidence);
+/// }
+/// # Ok::<(), yolo11s_tensorrt_rs::YoloError>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RawOutput<'a> {
+    data: &'a [f32],
+    count: usize,
+    reported: usize,
+    task: Option<Task>,
+}
+
+impl<'a> RawOutput<'a> {
+    /// 解析单张图片的输出缓冲区
+    ///
+    /// 插件在候选框超过上限时仍会累加计数，因此计数大于 [`MAX_NUM_OUTPUT_BBOX`]
+    /// 时按上限截断（见 [`overflowed`](Self::overflowed)）；计数不是非负整数，
+    /// 或缓冲区放不下计数对应的候选框时返回错误。
+    pub fn parse(data: &'a [f32]) -> YoloResult<Self> {
+        Self::parse_with_capacity(data, MAX_NUM_OUTPUT_BBOX)
+    }
+
+    /// 按引擎的输出容量解析单张图片的输出缓冲区
+    ///
+    /// 与 [`parse`](Self::parse) 相同，只是计数按 `max_detections`
+    /// （[`ModelInfo::max_detections`](crate::ModelInfo::max_detections)）截断。
+    pub fn parse_with_capacity(data: &'a [f32], max_detections: usize) -> YoloResult<Self> {
+        let Some(&raw_count) = data.first() else {
+            return Err(YoloError::Decode("输出缓冲区为空".to_string()));
+        };
+        if !raw_count.is_finite() || raw_count < 0.0 || raw_count.fract() != 0.0 {
+            return Err(YoloError::Decode(format!("候选框数量无效: {}", raw_count)));
+        }
+
+        // 超过 f32 精确表示范围的计数同样会被截断
+        let reported = raw_count.min(u32::MAX as f32) as usize;
+        let count = reported.min(max_detections);
+        let needed = 1 + count * DETECTION_STRIDE;
+        if data.len() < needed {
+            return Err(YoloError::Decode(format!(
+                "输出缓冲区长度 {} 放不下 {} 个候选框（需要 {}）",
+                data.len(),
+                count,
+                needed
+            )));
+        }
+
+        Ok(Self {
+            data,
+            count,
+            reported,
+            task: None,
+        })
+    }
+
+    /// 按任务类型解析候选框
+    ///
+    /// 只校验和保留该任务用到的字段，插件没有写入的字段（例如检测模型的掩码系数、
+    /// 关键点和角度）即使是 NaN 也不会让候选框被拒绝。
+    pub fn with_task(mut self, task: Task) -> Self {
+        self.task = Some(task);
+        self
+    }
+
+    /// 解析批量推理的输出缓冲区
+    ///
+    /// 每张图片占 [`ModelInfo::output_size`] 个 float，计数按
+    /// [`ModelInfo::max_detections`] 截断。
+    pub fn parse_batch(
+        data: &'a [f32],
+        model: &ModelInfo,
+        batch_size: usize,
+    ) -> YoloResult<Vec<Self>> {
+        let output_size = model.output_size();
+        if data.len() < batch_size * output_size {
+            return Err(YoloError::Decode(format!(
+                "输出缓冲区长度 {} 不足 {} 张图片（需要 {}）",
+                data.len(),
+                batch_size,
+                batch_size * output_size
+            )));
+        }
+        data.chunks_exact(output_size)
+            .take(batch_size)
+            .map(|output| Self::parse_with_capacity(output, model.max_detections))
+            .collect()
+    }
+
+    /// 有效的候选框数量
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// 是否没有候选框
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// 插件报告的候选框数量，可能大于缓冲区容量
+    pub fn reported_count(&self) -> usize {
+        self.reported
+    }
+
+    /// 候选框是否超过缓冲区容量而被丢弃
+    pub fn overflowed(&self) -> bool {
+        self.reported > self.count
+    }
+
+    /// 解析第 `index` 个候选框
+    pub fn get(&self, index: usize) -> Option<YoloResult<RawCandidate>> {
+        if index >= self.count {
+            return None;
+        }
+        let start = 1 + index * DETECTION_STRIDE;
+        Some(RawCandidate::parse(
+            index,
+            &self.data[start..start + DETECTION_STRIDE],
+            self.task,
+        ))
+    }
+
+    /// 逐个解析候选框
+    pub fn iter(&self) -> impl Iterator<Item = YoloResult<RawCandidate>> + 'a {
+        let output = *self;
+        (0..output.count).filter_map(move |index| output.get(index))
+    }
+
+    /// 解析全部候选框，遇到无效数据时返回错误
+    pub fn candidates(&self) -> YoloResult<Vec<RawCandidate>> {
+        self.iter().collect()
+    }
+}
+
+/// 单张图片的分割原型输出
+///
+/// `proto` 张量的布局为 `[MASK_COEFFICIENTS, height, width]`，
+/// 分辨率为网络输入的 1/`stride`。候选框的掩码是掩码系数与各通道的加权和再取 sigmoid。
+#[derive(Debug, Clone, Copy)]
+pub struct Proto<'a> {
+    data: &'a [f32],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> Proto<'a> {
+    /// 用原型数据和尺寸创建，长度必须等于 `MASK_COEFFICIENTS * width * height`
+    ///
+    /// 下采样倍数默认为 [`PROTO_STRIDE`]，实际引擎的倍数用 [`Proto::with_stride`] 设置。
+    pub fn new(data: &'a [f32], width: usize, height: usize) -> YoloResult<Self> {
+        if width == 0 || height == 0 || data.len() != MASK_COEFFICIENTS * width * height {
+            return Err(YoloError::Decode(format!(
+                "分割原型长度 {} 与尺寸 {}x{}x{} 不匹配",
+                data.len(),
+                MASK_COEFFICIENTS,
+                height,
+                width
+            )));
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+            stride: PROTO_STRIDE,
+        })
+    }
+
+    /// 设置原型相对网络输入的下采样倍数，见 [`ModelInfo::proto_stride`]，为 0 时按 1 处理
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    /// 原型宽度
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 原型高度
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 原型相对网络输入的下采样倍数
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// 网络输入坐标系下的边界框在原型上覆盖的像素范围 `(left, top, right, bottom)`，右、下边界不含
+    pub(crate) fn crop(&self, bbox: &[f32; 4]) -> (usize, usize, usize, usize) {
+        let stride = self.stride as f32;
+        let [x1, y1, x2, y2] = *bbox;
+        let left = (x1.max(0.0) / stride) as usize;
+        let top = (y1.max(0.0) / stride) as usize;
+        let right = ((x2.max(0.0) / stride) as usize).min(self.width);
+        let bottom = ((y2.max(0.0) / stride) as usize).min(self.height);
+        (left.min(right), top.min(bottom), right, bottom)
+    }
+
+    /// 范围 `(left, top, right, bottom)` 内各原型像素的掩码 logit，按行排列
+    pub(crate) fn crop_logits(
+        &self,
+        coefficients: &[f32; MASK_COEFFICIENTS],
+        (left, top, right, bottom): (usize, usize, usize, usize),
+    ) -> Vec<f32> {
+        let width = right - left;
+        let mut logits = vec![0.0f32; width * (bottom - top)];
+        if logits.is_empty() {
+            return logits;
+        }
+        // 逐通道累加整行，内层循环是连续内存上的乘加，便于编译器向量化
+        let plane = self.width * self.height;
+        for (channel, &coefficient) in coefficients.iter().enumerate() {
+            let channel = &self.data[channel * plane..(channel + 1) * plane];
+            for (y, row) in (top..bottom).zip(logits.chunks_exact_mut(width)) {
+                let src = &channel[y * self.width + left..y * self.width + right];
+                for (logit, &value) in row.iter_mut().zip(src) {
+                    *logit += coefficient * value;
+                }
+            }
+        }
+        logits
+    }
+
+    /// 解码单个候选框的掩码
+    ///
+    /// 在原型分辨率下计算边界框内的掩码概率，再双线性放大到网络输入尺寸，框外为 0。
+    pub fn decode_mask(&self, candidate: &RawCandidate) -> Vec<f32> {
+        let crop = self.crop(&candidate.bbox);
+        let (left, top, right, bottom) = crop;
+        let mut mask = vec![0.0f32; self.width * self.height];
+        if right > left {
+            let logits = self.crop_logits(&candidate.mask_coefficients, crop);
+            for (y, row) in (top..bottom).zip(logits.chunks_exact(right - left)) {
+                let dst = &mut mask[y * self.width + left..y * self.width + right];
+                for (probability, &logit) in dst.iter_mut().zip(row) {
+                    *probability = 1.0 / (1.0 + (-logit).exp());
+                }
+            }
+        }
+        resize_bilinear(
+            &mask,
+            (self.width, self.height),
+            (self.width * self.stride, self.height * self.stride),
+            crop,
+        )
+    }
+}
+
+/// 并行解码多个候选框的掩码，结果顺序与 `candidates` 一致
+///
+/// 只有一个候选框或 rayon 线程池只有一个线程时顺序解码，避免调度开销。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::decode::{decode_masks, Proto, MASK_COEFFICIENTS};
+/// use yolo11s_tensorrt_rs::RawCandidate;
+///
+/// let data = vec![0.0f32; MASK_COEFFICIENTS * 160 * 160];
+/// let proto = Proto::new(&data, 160, 160)?;
+/// let candidates = vec![RawCandidate::new([0.0, 0.0, 64.0, 64.0], 0.9, 0)];
+/// let masks = decode_masks(&candidates, &proto);
+/// assert_eq!(masks[0].len(), 640 * 640);
+/// # Ok::<(), yolo11s_tensorrt_rs::YoloError>(())
+/// ```
+pub fn decode_masks(candidates: &[RawCandidate], proto: &Proto<'_>) -> Vec<Vec<f32>> {
+    if candidates.len() < 2 || rayon::current_num_threads() < 2 {
+        return candidates
+            .iter()
+            .map(|candidate| proto.decode_mask(candidate))
+            .collect();
+    }
+    candidates
+        .par_iter()
+        .map(|candidate| proto.decode_mask(candidate))
+        .collect()
+}
+
+/// 双线性缩放，采样位置与 OpenCV 的 `INTER_LINEAR` 一致（像素中心对齐，边缘复制）
+///
+/// 源图只有范围 `(left, top, right, bottom)` 内非零，只计算会采样到该范围的目标像素。
+fn resize_bilinear(
+    src: &[f32],
+    (src_width, src_height): (usize, usize),
+    (dst_width, dst_height): (usize, usize),
+    (left, top, right, bottom): (usize, usize, usize, usize),
+) -> Vec<f32> {
+    let mut dst = vec![0.0f32; dst_width * dst_height];
+    if right <= left || bottom <= top {
+        return dst;
+    }
+
+    let sample = |dst: usize, src_len: usize, dst_len: usize| {
+        let position = ((dst as f32 + 0.5) * src_len as f32 / dst_len as f32 - 0.5).max(0.0);
+        let low = (position as usize).min(src_len - 1);
+        let high = (low + 1).min(src_len - 1);
+        (low, high, position - low as f32)
+    };
+    // 采样位置单调递增，只保留两个采样点至少有一个落在范围内的列
+    let columns: Vec<_> = (0..dst_width)
+        .map(|x| (x, sample(x, src_width, dst_width)))
+        .skip_while(|&(_, (_, x1, _))| x1 < left)
+        .take_while(|&(_, (x0, _, _))| x0 < right)
+        .collect();
+
+    for (y, row) in dst.chunks_exact_mut(dst_width).enumerate() {
+        let (y0, y1, fy) = sample(y, src_height, dst_height);
+        if y1 < top {
+            continue;
+        }
+        if y0 >= bottom {
+            break;
+        }
+        let upper_row = &src[y0 * src_width..(y0 + 1) * src_width];
+        let lower_row = &src[y1 * src_width..(y1 + 1) * src_width];
+        for &(x, (x0, x1, fx)) in &columns {
+            let upper = upper_row[x0] + (upper_row[x1] - upper_row[x0]) * fx;
+            let lower = lower_row[x0] + (lower_row[x1] - lower_row[x0]) * fx;
+            row[x] = upper + (lower - upper) * fy;
+        }
+    }
+    dst
+}
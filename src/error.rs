@@ -1,7 +1,113 @@
 use std::fmt;
 
+/// C++ 核心返回的错误码，与 C API 的 `YoloErrorCode` 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// 参数无效
+    InvalidArgument,
+    /// 文件不存在或无法打开
+    FileNotFound,
+    /// 图片解码失败
+    ImageDecode,
+    /// 引擎反序列化失败
+    EngineDeserialization,
+    /// CUDA 调用失败
+    Cuda,
+    /// TensorRT 调用失败
+    TensorRt,
+    /// 主机或显存不足
+    OutOfMemory,
+    /// 文件写入失败
+    Io,
+    /// 其他错误
+    Unknown,
+}
+
+impl ErrorCode {
+    /// 从 C API 的错误码转换，`YOLO_OK`（0）返回 `None`
+    pub fn from_raw(code: i32) -> Option<Self> {
+        match code {
+            0 => None,
+            1 => Some(ErrorCode::InvalidArgument),
+            2 => Some(ErrorCode::FileNotFound),
+            3 => Some(ErrorCode::ImageDecode),
+            4 => Some(ErrorCode::EngineDeserialization),
+            5 => Some(ErrorCode::Cuda),
+            6 => Some(ErrorCode::TensorRt),
+            7 => Some(ErrorCode::OutOfMemory),
+            8 => Some(ErrorCode::Io),
+            _ => Some(ErrorCode::Unknown),
+        }
+    }
+
+    /// 对应的 C API 错误码
+    pub fn as_raw(&self) -> i32 {
+        match self {
+            ErrorCode::InvalidArgument => 1,
+            ErrorCode::FileNotFound => 2,
+            ErrorCode::ImageDecode => 3,
+            ErrorCode::EngineDeserialization => 4,
+            ErrorCode::Cuda => 5,
+            ErrorCode::TensorRt => 6,
+            ErrorCode::OutOfMemory => 7,
+            ErrorCode::Io => 8,
+            ErrorCode::Unknown => 99,
+        }
+    }
+}
+
+/// 带错误码的错误详情，主要由 C++ 核心报告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeError {
+    /// 错误码
+    pub code: ErrorCode,
+    /// 原生错误信息
+    pub message: String,
+    /// 出错的文件路径（引擎、图片或标签文件）
+    pub path: Option<String>,
+    /// CUDA 错误时的 `cudaError_t`
+    pub cuda_error: Option<i32>,
+}
+
+impl NativeError {
+    /// 创建错误详情
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            path: None,
+            cuda_error: None,
+        }
+    }
+
+    /// 设置出错的文件路径
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// 设置 CUDA 错误码
+    pub fn with_cuda_error(mut self, cuda_error: i32) -> Self {
+        self.cuda_error = Some(cuda_error);
+        self
+    }
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(path) = self.path.as_deref().filter(|p| !self.message.contains(p)) {
+            write!(f, " ({})", path)?;
+        }
+        if let Some(cuda_error) = self.cuda_error {
+            write!(f, " [cudaError_t {}]", cuda_error)?;
+        }
+        Ok(())
+    }
+}
+
 /// YOLO 推理错误类型
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum YoloError {
     /// 初始化错误
     Initialization(String),
@@ -12,11 +118,31 @@ pub enum YoloError {
     /// 内存错误
     Memory(String),
     /// CUDA 错误
-    Cuda(String),
+    Cuda(NativeError),
     /// TensorRT 错误
-    TensorRt(String),
+    TensorRt(NativeError),
     /// 参数错误
     InvalidParameter(String),
+    /// 原生接口收到的参数无效
+    InvalidArgument(NativeError),
+    /// 文件不存在或无法打开
+    FileNotFound(NativeError),
+    /// 图片解码失败
+    ImageDecode(NativeError),
+    /// 引擎反序列化失败
+    EngineDeserialization(NativeError),
+    /// 主机或显存不足
+    OutOfMemory(NativeError),
+    /// 文件写入失败
+    Io(NativeError),
+    /// 任务队列已满
+    QueueFull(String),
+    /// 推理器已关闭
+    ShutDown(String),
+    /// 推理超时
+    Timeout(String),
+    /// 原始输出数据无效
+    Decode(String),
     /// 未知错误
     Unknown(String),
 }
@@ -28,14 +154,90 @@ impl fmt::Display for YoloError {
             YoloError::Inference(msg) => write!(f, "推理错误: {}", msg),
             YoloError::File(msg) => write!(f, "文件错误: {}", msg),
             YoloError::Memory(msg) => write!(f, "内存错误: {}", msg),
-            YoloError::Cuda(msg) => write!(f, "CUDA 错误: {}", msg),
-            YoloError::TensorRt(msg) => write!(f, "TensorRT 错误: {}", msg),
+            YoloError::Cuda(err) => write!(f, "CUDA 错误: {}", err),
+            YoloError::TensorRt(err) => write!(f, "TensorRT 错误: {}", err),
             YoloError::InvalidParameter(msg) => write!(f, "参数错误: {}", msg),
+            YoloError::InvalidArgument(err) => write!(f, "参数错误: {}", err),
+            YoloError::FileNotFound(err) => write!(f, "文件不存在: {}", err),
+            YoloError::ImageDecode(err) => write!(f, "图片解码错误: {}", err),
+            YoloError::EngineDeserialization(err) => write!(f, "引擎反序列化错误: {}", err),
+            YoloError::OutOfMemory(err) => write!(f, "内存不足: {}", err),
+            YoloError::Io(err) => write!(f, "文件写入错误: {}", err),
+            YoloError::QueueFull(msg) => write!(f, "队列已满: {}", msg),
+            YoloError::ShutDown(msg) => write!(f, "已关闭: {}", msg),
+            YoloError::Timeout(msg) => write!(f, "超时: {}", msg),
+            YoloError::Decode(msg) => write!(f, "输出解析错误: {}", msg),
             YoloError::Unknown(msg) => write!(f, "未知错误: {}", msg),
         }
     }
 }
 
+impl YoloError {
+    /// 错误类别名称，用于统计和监控标签
+    pub fn kind(&self) -> &'static str {
+        match self {
+            YoloError::Initialization(_) => "initialization",
+            YoloError::Inference(_) => "inference",
+            YoloError::File(_) => "file",
+            YoloError::Memory(_) => "memory",
+            YoloError::Cuda(_) => "cuda",
+            YoloError::TensorRt(_) => "tensorrt",
+            YoloError::InvalidParameter(_) => "invalid_parameter",
+            YoloError::InvalidArgument(_) => "invalid_argument",
+            YoloError::FileNotFound(_) => "file_not_found",
+            YoloError::ImageDecode(_) => "image_decode",
+            YoloError::EngineDeserialization(_) => "engine_deserialization",
+            YoloError::OutOfMemory(_) => "out_of_memory",
+            YoloError::Io(_) => "io",
+            YoloError::QueueFull(_) => "queue_full",
+            YoloError::ShutDown(_) => "shut_down",
+            YoloError::Timeout(_) => "timeout",
+            YoloError::Decode(_) => "decode",
+            YoloError::Unknown(_) => "unknown",
+        }
+    }
+
+    /// 由原生错误详情构造对应的错误，未分类的错误交给 `fallback`
+    pub fn from_native(error: NativeError, fallback: impl FnOnce(String) -> YoloError) -> Self {
+        match error.code {
+            ErrorCode::InvalidArgument => YoloError::InvalidArgument(error),
+            ErrorCode::FileNotFound => YoloError::FileNotFound(error),
+            ErrorCode::ImageDecode => YoloError::ImageDecode(error),
+            ErrorCode::EngineDeserialization => YoloError::EngineDeserialization(error),
+            ErrorCode::Cuda => YoloError::Cuda(error),
+            ErrorCode::TensorRt => YoloError::TensorRt(error),
+            ErrorCode::OutOfMemory => YoloError::OutOfMemory(error),
+            ErrorCode::Io => YoloError::Io(error),
+            ErrorCode::Unknown => fallback(error.message),
+        }
+    }
+
+    /// 原生错误详情，只有来自 C++ 核心且带错误码的错误才有
+    pub fn native(&self) -> Option<&NativeError> {
+        match self {
+            YoloError::Cuda(err)
+            | YoloError::TensorRt(err)
+            | YoloError::InvalidArgument(err)
+            | YoloError::FileNotFound(err)
+            | YoloError::ImageDecode(err)
+            | YoloError::EngineDeserialization(err)
+            | YoloError::OutOfMemory(err)
+            | YoloError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// 原生错误码
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.native().map(|err| err.code)
+    }
+
+    /// 出错的文件路径
+    pub fn path(&self) -> Option<&str> {
+        self.native().and_then(|err| err.path.as_deref())
+    }
+}
+
 impl std::error::Error for YoloError {}
 
 impl From<std::io::Error> for YoloError {
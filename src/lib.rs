@@ -38,19 +38,165 @@
 //! }
 //! ```
 //!
-//! # 批量推理
+//! # 基准测试
 //!
 //! ```rust
-//! use yolo11s_tensorrt_rs::Yolo;
+//! use yolo11s_tensorrt_rs::{Benchmark, Yolo};
 //!
 //! let yolo = Yolo::with_engine("models/yolo11s-seg.engine")?;
 //!
-//! // 执行批量推理测试
-//! let stats = yolo.batch_inference_test("images/test.jpg", 1000)?;
-//! println!("平均 FPS: {:.1}", stats.fps());
-//! println!("TensorRT 推理占比: {:.1}%", stats.tensorrt_percentage());
+//! // 完整流水线，预热后计时 1000 次
+//! let report = Benchmark::new(&yolo)
+//!     .with_images(["images/test.jpg"])
+//!     .with_iterations(1000)
+//!     .run()?;
+//! println!("平均 FPS: {:.1}", report.throughput_fps());
+//! println!("p99 延迟: {:.2}ms", report.latency.p99_ms);
+//! println!("{}", report.to_json());
+//! ```
+//!
+//! # 线程安全
+//!
+//! [`Yolo`] 实现了 `Send`，可以移动到工作线程中，但同一执行上下文不能被并发使用，
+//! 因此不实现 `Sync`。需要在多个线程间共享时使用 [`SharedYolo`]，它在内部串行化访问：
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use yolo11s_tensorrt_rs::{Config, SharedYolo};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let yolo = Arc::new(SharedYolo::new(Config::new("models/yolo11s-seg.engine"))?);
+//! let worker = Arc::clone(&yolo);
+//! std::thread::spawn(move || worker.inference("images/test.jpg")).join().unwrap()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # 工作池
+//!
+//! [`YoloPool`] 持有多个推理器实例，通过有界队列接收任务并在各自的工作线程上执行：
+//!
+//! ```no_run
+//! use yolo11s_tensorrt_rs::{Config, PoolConfig, Scheduling, YoloPool};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = YoloPool::new(
+//!     Config::new("models/yolo11s-seg.engine"),
+//!     PoolConfig::new(2).with_scheduling(Scheduling::RoundRobin),
+//! )?;
+//! let job = pool.submit("images/test.jpg")?;
+//! println!("检测到 {} 个目标", job.wait()?.detection_count());
+//! # Ok(())
+//! # }
 //! ```
 //!
+//! # 动态批处理
+//!
+//! 引擎构建时支持大于 1 的批次（静态批次或动态 batch 维度）时，
+//! [`DynamicBatcher`] 会把并发提交的单张图片合并成一次批量推理，
+//! 以少量等待时间（[`BatcherConfig::max_wait`]）换取更高的吞吐。
+//! 批次大小分布可通过 [`DynamicBatcher::metrics`] 查看。
+//!
+//! # 性能统计
+//!
+//! 每个推理器都带有一个 [`PerfStats`] 收集器，自动记录各阶段耗时直方图、
+//! 每个类别的检测数量、错误次数和滑动窗口吞吐量。工作池等场景下可以通过
+//! [`Config::with_stats`] 让多个推理器共享同一个收集器：
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use yolo11s_tensorrt_rs::{Config, PerfStats, PoolConfig, Stage, YoloPool};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let stats = Arc::new(PerfStats::new());
+//! let config = Config::new("models/yolo11s-seg.engine").with_stats(Arc::clone(&stats));
+//! let pool = YoloPool::new(config, PoolConfig::new(2))?;
+//! pool.submit("images/test.jpg")?.wait()?;
+//!
+//! let snapshot = stats.snapshot();
+//! println!("总耗时 p99: {:.2}ms", snapshot.stage(Stage::Total).quantile_ms(0.99));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # 监控
+//!
+//! [`render_metrics`] 把 [`PerfStats`] 输出为 Prometheus 文本格式，可以接入任意 HTTP 服务；
+//! 启用 `metrics-server` 特性后，`MetricsServer` 提供一个只依赖标准库的 `/metrics` 端点。
+//!
+//! # 链路追踪
+//!
+//! 启用 `tracing` 特性后，[`Yolo::inference`] 和 [`Yolo::inference_frame`] 在 `yolo.inference` span
+//! （带引擎路径、图片尺寸和检测数量）中执行，推理路径和耗时与未启用时完全相同。
+//! 各阶段另有 `preprocess`、`execute`、`copy_back`、`nms`、`mask_decode` 子 span，
+//! 批量推理在 `yolo.inference_batch` span 下为每张图片创建同样的子 span。
+//! C++ 核心在一次调用中完成预处理、引擎执行和结果拷贝（默认后处理时还包括 NMS 和掩码解码），
+//! 这些子 span 在调用返回后创建，耗时记录在 `duration_ms` 字段中，
+//! 同时作为 `image_load_ms`、`preprocess_ms`、`execute_ms`、`copy_back_ms`、`postprocess_ms`
+//! 字段记录在父 span 上；由 Rust 完成后处理时，`image_load`、`nms`、`mask_decode` 是实时的 span。
+//!
+//! # 日志
+//!
+//! TensorRT 运行时和 C++ 核心的日志（包括 C API 的错误信息）以 `tensorrt` 为目标转发到
+//! [`log`](https://docs.rs/log) crate，启用 `tracing` 特性时改为 `tracing` 事件，
+//! 不再直接写到标准输出。默认只转发警告及以上，[`Config::with_verbose`] 打开 TensorRT 的详细日志，
+//! 也可以用 [`set_native_log_level`] 随时调整：
+//!
+//! ```no_run
+//! use yolo11s_tensorrt_rs::{Config, Yolo};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // 先初始化任意 log 实现，例如 env_logger
+//! let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine").with_verbose(true))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # 错误处理
+//!
+//! C++ 核心的失败带有错误码，转换为对应的 [`YoloError`] 变体（例如 [`YoloError::FileNotFound`]、
+//! [`YoloError::EngineDeserialization`]、[`YoloError::Cuda`]），可以通过 [`YoloError::code`]、
+//! [`YoloError::path`] 和 [`YoloError::native`] 读取错误码、出错的文件和 `cudaError_t`。
+//!
+//! CUDA 调用失败不会终止进程，而是让推理器失效（[`Yolo::is_poisoned`]），之后的推理都返回
+//! [`YoloError::Cuda`]，直到 [`Yolo::recover`] 重新创建执行上下文和流。
+//! [`YoloPool`] 的工作线程会自动恢复失效的推理器。
+//!
+//! 无人值守的场景可以用 [`Supervisor`] 包装推理器：失败按 [`SupervisorConfig`] 退避重试，
+//! 连续失败或推理超过看门狗时间时重建推理器，并通过回调报告 [`HealthState`] 的变化。
+//!
+//! # 后处理
+//!
+//! 默认由 C++ 核心执行按类别的 NMS 和掩码解码。[`Config::with_nms`] 改为在 Rust 中后处理：
+//! C++ 核心只负责预处理、引擎推理和结果拷贝，原始输出由 [`decode`] 解析，再按 [`NmsConfig`]
+//! 执行跨类别或按类别的 NMS、Soft-NMS、DIoU-NMS，并限制每张图片和每个类别的检测数量：
+//!
+//! ```no_run
+//! use yolo11s_tensorrt_rs::{Config, NmsConfig, NmsMethod, Yolo};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let nms = NmsConfig::default()
+//!     .with_method(NmsMethod::Linear)
+//!     .with_max_detections(100);
+//! let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine").with_nms(nms))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Config::with_classes`] 在 NMS 之前按类别过滤候选框：按类别设置置信度阈值、
+//! 只保留或丢弃部分类别，以及把细分类别合并为一个类别，见 [`ClassFilter`]。
+//!
+//! 分割掩码可以用 [`MaskRefinement`] 做形态学开/闭运算、孔洞填充和连通域过滤，
+//! 见 [`InferenceResult::refine_masks`]。
+//!
+//! 分割模型可以用 [`Overlap::Mask`] 按实例掩码而不是边界框判断重叠，
+//! 边界框大量重叠的交叉裂纹等细长缺陷不会互相抑制。
+//!
+//! 姿态和旋转框引擎用 [`Config::with_task`] 指定 [`Task::Pose`] 或 [`Task::Obb`]，
+//! 检测结果分别带有 [`Keypoint`] 和 [`RotatedBox`]，旋转框按 [`Overlap::Rotated`] 执行 NMS。
+//!
+//! 输入尺寸、检测输出容量和分割原型尺寸在加载时从引擎读取（见 [`Yolo::model_info`]），
+//! 320、1280 等非默认尺寸的引擎和没有分割原型输出的检测引擎都无需重新编译 C++ 核心。
+//!
 //! # 性能优化
 //!
 //! ```rust
@@ -71,14 +217,53 @@
 //! )?;
 //! ```
 
+#[cfg(feature = "async")]
+pub mod async_yolo;
+pub mod backend;
+pub mod batcher;
+pub mod benchmark;
+pub mod classes;
+pub mod decode;
 pub mod error;
+pub mod logging;
+pub mod mask;
+pub mod metrics;
+pub mod nms;
+pub mod pipeline;
+pub mod pool;
+mod postprocess;
+pub mod shared;
+pub mod stats;
+pub mod supervisor;
 pub mod types;
 pub mod yolo;
 
 // 重新导出主要类型
-pub use error::{YoloError, YoloResult};
+#[cfg(feature = "async")]
+pub use async_yolo::AsyncYolo;
+pub use backend::InferenceBackend;
+pub use batcher::{BatchMetrics, BatcherConfig, DynamicBatcher};
+pub use benchmark::{Benchmark, BenchmarkMode, BenchmarkReport, LatencyStats};
+pub use classes::{ClassFilter, ClassRef, ResolvedClasses};
+pub use decode::{Proto, RawCandidate, RawOutput};
+pub use error::{ErrorCode, NativeError, YoloError, YoloResult};
+pub use logging::set_native_log_level;
+pub use mask::{BinaryMask, Kernel, MaskRefinement};
+pub use metrics::render_metrics;
+#[cfg(feature = "metrics-server")]
+pub use metrics::{MetricsServer, MetricsServerBuilder};
+pub use nms::{NmsConfig, NmsMethod, Overlap};
+pub use pipeline::{Pipeline, PipelineRun};
+pub use pool::{JobHandle, PoolConfig, Scheduling, YoloPool};
+pub use postprocess::postprocess_output;
+pub use shared::SharedYolo;
+pub use stats::{Histogram, PerfSnapshot, PerfStats, Stage};
+pub use supervisor::{
+    HealthState, HealthTransition, Supervisor, SupervisorConfig, SupervisorStats,
+};
 pub use types::{
-    Config, Detection, InferenceResult, PerformanceBreakdown, TensorRtBuffers, TensorRtInfo,
+    Config, Detection, Frame, InferenceResult, Keypoint, ModelInfo, PerformanceBreakdown,
+    RotatedBox, Task, TensorInfo, TensorRole, TensorRtBuffers, TensorRtInfo,
 };
 pub use yolo::Yolo;
 
@@ -96,6 +281,8 @@ pub mod yolo_c_api {
         pub mask_data: *mut f32,
         pub mask_width: c_int,
         pub mask_height: c_int,
+        pub keypoints: [f32; 51],
+        pub angle: f32,
     }
 
     #[repr(C)]
@@ -108,6 +295,10 @@ pub mod yolo_c_api {
         pub tensorrt_time_ms: f64,
         pub postprocess_time_ms: f64,
         pub result_copy_time_ms: f64,
+        pub gpu_preprocess_time_ms: f64,
+        pub gpu_tensorrt_time_ms: f64,
+        pub gpu_copy_time_ms: f64,
+        pub mask_decode_time_ms: f64,
     }
 
     pub type YoloInferenceHandle = *mut c_void;
@@ -179,6 +370,10 @@ pub mod yolo_c_api {
                 tensorrt_time_ms: 0.0,
                 postprocess_time_ms: 0.0,
                 result_copy_time_ms: 0.0,
+                gpu_preprocess_time_ms: 0.0,
+                gpu_tensorrt_time_ms: 0.0,
+                gpu_copy_time_ms: 0.0,
+                mask_decode_time_ms: 0.0,
             };
             let ok = unsafe { yolo_inference(self.handle, image_c.as_ptr(), &mut result) };
             if !ok {
@@ -0,0 +1,137 @@
+//! 把 TensorRT 运行时和 C++ 核心的日志转发到 Rust 日志生态
+//!
+//! 默认转发到 [`log`] crate；启用 `tracing` 特性时改为发出 `tracing` 事件。
+//! 日志目标统一为 `tensorrt`，可以用 `RUST_LOG=tensorrt=debug` 之类的过滤规则单独控制。
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::sync::Once;
+
+/// 日志目标
+pub const LOG_TARGET: &str = "tensorrt";
+
+// 与 TensorRT 的 ILogger::Severity 一致
+const SEVERITY_INTERNAL_ERROR: c_int = 0;
+const SEVERITY_ERROR: c_int = 1;
+const SEVERITY_WARNING: c_int = 2;
+const SEVERITY_INFO: c_int = 3;
+const SEVERITY_VERBOSE: c_int = 4;
+
+static INSTALL: Once = Once::new();
+
+/// 安装日志回调并按配置设置原生日志级别
+///
+/// 回调是进程级的，只安装一次；日志级别取 `verbose` 和 `log` 当前最高级别中较严格的一个，
+/// 由最近创建的推理器决定。
+pub(crate) fn install(verbose: bool) {
+    INSTALL.call_once(|| unsafe { yolo_set_log_callback(Some(forward)) });
+
+    let ceiling = if verbose {
+        SEVERITY_VERBOSE
+    } else {
+        SEVERITY_WARNING
+    };
+    unsafe { yolo_set_log_level(ceiling.min(enabled_severity())) };
+}
+
+/// 设置原生日志的最高级别（进程级）
+///
+/// 在 TensorRT 一侧就过滤掉不需要的日志，避免逐条跨越 FFI 边界。
+/// 之后创建的推理器会按自己的 [`Config::verbose`](crate::Config::verbose) 重新设置级别。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{set_native_log_level, Config, Yolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine").with_verbose(true))?;
+///     // 引擎加载完成后只保留错误
+///     set_native_log_level(log::LevelFilter::Error);
+///     yolo.inference("images/test.jpg")?;
+///     Ok(())
+/// }
+/// ```
+pub fn set_native_log_level(level: log::LevelFilter) {
+    INSTALL.call_once(|| unsafe { yolo_set_log_callback(Some(forward)) });
+    unsafe { yolo_set_log_level(severity_for(level)) };
+}
+
+/// `log` 的级别对应的最高原生日志级别，`Off` 时只保留内部错误
+fn severity_for(level: log::LevelFilter) -> c_int {
+    match level {
+        log::LevelFilter::Off => SEVERITY_INTERNAL_ERROR,
+        log::LevelFilter::Error => SEVERITY_ERROR,
+        log::LevelFilter::Warn => SEVERITY_WARNING,
+        log::LevelFilter::Info => SEVERITY_INFO,
+        log::LevelFilter::Debug | log::LevelFilter::Trace => SEVERITY_VERBOSE,
+    }
+}
+
+/// 当前日志实现允许的最高原生日志级别
+#[cfg(not(feature = "tracing"))]
+fn enabled_severity() -> c_int {
+    severity_for(log::max_level())
+}
+
+#[cfg(feature = "tracing")]
+fn enabled_severity() -> c_int {
+    use tracing::level_filters::LevelFilter;
+
+    let current = LevelFilter::current();
+    if current >= LevelFilter::DEBUG {
+        SEVERITY_VERBOSE
+    } else if current >= LevelFilter::INFO {
+        SEVERITY_INFO
+    } else if current >= LevelFilter::WARN {
+        SEVERITY_WARNING
+    } else if current >= LevelFilter::ERROR {
+        SEVERITY_ERROR
+    } else {
+        SEVERITY_INTERNAL_ERROR
+    }
+}
+
+/// 原生日志级别对应的 `log` 级别
+fn level_for(severity: c_int) -> log::Level {
+    match severity {
+        SEVERITY_INTERNAL_ERROR | SEVERITY_ERROR => log::Level::Error,
+        SEVERITY_WARNING => log::Level::Warn,
+        SEVERITY_INFO => log::Level::Info,
+        _ => log::Level::Debug,
+    }
+}
+
+extern "C" fn forward(severity: c_int, message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    let message = message.trim_end();
+    // 回调运行在 C++ 栈帧中，不能让 panic 越过 FFI 边界
+    let _ = std::panic::catch_unwind(|| emit(level_for(severity), message));
+}
+
+#[cfg(not(feature = "tracing"))]
+fn emit(level: log::Level, message: &str) {
+    log::log!(target: LOG_TARGET, level, "{}", message);
+}
+
+#[cfg(feature = "tracing")]
+fn emit(level: log::Level, message: &str) {
+    match level {
+        log::Level::Error => tracing::error!(target: LOG_TARGET, "{}", message),
+        log::Level::Warn => tracing::warn!(target: LOG_TARGET, "{}", message),
+        log::Level::Info => tracing::info!(target: LOG_TARGET, "{}", message),
+        log::Level::Debug => tracing::debug!(target: LOG_TARGET, "{}", message),
+        log::Level::Trace => tracing::trace!(target: LOG_TARGET, "{}", message),
+    }
+}
+
+type LogCallback = extern "C" fn(severity: c_int, message: *const c_char);
+
+// C API 函数声明
+extern "C" {
+    fn yolo_set_log_callback(callback: Option<LogCallback>);
+    fn yolo_set_log_level(max_severity: c_int);
+}
@@ -0,0 +1,462 @@
+//! 二值掩码的后处理
+//!
+//! 模型输出的实例掩码常有噪点、空洞和毛糙的边缘。这里在二值掩码上提供形态学开/闭运算、
+//! 孔洞填充、小连通域去除和只保留最大连通域，全部用 Rust 实现。
+//! 前景连通域按 8 邻域计算，孔洞（不与边缘连通的背景）按 4 邻域计算。
+
+use crate::error::{YoloError, YoloResult};
+
+/// 二值掩码，按行存储
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryMask {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl BinaryMask {
+    /// 用像素数据创建，长度不等于 `width * height` 时返回错误
+    pub fn new(width: usize, height: usize, pixels: Vec<bool>) -> YoloResult<Self> {
+        check_len(pixels.len(), width, height)?;
+        Ok(Self::from_parts(width, height, pixels))
+    }
+
+    /// 把概率大于 `threshold` 的像素作为前景，长度不等于 `width * height` 时返回错误
+    pub fn from_probabilities(
+        probabilities: &[f32],
+        width: usize,
+        height: usize,
+        threshold: f32,
+    ) -> YoloResult<Self> {
+        check_len(probabilities.len(), width, height)?;
+        Ok(Self::from_parts(
+            width,
+            height,
+            probabilities.iter().map(|&p| p > threshold).collect(),
+        ))
+    }
+
+    /// 长度已经确定一致的像素数据
+    fn from_parts(width: usize, height: usize, pixels: Vec<bool>) -> Self {
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// 转换为 0/1 的概率图
+    pub fn to_probabilities(&self) -> Vec<f32> {
+        self.pixels
+            .iter()
+            .map(|&p| if p { 1.0 } else { 0.0 })
+            .collect()
+    }
+
+    /// 宽度
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 高度
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 像素数据
+    pub fn pixels(&self) -> &[bool] {
+        &self.pixels
+    }
+
+    /// 像素 `(x, y)` 是否为前景
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    /// 前景像素数
+    pub fn area(&self) -> usize {
+        self.pixels.iter().filter(|&&p| p).count()
+    }
+
+    /// 前景的外接矩形 `[x1, y1, x2, y2]`，右、下边界不含；没有前景时返回 `None`
+    pub fn bounding_box(&self) -> Option<[usize; 4]> {
+        let mut extent: Option<[usize; 4]> = None;
+        for (y, row) in self.pixels.chunks(self.width.max(1)).enumerate() {
+            let Some(left) = row.iter().position(|&p| p) else {
+                continue;
+            };
+            let right = row.iter().rposition(|&p| p).unwrap_or(left) + 1;
+            extent = Some(match extent {
+                Some([x1, y1, x2, _]) => [x1.min(left), y1, x2.max(right), y + 1],
+                None => [left, y, right, y + 1],
+            });
+        }
+        extent
+    }
+
+    /// 腐蚀，图像外视为前景（与 OpenCV 默认边界一致）
+    pub fn erode(&self, kernel: &Kernel) -> Self {
+        self.morph(kernel, true)
+    }
+
+    /// 膨胀，图像外视为背景
+    pub fn dilate(&self, kernel: &Kernel) -> Self {
+        self.morph(kernel, false)
+    }
+
+    /// 开运算：先腐蚀再膨胀，去除小于结构元素的噪点和毛刺
+    pub fn open(&self, kernel: &Kernel) -> Self {
+        self.erode(kernel).dilate(kernel)
+    }
+
+    /// 闭运算：先膨胀再腐蚀，弥合小于结构元素的缝隙
+    pub fn close(&self, kernel: &Kernel) -> Self {
+        self.dilate(kernel).erode(kernel)
+    }
+
+    /// 填充不与图像边缘连通的背景区域
+    pub fn fill_holes(&self) -> Self {
+        let (width, height) = (self.width, self.height);
+        let mut outside = vec![false; self.pixels.len()];
+        let mut stack: Vec<usize> = (0..width)
+            .flat_map(|x| [x, (height.saturating_sub(1)) * width + x])
+            .chain((0..height).flat_map(|y| [y * width, y * width + width.saturating_sub(1)]))
+            .filter(|&i| i < self.pixels.len())
+            .collect();
+        while let Some(index) = stack.pop() {
+            if self.pixels[index] || outside[index] {
+                continue;
+            }
+            outside[index] = true;
+            let (x, y) = (index % width, index / width);
+            if x > 0 {
+                stack.push(index - 1);
+            }
+            if x + 1 < width {
+                stack.push(index + 1);
+            }
+            if y > 0 {
+                stack.push(index - width);
+            }
+            if y + 1 < height {
+                stack.push(index + width);
+            }
+        }
+        Self::from_parts(width, height, outside.into_iter().map(|o| !o).collect())
+    }
+
+    /// 去除面积小于 `min_area` 的连通域
+    pub fn remove_small_components(&self, min_area: usize) -> Self {
+        let (labels, areas) = self.components();
+        self.keep_labels(&labels, |label| areas[label] >= min_area)
+    }
+
+    /// 只保留面积最大的连通域，面积相同时保留扫描顺序靠前的
+    pub fn keep_largest_component(&self) -> Self {
+        let (labels, areas) = self.components();
+        let largest = areas
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, area)| area)
+            .map(|(label, _)| label);
+        self.keep_labels(&labels, |label| Some(label) == largest)
+    }
+
+    /// 8 邻域连通域标记，返回每个像素的标记（背景为 `usize::MAX`）和各连通域面积
+    fn components(&self) -> (Vec<usize>, Vec<usize>) {
+        let (width, height) = (self.width, self.height);
+        let mut labels = vec![usize::MAX; self.pixels.len()];
+        let mut areas = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.pixels.len() {
+            if !self.pixels[start] || labels[start] != usize::MAX {
+                continue;
+            }
+            let label = areas.len();
+            let mut area = 0;
+            labels[start] = label;
+            stack.push(start);
+            while let Some(index) = stack.pop() {
+                area += 1;
+                let (x, y) = (index % width, index / width);
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        let neighbor = ny * width + nx;
+                        if self.pixels[neighbor] && labels[neighbor] == usize::MAX {
+                            labels[neighbor] = label;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+            areas.push(area);
+        }
+        (labels, areas)
+    }
+
+    fn keep_labels(&self, labels: &[usize], keep: impl Fn(usize) -> bool) -> Self {
+        let pixels = labels
+            .iter()
+            .map(|&label| label != usize::MAX && keep(label))
+            .collect();
+        Self::from_parts(self.width, self.height, pixels)
+    }
+
+    /// 腐蚀要求结构元素覆盖的像素全为前景，膨胀要求至少一个为前景
+    ///
+    /// 每行预先计算前缀和，结构元素的每一行只需 O(1) 查询。
+    fn morph(&self, kernel: &Kernel, erode: bool) -> Self {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let prefix: Vec<Vec<u32>> = self
+            .pixels
+            .chunks(self.width.max(1))
+            .map(|row| {
+                std::iter::once(0)
+                    .chain(row.iter().scan(0, |sum, &p| {
+                        *sum += p as u32;
+                        Some(*sum)
+                    }))
+                    .collect()
+            })
+            .collect();
+        let (anchor_x, anchor_y) = kernel.anchor();
+
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+        for y in 0..height {
+            for x in 0..width {
+                let mut hit = erode;
+                for (row, &(start, end)) in kernel.spans.iter().enumerate() {
+                    let yy = y + row as isize - anchor_y;
+                    if start == end || yy < 0 || yy >= height {
+                        continue;
+                    }
+                    let left = (x + start as isize - anchor_x).clamp(0, width) as usize;
+                    let right = (x + end as isize - anchor_x).clamp(0, width) as usize;
+                    let count = prefix[yy as usize][right] - prefix[yy as usize][left];
+                    if erode && count < (right - left) as u32 {
+                        hit = false;
+                        break;
+                    }
+                    if !erode && count > 0 {
+                        hit = true;
+                        break;
+                    }
+                }
+                pixels.push(hit);
+            }
+        }
+        Self::from_parts(self.width, self.height, pixels)
+    }
+}
+
+/// 检查像素数量与尺寸一致
+fn check_len(len: usize, width: usize, height: usize) -> YoloResult<()> {
+    if width.checked_mul(height) != Some(len) {
+        return Err(YoloError::InvalidParameter(format!(
+            "掩码长度 {} 与尺寸 {}x{} 不匹配",
+            len, width, height
+        )));
+    }
+    Ok(())
+}
+
+/// 形态学运算的结构元素，锚点位于中心
+///
+/// 形状与 OpenCV 的 `getStructuringElement` 一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kernel {
+    width: usize,
+    height: usize,
+    /// 每一行覆盖的列范围 `[start, end)`
+    spans: Vec<(usize, usize)>,
+}
+
+impl Kernel {
+    /// 矩形结构元素
+    pub fn rect(width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        Self {
+            width,
+            height,
+            spans: vec![(0, width); height],
+        }
+    }
+
+    /// 椭圆结构元素
+    pub fn ellipse(width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        let (r, c) = ((height / 2) as f64, (width / 2) as f64);
+        let inv_r2 = if r > 0.0 { 1.0 / (r * r) } else { 0.0 };
+        let spans = (0..height)
+            .map(|i| {
+                let dy = i as f64 - r;
+                let dx = (c * ((r * r - dy * dy) * inv_r2).sqrt()).round();
+                let start = (c - dx).max(0.0) as usize;
+                let end = ((c + dx + 1.0) as usize).min(width);
+                (start, end)
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            spans,
+        }
+    }
+
+    /// 十字形结构元素
+    pub fn cross(width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        let spans = (0..height)
+            .map(|i| {
+                if i == height / 2 {
+                    (0, width)
+                } else {
+                    (width / 2, width / 2 + 1)
+                }
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            spans,
+        }
+    }
+
+    /// 宽度
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 高度
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn anchor(&self) -> (isize, isize) {
+        ((self.width / 2) as isize, (self.height / 2) as isize)
+    }
+}
+
+/// 掩码细化配置
+///
+/// 按以下顺序执行已启用的步骤：二值化、开运算、闭运算、孔洞填充、去除小连通域、只保留最大连通域，
+/// 最后可以按细化后的掩码收紧边界框。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::mask::{Kernel, MaskRefinement};
+/// use yolo11s_tensorrt_rs::Detection;
+///
+/// let refinement = MaskRefinement::default()
+///     .with_open(Kernel::ellipse(5, 5))
+///     .with_close(Kernel::ellipse(7, 7))
+///     .with_fill_holes(true)
+///     .with_min_area(200);
+///
+/// let mut detection =
+///     Detection::new([0.0, 0.0, 64.0, 64.0], 0.9, 0).with_mask(vec![0.8; 64 * 64], 64, 64);
+/// detection.refine_mask(&refinement);
+/// assert!(detection.mask_data().iter().all(|&p| p == 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskRefinement {
+    /// 二值化阈值，概率大于阈值的像素为前景
+    pub threshold: f32,
+    /// 开运算的结构元素
+    pub open: Option<Kernel>,
+    /// 闭运算的结构元素
+    pub close: Option<Kernel>,
+    /// 是否填充孔洞
+    pub fill_holes: bool,
+    /// 连通域的最小面积（像素）
+    pub min_area: Option<usize>,
+    /// 是否只保留最大连通域
+    pub keep_largest: bool,
+    /// 是否按细化后的掩码重新计算边界框，并丢弃掩码为空的检测
+    pub tighten_boxes: bool,
+}
+
+impl Default for MaskRefinement {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            open: None,
+            close: None,
+            fill_holes: false,
+            min_area: None,
+            keep_largest: false,
+            tighten_boxes: false,
+        }
+    }
+}
+
+impl MaskRefinement {
+    /// 设置二值化阈值
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// 启用开运算
+    pub fn with_open(mut self, kernel: Kernel) -> Self {
+        self.open = Some(kernel);
+        self
+    }
+
+    /// 启用闭运算
+    pub fn with_close(mut self, kernel: Kernel) -> Self {
+        self.close = Some(kernel);
+        self
+    }
+
+    /// 设置是否填充孔洞
+    pub fn with_fill_holes(mut self, fill_holes: bool) -> Self {
+        self.fill_holes = fill_holes;
+        self
+    }
+
+    /// 去除面积小于 `min_area` 的连通域
+    pub fn with_min_area(mut self, min_area: usize) -> Self {
+        self.min_area = Some(min_area);
+        self
+    }
+
+    /// 设置是否只保留最大连通域
+    pub fn with_keep_largest(mut self, keep_largest: bool) -> Self {
+        self.keep_largest = keep_largest;
+        self
+    }
+
+    /// 设置是否按掩码收紧边界框
+    ///
+    /// 边界框改为掩码前景的外接矩形，使报告的范围与绘制和测量的掩码一致；
+    /// [`InferenceResult::refine_masks`](crate::InferenceResult::refine_masks)
+    /// 会丢弃二值化和细化后掩码为空的检测。
+    pub fn with_tighten_boxes(mut self, tighten_boxes: bool) -> Self {
+        self.tighten_boxes = tighten_boxes;
+        self
+    }
+
+    /// 对二值掩码执行已启用的步骤
+    pub fn apply(&self, mask: BinaryMask) -> BinaryMask {
+        let mut mask = mask;
+        if let Some(kernel) = &self.open {
+            mask = mask.open(kernel);
+        }
+        if let Some(kernel) = &self.close {
+            mask = mask.close(kernel);
+        }
+        if self.fill_holes {
+            mask = mask.fill_holes();
+        }
+        if let Some(min_area) = self.min_area {
+            mask = mask.remove_small_components(min_area);
+        }
+        if self.keep_largest {
+            mask = mask.keep_largest_component();
+        }
+        mask
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt::Write as _;
+
+use crate::stats::{PerfStats, Stage};
+
+/// Prometheus 文本格式的内容类型
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 以 Prometheus 文本格式输出统计数据
+///
+/// 包括各阶段耗时直方图（秒）、推理和错误计数、每个类别的检测数量、
+/// 滑动窗口吞吐量，以及可选的队列深度（例如 [`YoloPool::queue_depth`](crate::YoloPool::queue_depth)）。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{render_metrics, Yolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = Yolo::with_engine("models/yolo11s-seg.engine")?;
+///     yolo.inference("images/test.jpg")?;
+///     print!("{}", render_metrics(yolo.stats(), None));
+///     Ok(())
+/// }
+/// ```
+pub fn render_metrics(stats: &PerfStats, queue_depth: Option<usize>) -> String {
+    let snapshot = stats.snapshot();
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "yolo_inferences_total",
+        "counter",
+        "成功的推理次数",
+    );
+    let _ = writeln!(out, "yolo_inferences_total {}", snapshot.inferences);
+
+    header(&mut out, "yolo_errors_total", "counter", "失败的推理次数");
+    if snapshot.errors_by_kind.is_empty() {
+        let _ = writeln!(out, "yolo_errors_total 0");
+    }
+    for (kind, count) in &snapshot.errors_by_kind {
+        let _ = writeln!(out, "yolo_errors_total{{kind=\"{}\"}} {}", kind, count);
+    }
+
+    header(
+        &mut out,
+        "yolo_detections_total",
+        "counter",
+        "检测到的目标数量",
+    );
+    if snapshot.detections_per_class.is_empty() {
+        let _ = writeln!(out, "yolo_detections_total 0");
+    }
+    for (class_id, count) in &snapshot.detections_per_class {
+        let _ = writeln!(
+            out,
+            "yolo_detections_total{{class_id=\"{}\"}} {}",
+            class_id, count
+        );
+    }
+
+    header(
+        &mut out,
+        "yolo_stage_duration_seconds",
+        "histogram",
+        "各推理阶段的耗时",
+    );
+    for stage in Stage::ALL {
+        let histogram = snapshot.stage(stage);
+        for (bound, count) in histogram.cumulative() {
+            let le = if bound.is_infinite() {
+                "+Inf".to_string()
+            } else {
+                format_float(bound / 1000.0)
+            };
+            let _ = writeln!(
+                out,
+                "yolo_stage_duration_seconds_bucket{{stage=\"{}\",le=\"{}\"}} {}",
+                stage.name(),
+                le,
+                count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "yolo_stage_duration_seconds_sum{{stage=\"{}\"}} {}",
+            stage.name(),
+            format_float(histogram.sum_ms / 1000.0)
+        );
+        let _ = writeln!(
+            out,
+            "yolo_stage_duration_seconds_count{{stage=\"{}\"}} {}",
+            stage.name(),
+            histogram.count
+        );
+    }
+
+    header(
+        &mut out,
+        "yolo_throughput_per_second",
+        "gauge",
+        "滑动窗口内的推理吞吐量",
+    );
+    for (window, value) in [
+        ("1s", snapshot.throughput_1s),
+        ("10s", snapshot.throughput_10s),
+        ("60s", snapshot.throughput_60s),
+    ] {
+        let _ = writeln!(
+            out,
+            "yolo_throughput_per_second{{window=\"{}\"}} {}",
+            window,
+            format_float(value)
+        );
+    }
+
+    if let Some(depth) = queue_depth {
+        header(&mut out, "yolo_queue_depth", "gauge", "等待执行的任务数");
+        let _ = writeln!(out, "yolo_queue_depth {}", depth);
+    }
+
+    header(
+        &mut out,
+        "yolo_uptime_seconds",
+        "gauge",
+        "统计开始或上次重置以来的时间",
+    );
+    let _ = writeln!(
+        out,
+        "yolo_uptime_seconds {}",
+        format_float(snapshot.uptime.as_secs_f64())
+    );
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn format_float(value: f64) -> String {
+    if value.is_finite() {
+        format!("{}", value)
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else if value > 0.0 {
+        "+Inf".to_string()
+    } else {
+        "-Inf".to_string()
+    }
+}
+
+#[cfg(feature = "metrics-server")]
+pub use server::{MetricsServer, MetricsServerBuilder};
+
+#[cfg(feature = "metrics-server")]
+mod server {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    use super::{render_metrics, CONTENT_TYPE};
+    use crate::error::YoloResult;
+    use crate::stats::PerfStats;
+
+    type QueueDepth = Box<dyn Fn() -> usize + Send + Sync>;
+
+    /// 内置的 `/metrics` HTTP 服务
+    ///
+    /// 只依赖标准库，在后台线程中逐个处理连接，适合低频的 Prometheus 抓取。
+    /// 需要启用 `metrics-server` 特性。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use yolo11s_tensorrt_rs::{Config, MetricsServer, PerfStats, PoolConfig, YoloPool};
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let stats = Arc::new(PerfStats::new());
+    ///     let config = Config::new("models/yolo11s-seg.engine").with_stats(Arc::clone(&stats));
+    ///     let pool = Arc::new(YoloPool::new(config, PoolConfig::new(2))?);
+    ///
+    ///     let queue = Arc::clone(&pool);
+    ///     let server = MetricsServer::builder(stats)
+    ///         .with_queue_depth(move || queue.queue_depth())
+    ///         .serve("0.0.0.0:9100")?;
+    ///     println!("metrics: http://{}/metrics", server.local_addr());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub struct MetricsServer {
+        local_addr: SocketAddr,
+        stopped: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    /// [`MetricsServer`] 构建器
+    pub struct MetricsServerBuilder {
+        stats: Arc<PerfStats>,
+        queue_depth: Option<QueueDepth>,
+    }
+
+    impl MetricsServerBuilder {
+        /// 设置队列深度的来源
+        pub fn with_queue_depth<F>(mut self, queue_depth: F) -> Self
+        where
+            F: Fn() -> usize + Send + Sync + 'static,
+        {
+            self.queue_depth = Some(Box::new(queue_depth));
+            self
+        }
+
+        /// 绑定地址并在后台线程中开始服务
+        pub fn serve<A: ToSocketAddrs>(self, addr: A) -> YoloResult<MetricsServer> {
+            let listener = TcpListener::bind(addr)?;
+            let local_addr = listener.local_addr()?;
+            let stopped = Arc::new(AtomicBool::new(false));
+
+            let thread = {
+                let stopped = Arc::clone(&stopped);
+                thread::Builder::new()
+                    .name("yolo-metrics".to_string())
+                    .spawn(move || {
+                        for stream in listener.incoming() {
+                            if stopped.load(Ordering::SeqCst) {
+                                break;
+                            }
+                            if let Ok(stream) = stream {
+                                let _ = self.handle(stream);
+                            }
+                        }
+                    })?
+            };
+
+            Ok(MetricsServer {
+                local_addr,
+                stopped,
+                thread: Some(thread),
+            })
+        }
+
+        fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+            stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(&stream);
+            reader.read_line(&mut request_line)?;
+            // 读完请求头，避免客户端在发送途中被重置
+            let mut line = String::new();
+            while reader.read_line(&mut line)? > 2 {
+                line.clear();
+            }
+
+            let mut parts = request_line.split_whitespace();
+            let (status, content_type, body) = match (parts.next(), parts.next()) {
+                (Some("GET"), Some("/metrics")) => {
+                    let depth = self.queue_depth.as_ref().map(|f| f());
+                    ("200 OK", CONTENT_TYPE, render_metrics(&self.stats, depth))
+                }
+                (Some("GET"), _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+                _ => (
+                    "405 Method Not Allowed",
+                    "text/plain",
+                    "method not allowed\n".to_string(),
+                ),
+            };
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            )?;
+            stream.flush()
+        }
+    }
+
+    impl MetricsServer {
+        /// 创建构建器
+        pub fn builder(stats: Arc<PerfStats>) -> MetricsServerBuilder {
+            MetricsServerBuilder {
+                stats,
+                queue_depth: None,
+            }
+        }
+
+        /// 实际监听的地址（绑定端口 0 时可用于获取分配的端口）
+        pub fn local_addr(&self) -> SocketAddr {
+            self.local_addr
+        }
+
+        /// 停止服务并等待后台线程退出
+        pub fn shutdown(mut self) {
+            self.stop();
+        }
+
+        fn stop(&mut self) {
+            if let Some(thread) = self.thread.take() {
+                self.stopped.store(true, Ordering::SeqCst);
+                // accept 是阻塞的，主动连一次让后台线程看到停止标志
+                let mut addr = self.local_addr;
+                if addr.ip().is_unspecified() {
+                    addr.set_ip(match addr {
+                        SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                        SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+                    });
+                }
+                let _ = TcpStream::connect(addr);
+                let _ = thread.join();
+            }
+        }
+    }
+
+    impl Drop for MetricsServer {
+        fn drop(&mut self) {
+            self.stop();
+        }
+    }
+}
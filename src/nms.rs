@@ -0,0 +1,465 @@
+//! 非极大值抑制
+//!
+//! 在 Rust 中对 [`RawCandidate`] 执行 NMS，取代 C++ 核心按类别的贪心 NMS。
+//! 支持按类别或跨类别抑制、Soft-NMS（线性/高斯衰减）、DIoU-NMS、
+//! 每张图片和每个类别的数量上限，以及 NMS 之前按置信度只保留前 k 个候选框。
+//! 分割模型还可以用实例掩码的交并比代替边界框的交并比，旋转框模型使用旋转框的交并比。
+
+use std::cell::OnceCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::decode::{Proto, RawCandidate};
+use crate::error::{YoloError, YoloResult};
+use crate::types::RotatedBox;
+
+/// 抑制方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NmsMethod {
+    /// 重叠度超过阈值的候选框直接丢弃
+    Hard,
+    /// Soft-NMS 线性衰减：重叠度超过阈值时置信度乘以 `1 - 重叠度`
+    Linear,
+    /// Soft-NMS 高斯衰减：置信度乘以 `exp(-重叠度² / sigma)`，与阈值无关
+    Gaussian {
+        /// 衰减速度，越小衰减越快，必须是正的有限值
+        sigma: f32,
+    },
+}
+
+/// 候选框之间的重叠度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlap {
+    /// 交并比
+    Iou,
+    /// DIoU：交并比减去中心点距离与最小外接框对角线之比的平方，
+    /// 中心相距较远的相邻目标更不容易被抑制
+    Diou,
+    /// 掩码交并比：在原型分辨率下比较二值化（概率大于 0.5）的实例掩码，
+    /// 边界框大量重叠但掩码不重叠的细长目标（如交叉的裂纹）不会互相抑制
+    ///
+    /// 需要分割原型，只在 [`nms_with_proto`] 中生效；[`nms`] 退化为交并比。
+    Mask,
+    /// 旋转框交并比：`bbox` 按 `[cx, cy, w, h]` 解释，加上 `angle` 构成旋转框，
+    /// 用于旋转框模型
+    Rotated,
+}
+
+/// NMS 配置
+///
+/// 默认值与 C++ 核心一致：按类别的硬 NMS，置信度阈值 0.5，IoU 阈值 0.45，不限数量。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::{Config, NmsConfig, NmsMethod, Overlap};
+///
+/// let nms = NmsConfig::default()
+///     .with_method(NmsMethod::Gaussian { sigma: 0.5 })
+///     .with_overlap(Overlap::Diou)
+///     .with_top_k(300)
+///     .with_max_detections(100);
+/// let config = Config::new("models/yolo11s-seg.engine").with_nms(nms);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NmsConfig {
+    /// 抑制方式
+    pub method: NmsMethod,
+    /// 重叠度度量
+    pub overlap: Overlap,
+    /// 是否跨类别抑制，为 `false` 时只在同一类别内抑制
+    pub class_agnostic: bool,
+    /// 置信度阈值，只保留置信度大于阈值的候选框；Soft-NMS 衰减后低于阈值的同样丢弃
+    pub confidence_threshold: f32,
+    /// 重叠度阈值
+    pub iou_threshold: f32,
+    /// NMS 之前按置信度最多保留的候选框数量
+    pub top_k: Option<usize>,
+    /// 每个类别最多保留的检测数量
+    pub max_per_class: Option<usize>,
+    /// 每张图片最多保留的检测数量
+    pub max_detections: Option<usize>,
+}
+
+impl Default for NmsConfig {
+    fn default() -> Self {
+        Self {
+            method: NmsMethod::Hard,
+            overlap: Overlap::Iou,
+            class_agnostic: false,
+            confidence_threshold: 0.5,
+            iou_threshold: 0.45,
+            top_k: None,
+            max_per_class: None,
+            max_detections: None,
+        }
+    }
+}
+
+impl NmsConfig {
+    /// 设置抑制方式
+    pub fn with_method(mut self, method: NmsMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// 设置重叠度度量
+    pub fn with_overlap(mut self, overlap: Overlap) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// 设置是否跨类别抑制
+    pub fn with_class_agnostic(mut self, class_agnostic: bool) -> Self {
+        self.class_agnostic = class_agnostic;
+        self
+    }
+
+    /// 设置置信度阈值
+    pub fn with_confidence_threshold(mut self, confidence_threshold: f32) -> Self {
+        self.confidence_threshold = confidence_threshold;
+        self
+    }
+
+    /// 设置重叠度阈值
+    pub fn with_iou_threshold(mut self, iou_threshold: f32) -> Self {
+        self.iou_threshold = iou_threshold;
+        self
+    }
+
+    /// 设置 NMS 之前最多保留的候选框数量
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// 设置每个类别最多保留的检测数量
+    pub fn with_max_per_class(mut self, max_per_class: usize) -> Self {
+        self.max_per_class = Some(max_per_class);
+        self
+    }
+
+    /// 设置每张图片最多保留的检测数量
+    pub fn with_max_detections(mut self, max_detections: usize) -> Self {
+        self.max_detections = Some(max_detections);
+        self
+    }
+
+    /// 检查参数是否有效，高斯衰减的 `sigma` 必须是正的有限值
+    pub fn validate(&self) -> YoloResult<()> {
+        if let NmsMethod::Gaussian { sigma } = self.method {
+            if !sigma.is_finite() || sigma <= 0.0 {
+                return Err(YoloError::InvalidParameter(format!(
+                    "高斯衰减的 sigma 必须是正数: {}",
+                    sigma
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 两个 `[x1, y1, x2, y2]` 边界框的交并比
+pub fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let width = a[2].min(b[2]) - a[0].max(b[0]);
+    let height = a[3].min(b[3]) - a[1].max(b[1]);
+    if width <= 0.0 || height <= 0.0 {
+        return 0.0;
+    }
+    let intersection = width * height;
+    let union = (a[2] - a[0]) * (a[3] - a[1]) + (b[2] - b[0]) * (b[3] - b[1]) - intersection;
+    if union > 0.0 {
+        intersection / union
+    } else {
+        0.0
+    }
+}
+
+/// 两个 `[x1, y1, x2, y2]` 边界框的 DIoU，取值范围 `(-1, 1]`
+pub fn diou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let dx = (a[0] + a[2] - b[0] - b[2]) / 2.0;
+    let dy = (a[1] + a[3] - b[1] - b[3]) / 2.0;
+    let cw = a[2].max(b[2]) - a[0].min(b[0]);
+    let ch = a[3].max(b[3]) - a[1].min(b[1]);
+    let diagonal = cw * cw + ch * ch;
+    if diagonal > 0.0 {
+        iou(a, b) - (dx * dx + dy * dy) / diagonal
+    } else {
+        iou(a, b)
+    }
+}
+
+/// 两个旋转框的交并比
+///
+/// 用 Sutherland–Hodgman 算法求两个凸四边形的交集多边形，再按鞋带公式计算面积。
+pub fn rotated_iou(a: &RotatedBox, b: &RotatedBox) -> f32 {
+    let (area_a, area_b) = (a.area(), b.area());
+    if area_a <= 0.0 || area_b <= 0.0 {
+        return 0.0;
+    }
+
+    let mut polygon = a.corners().to_vec();
+    let clip = b.corners();
+    for (i, &p) in clip.iter().enumerate() {
+        let q = clip[(i + 1) % clip.len()];
+        // 顶点顺序一致，叉积非负即在裁剪边内侧
+        let side = |r: [f32; 2]| (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0]);
+        let input = std::mem::take(&mut polygon);
+        for (j, &s) in input.iter().enumerate() {
+            let e = input[(j + 1) % input.len()];
+            let (ds, de) = (side(s), side(e));
+            if ds >= 0.0 {
+                polygon.push(s);
+            }
+            if (ds >= 0.0) != (de >= 0.0) {
+                let t = ds / (ds - de);
+                polygon.push([s[0] + (e[0] - s[0]) * t, s[1] + (e[1] - s[1]) * t]);
+            }
+        }
+        if polygon.is_empty() {
+            return 0.0;
+        }
+    }
+
+    let twice_area: f32 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(s, e)| s[0] * e[1] - e[0] * s[1])
+        .sum();
+    let intersection = (twice_area.abs() / 2.0).min(area_a.min(area_b));
+    intersection / (area_a + area_b - intersection)
+}
+
+/// 对候选框执行非极大值抑制
+///
+/// 返回的检测按置信度从高到低排列；Soft-NMS 返回衰减后的置信度。
+/// 置信度相同时按 `x1` 从小到大、再按输入顺序排列，因此结果是确定的。
+///
+/// # 示例
+///
+/// ```
+/// use yolo11s_tensorrt_rs::nms::{nms, NmsConfig};
+/// use yolo11s_tensorrt_rs::RawCandidate;
+///
+/// let candidates = vec![
+///     RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+///     RawCandidate::new([1.0, 1.0, 11.0, 11.0], 0.8, 0),
+///     RawCandidate::new([1.0, 1.0, 11.0, 11.0], 0.7, 1),
+/// ];
+/// let kept = nms(candidates, &NmsConfig::default());
+/// assert_eq!(kept.len(), 2);
+/// ```
+pub fn nms(candidates: Vec<RawCandidate>, config: &NmsConfig) -> Vec<RawCandidate> {
+    suppress(candidates, None, config)
+}
+
+/// 使用分割原型执行非极大值抑制
+///
+/// 与 [`nms`] 相同，但 [`Overlap::Mask`] 按实例掩码计算重叠度。
+/// 掩码只在需要比较时才计算，边界框不相交的候选框不会解码掩码。
+pub fn nms_with_proto(
+    candidates: Vec<RawCandidate>,
+    proto: &Proto<'_>,
+    config: &NmsConfig,
+) -> Vec<RawCandidate> {
+    suppress(candidates, Some(proto), config)
+}
+
+fn suppress(
+    candidates: Vec<RawCandidate>,
+    proto: Option<&Proto<'_>>,
+    config: &NmsConfig,
+) -> Vec<RawCandidate> {
+    let context = Context { config, proto };
+    let mut ranked: Vec<Ranked> = candidates
+        .into_iter()
+        .filter(|c| c.confidence > config.confidence_threshold)
+        .enumerate()
+        .map(|(order, candidate)| Ranked {
+            order,
+            candidate,
+            mask: OnceCell::new(),
+        })
+        .collect();
+    ranked.sort_by(Ranked::cmp_rank);
+    if let Some(top_k) = config.top_k {
+        ranked.truncate(top_k);
+    }
+
+    let groups = if config.class_agnostic {
+        vec![ranked]
+    } else {
+        // 按类别分组，组内保持排序
+        let mut groups: HashMap<i32, Vec<Ranked>> = HashMap::new();
+        for entry in ranked {
+            groups
+                .entry(entry.candidate.class_id)
+                .or_default()
+                .push(entry);
+        }
+        groups.into_values().collect()
+    };
+
+    let mut kept: Vec<Ranked> = groups
+        .into_iter()
+        .flat_map(|group| match config.method {
+            NmsMethod::Hard => suppress_hard(group, &context),
+            method => suppress_soft(group, &context, method),
+        })
+        .collect();
+    kept.sort_by(Ranked::cmp_rank);
+
+    let mut kept = kept.into_iter().map(|entry| entry.candidate).collect();
+    limit(&mut kept, config);
+    kept
+}
+
+/// 对按置信度排好序的结果应用每类和总数上限
+pub(crate) fn limit(kept: &mut Vec<RawCandidate>, config: &NmsConfig) {
+    if let Some(max_per_class) = config.max_per_class {
+        let mut counts: HashMap<i32, usize> = HashMap::new();
+        kept.retain(|candidate| {
+            let count = counts.entry(candidate.class_id).or_insert(0);
+            *count += 1;
+            *count <= max_per_class
+        });
+    }
+    if let Some(max_detections) = config.max_detections {
+        kept.truncate(max_detections);
+    }
+}
+
+/// 一次抑制所需的配置和分割原型
+struct Context<'a> {
+    config: &'a NmsConfig,
+    proto: Option<&'a Proto<'a>>,
+}
+
+impl Context<'_> {
+    fn overlap(&self, a: &Ranked, b: &Ranked) -> f32 {
+        match (self.config.overlap, self.proto) {
+            (Overlap::Diou, _) => diou(&a.candidate.bbox, &b.candidate.bbox),
+            (Overlap::Rotated, _) => {
+                rotated_iou(&a.candidate.rotated_box(), &b.candidate.rotated_box())
+            }
+            (Overlap::Mask, Some(proto)) => {
+                let a = a.mask.get_or_init(|| CropMask::new(proto, &a.candidate));
+                let b = b.mask.get_or_init(|| CropMask::new(proto, &b.candidate));
+                a.iou(b)
+            }
+            _ => iou(&a.candidate.bbox, &b.candidate.bbox),
+        }
+    }
+}
+
+/// 带输入顺序的候选框，用于得到确定的排序
+struct Ranked {
+    order: usize,
+    candidate: RawCandidate,
+    /// 按需计算的二值掩码
+    mask: OnceCell<CropMask>,
+}
+
+impl Ranked {
+    /// 置信度从高到低，再按 x1 从小到大（与 C++ 核心一致），最后按输入顺序
+    fn cmp_rank(a: &Self, b: &Self) -> Ordering {
+        b.candidate
+            .confidence
+            .total_cmp(&a.candidate.confidence)
+            .then(a.candidate.bbox[0].total_cmp(&b.candidate.bbox[0]))
+            .then(a.order.cmp(&b.order))
+    }
+}
+
+/// 边界框范围内的二值掩码，框外视为背景
+struct CropMask {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+    pixels: Vec<bool>,
+    area: usize,
+}
+
+impl CropMask {
+    fn new(proto: &Proto<'_>, candidate: &RawCandidate) -> Self {
+        let crop = proto.crop(&candidate.bbox);
+        let (left, top, right, bottom) = crop;
+        let pixels: Vec<bool> = proto
+            .crop_logits(&candidate.mask_coefficients, crop)
+            .into_iter()
+            .map(|logit| logit > 0.0)
+            .collect();
+        let area = pixels.iter().filter(|&&p| p).count();
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            pixels,
+            area,
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[(y - self.top) * (self.right - self.left) + x - self.left]
+    }
+
+    fn iou(&self, other: &Self) -> f32 {
+        let left = self.left.max(other.left);
+        let top = self.top.max(other.top);
+        let right = self.right.min(other.right);
+        let bottom = self.bottom.min(other.bottom);
+        let mut intersection = 0;
+        for y in top..bottom {
+            for x in left..right {
+                if self.get(x, y) && other.get(x, y) {
+                    intersection += 1;
+                }
+            }
+        }
+        let union = self.area + other.area - intersection;
+        if union > 0 {
+            intersection as f32 / union as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 已排序的同组候选框依次保留，与已保留的任意一个重叠度超过阈值的丢弃
+fn suppress_hard(group: Vec<Ranked>, context: &Context<'_>) -> Vec<Ranked> {
+    let config = context.config;
+    let mut kept: Vec<Ranked> = Vec::new();
+    for entry in group {
+        if kept
+            .iter()
+            .all(|k| context.overlap(k, &entry) <= config.iou_threshold)
+        {
+            kept.push(entry);
+        }
+    }
+    kept
+}
+
+/// 每次取出当前置信度最高的候选框，衰减其余候选框的置信度
+fn suppress_soft(mut group: Vec<Ranked>, context: &Context<'_>, method: NmsMethod) -> Vec<Ranked> {
+    let config = context.config;
+    let mut kept = Vec::new();
+    while let Some(best) = (0..group.len()).min_by(|&a, &b| Ranked::cmp_rank(&group[a], &group[b]))
+    {
+        let entry = group.swap_remove(best);
+        for other in &mut group {
+            let overlap = context.overlap(&entry, other).clamp(0.0, 1.0);
+            let factor = match method {
+                NmsMethod::Linear if overlap > config.iou_threshold => 1.0 - overlap,
+                NmsMethod::Gaussian { sigma } => (-overlap * overlap / sigma).exp(),
+                _ => 1.0,
+            };
+            other.candidate.confidence *= factor;
+        }
+        group.retain(|other| other.candidate.confidence > config.confidence_threshold);
+        kept.push(entry);
+    }
+    kept
+}
@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+use std::os::raw::{c_int, c_void};
+
+use crate::error::{YoloError, YoloResult};
+use crate::postprocess::{self, Rules};
+use crate::types::{Frame, InferenceResult, YoloInferenceHandle, YoloResult as YoloResultRaw};
+use crate::yolo::{last_native_error, take_raw_result, Yolo};
+
+/// 流水线推理
+///
+/// 每个槽位独立持有执行上下文、CUDA 流和输入输出缓冲区。帧按顺序轮流提交到各槽位：
+/// 第 N+1 帧的预处理与第 N 帧的推理、第 N-1 帧的 CPU 后处理（NMS、掩码）同时进行，
+/// 结果仍按输入顺序返回。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{Config, Frame, Pipeline, Yolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine"))?;
+///     let mut pipeline = Pipeline::new(&yolo, 3)?;
+///
+///     let frames = ["images/test1.jpg", "images/test2.jpg"]
+///         .iter()
+///         .map(|path| Frame::open(path))
+///         .collect::<Result<Vec<_>, _>>()?;
+///
+///     for result in pipeline.run(frames) {
+///         println!("检测到 {} 个目标", result?.detection_count());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Pipeline<'a> {
+    handle: YoloPipelineHandle,
+    depth: usize,
+    skip_masks: bool,
+    yolo: &'a Yolo,
+}
+
+impl<'a> Pipeline<'a> {
+    /// 创建流水线
+    ///
+    /// # 参数
+    ///
+    /// * `yolo` - 提供引擎的推理器，流水线不会使用它自己的执行上下文
+    /// * `depth` - 槽位数量，即同时在途的帧数，通常取 2 或 3
+    pub fn new(yolo: &'a Yolo, depth: usize) -> YoloResult<Self> {
+        if depth == 0 {
+            return Err(YoloError::InvalidParameter(
+                "流水线深度必须大于 0".to_string(),
+            ));
+        }
+        let handle = unsafe { yolo_pipeline_create(yolo.handle(), depth as c_int) };
+        if handle.is_null() {
+            return Err(last_native_error(YoloError::Initialization));
+        }
+        Ok(Self {
+            handle,
+            depth,
+            skip_masks: false,
+            yolo,
+        })
+    }
+
+    /// 跳过分割掩码解码，只返回检测框
+    pub fn with_skip_masks(mut self, skip_masks: bool) -> Self {
+        self.skip_masks = skip_masks;
+        self
+    }
+
+    /// 槽位数量
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// 对帧序列执行流水线推理，按输入顺序逐个返回结果
+    pub fn run<I>(&mut self, frames: I) -> PipelineRun<'_, 'a, I::IntoIter>
+    where
+        I: IntoIterator<Item = Frame>,
+    {
+        PipelineRun {
+            pipeline: self,
+            frames: frames.into_iter(),
+            in_flight: VecDeque::new(),
+            next_slot: 0,
+        }
+    }
+
+    fn submit(&self, slot: usize, frame: &Frame) -> YoloResult<()> {
+        let ok = unsafe {
+            yolo_pipeline_submit(
+                self.handle,
+                slot as c_int,
+                frame.data().as_ptr(),
+                frame.width(),
+                frame.height(),
+                3,
+            )
+        };
+        if !ok {
+            return Err(last_native_error(YoloError::Inference));
+        }
+        Ok(())
+    }
+
+    fn collect(&self, slot: usize) -> YoloResult<InferenceResult> {
+        if let Some(rules) = self.yolo.rules() {
+            return self.collect_raw(slot, &rules);
+        }
+
+        let mut raw_result = YoloResultRaw::empty();
+        let ok = unsafe {
+            yolo_pipeline_collect(self.handle, slot as c_int, &mut raw_result, self.skip_masks)
+        };
+        if !ok {
+            return Err(last_native_error(YoloError::Inference));
+        }
+        Ok(take_raw_result(&mut raw_result, self.yolo.config().task))
+    }
+
+    /// 取回槽位的原始输出，在 Rust 中执行 NMS 和掩码解码
+    fn collect_raw(&self, slot: usize, rules: &Rules<'_>) -> YoloResult<InferenceResult> {
+        let mut raw_result = YoloResultRaw::empty();
+        let mut output = std::ptr::null();
+        let mut proto = std::ptr::null();
+        let ok = unsafe {
+            yolo_pipeline_collect_raw(
+                self.handle,
+                slot as c_int,
+                &mut raw_result,
+                &mut output,
+                &mut proto,
+            )
+        };
+        if !ok {
+            return Err(last_native_error(YoloError::Inference));
+        }
+
+        // SAFETY: 槽位缓冲区在该槽位再次提交前有效，这里用完后才会提交下一帧
+        let model = self.yolo.model_info();
+        let output = unsafe { std::slice::from_raw_parts(output, model.output_size()) };
+        let proto = (!proto.is_null())
+            .then(|| unsafe { std::slice::from_raw_parts(proto, model.proto_size()) });
+        let mut result = take_raw_result(&mut raw_result, self.yolo.config().task);
+        postprocess::finish(&mut result, output, proto, model, !self.skip_masks, rules)?;
+        Ok(result)
+    }
+}
+
+impl Drop for Pipeline<'_> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe { yolo_pipeline_destroy(self.handle) };
+        }
+    }
+}
+
+enum InFlight {
+    /// 已提交到的槽位
+    Submitted(usize),
+    Failed(YoloError),
+}
+
+/// 流水线推理结果迭代器，由 [`Pipeline::run`] 创建
+pub struct PipelineRun<'p, 'a, I> {
+    pipeline: &'p mut Pipeline<'a>,
+    frames: I,
+    in_flight: VecDeque<InFlight>,
+    next_slot: usize,
+}
+
+impl<I: Iterator<Item = Frame>> Iterator for PipelineRun<'_, '_, I> {
+    type Item = YoloResult<InferenceResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // 先把所有空闲槽位填满，再取回最早的一帧，使 GPU 在 CPU 后处理期间保持忙碌
+        while self.in_flight.len() < self.pipeline.depth {
+            let Some(frame) = self.frames.next() else {
+                break;
+            };
+            let slot = self.next_slot;
+            self.next_slot = (self.next_slot + 1) % self.pipeline.depth;
+            self.in_flight
+                .push_back(match self.pipeline.submit(slot, &frame) {
+                    Ok(()) => InFlight::Submitted(slot),
+                    Err(e) => InFlight::Failed(e),
+                });
+        }
+
+        let outcome = match self.in_flight.pop_front()? {
+            // 帧只在有空闲槽位时才取出并立即提交，没有排队时间
+            InFlight::Submitted(slot) => self.pipeline.collect(slot),
+            InFlight::Failed(e) => Err(e),
+        };
+        Some(self.pipeline.yolo.track(outcome))
+    }
+}
+
+impl<I> Drop for PipelineRun<'_, '_, I> {
+    fn drop(&mut self) {
+        // 提前结束迭代时取回在途帧，保证槽位可以被下一次 run 复用
+        for in_flight in self.in_flight.drain(..) {
+            if let InFlight::Submitted(slot) = in_flight {
+                let _ = self.pipeline.collect(slot);
+            }
+        }
+    }
+}
+
+type YoloPipelineHandle = *mut c_void;
+
+// C API 函数声明
+extern "C" {
+    fn yolo_pipeline_create(handle: YoloInferenceHandle, depth: c_int) -> YoloPipelineHandle;
+    fn yolo_pipeline_destroy(pipeline: YoloPipelineHandle);
+    fn yolo_pipeline_submit(
+        pipeline: YoloPipelineHandle,
+        slot: c_int,
+        image_data: *const u8,
+        width: c_int,
+        height: c_int,
+        channels: c_int,
+    ) -> bool;
+    fn yolo_pipeline_collect(
+        pipeline: YoloPipelineHandle,
+        slot: c_int,
+        result: *mut YoloResultRaw,
+        skip_mask_copy: bool,
+    ) -> bool;
+    fn yolo_pipeline_collect_raw(
+        pipeline: YoloPipelineHandle,
+        slot: c_int,
+        result: *mut YoloResultRaw,
+        output: *mut *const f32,
+        proto: *mut *const f32,
+    ) -> bool;
+}
@@ -0,0 +1,436 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crate::backend::InferenceBackend;
+use crate::error::{YoloError, YoloResult};
+use crate::types::{Config, InferenceResult};
+use crate::yolo::Yolo;
+
+/// 任务调度策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheduling {
+    /// 依次轮流分配给每个工作线程
+    RoundRobin,
+    /// 分配给当前排队和执行中任务最少的工作线程
+    LeastLoaded,
+}
+
+/// 推理池配置
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// 工作线程（推理器实例）数量
+    pub workers: usize,
+    /// 每个工作线程的队列容量
+    pub queue_capacity: usize,
+    /// 调度策略
+    pub scheduling: Scheduling,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            workers: 2,
+            queue_capacity: 8,
+            scheduling: Scheduling::LeastLoaded,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// 创建新的配置
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            ..Self::default()
+        }
+    }
+
+    /// 设置每个工作线程的队列容量
+    pub fn with_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    /// 设置调度策略
+    pub fn with_scheduling(mut self, scheduling: Scheduling) -> Self {
+        self.scheduling = scheduling;
+        self
+    }
+}
+
+type Callback = Box<dyn FnOnce(YoloResult<InferenceResult>) + Send>;
+
+pub(crate) struct Job {
+    image_path: String,
+    callback: Callback,
+    /// 创建时间，用于计算排队时间
+    created: Instant,
+    /// 置位后，尚未开始执行的任务会被直接丢弃
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl Job {
+    pub(crate) fn new<F>(image_path: String, callback: F) -> Self
+    where
+        F: FnOnce(YoloResult<InferenceResult>) + Send + 'static,
+    {
+        Self {
+            image_path,
+            callback: Box::new(callback),
+            created: Instant::now(),
+            cancelled: None,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn with_cancel_flag(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+}
+
+/// 等待队列空位的异步任务
+type CapacityWaiters = Arc<Mutex<Vec<Waker>>>;
+
+struct Worker {
+    sender: Option<SyncSender<Job>>,
+    /// 已排队但尚未开始的任务数
+    queued: Arc<AtomicUsize>,
+    /// 排队和执行中的任务总数
+    pending: Arc<AtomicUsize>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// 推理任务句柄
+///
+/// 通过 [`YoloPool::submit`] 获得，用于等待任务结果。
+pub struct JobHandle {
+    receiver: Receiver<YoloResult<InferenceResult>>,
+}
+
+impl JobHandle {
+    pub(crate) fn new(receiver: Receiver<YoloResult<InferenceResult>>) -> Self {
+        Self { receiver }
+    }
+
+    /// 阻塞等待任务完成
+    pub fn wait(self) -> YoloResult<InferenceResult> {
+        self.receiver
+            .recv()
+            .unwrap_or_else(|_| Err(YoloError::ShutDown("工作线程已退出".to_string())))
+    }
+
+    /// 检查任务是否完成，未完成时返回 `None`
+    pub fn try_wait(&self) -> Option<YoloResult<InferenceResult>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// 推理工作池
+///
+/// 持有多个推理器实例，每个实例运行在独立的工作线程上（各自拥有执行上下文和 CUDA 流）。
+/// 任务通过有界队列提交，队列满时 [`submit`](Self::submit) 阻塞、
+/// [`try_submit`](Self::try_submit) 返回 [`YoloError::QueueFull`]。
+///
+/// 关闭（[`shutdown`](Self::shutdown) 或 drop）时会先处理完所有已提交的任务再退出。
+///
+/// # 示例
+///
+/// ```no_run
+/// use yolo11s_tensorrt_rs::{Config, PoolConfig, YoloPool};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let pool = YoloPool::new(Config::new("models/yolo11s-seg.engine"), PoolConfig::new(2))?;
+///
+///     let jobs: Vec<_> = ["images/test1.jpg", "images/test2.jpg"]
+///         .iter()
+///         .map(|path| pool.submit(*path))
+///         .collect::<Result<_, _>>()?;
+///
+///     for job in jobs {
+///         println!("检测到 {} 个目标", job.wait()?.detection_count());
+///     }
+///
+///     pool.shutdown();
+///     Ok(())
+/// }
+/// ```
+pub struct YoloPool {
+    workers: Vec<Worker>,
+    scheduling: Scheduling,
+    next: AtomicUsize,
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    capacity_waiters: CapacityWaiters,
+}
+
+impl YoloPool {
+    /// 按配置创建 `pool_config.workers` 个推理器组成工作池
+    pub fn new(config: Config, pool_config: PoolConfig) -> YoloResult<Self> {
+        let backends = (0..pool_config.workers)
+            .map(|_| Yolo::new(config.clone()))
+            .collect::<YoloResult<Vec<_>>>()?;
+        Self::from_backends(backends, pool_config)
+    }
+
+    /// 用已有的后端创建工作池
+    ///
+    /// 工作线程数量等于 `backends` 的长度，`pool_config.workers` 被忽略。
+    pub fn from_backends<B: InferenceBackend + 'static>(
+        backends: Vec<B>,
+        pool_config: PoolConfig,
+    ) -> YoloResult<Self> {
+        if backends.is_empty() {
+            return Err(YoloError::InvalidParameter(
+                "工作池至少需要一个推理器".to_string(),
+            ));
+        }
+        if pool_config.queue_capacity == 0 {
+            return Err(YoloError::InvalidParameter(
+                "队列容量必须大于 0".to_string(),
+            ));
+        }
+
+        let capacity_waiters = CapacityWaiters::default();
+        let workers = backends
+            .into_iter()
+            .enumerate()
+            .map(|(index, backend)| {
+                spawn_worker(
+                    index,
+                    backend,
+                    pool_config.queue_capacity,
+                    Arc::clone(&capacity_waiters),
+                )
+            })
+            .collect::<YoloResult<Vec<_>>>()?;
+
+        Ok(Self {
+            workers,
+            scheduling: pool_config.scheduling,
+            next: AtomicUsize::new(0),
+            capacity_waiters,
+        })
+    }
+
+    /// 提交任务，返回用于等待结果的句柄
+    ///
+    /// 目标工作线程的队列已满时阻塞，直到有空位。
+    pub fn submit(&self, image_path: impl Into<String>) -> YoloResult<JobHandle> {
+        let (sender, receiver) = mpsc::channel();
+        self.submit_with(image_path, move |result| {
+            let _ = sender.send(result);
+        })?;
+        Ok(JobHandle { receiver })
+    }
+
+    /// 提交任务，完成后在工作线程上调用 `callback`
+    ///
+    /// 目标工作线程的队列已满时阻塞，直到有空位。
+    pub fn submit_with<F>(&self, image_path: impl Into<String>, callback: F) -> YoloResult<()>
+    where
+        F: FnOnce(YoloResult<InferenceResult>) + Send + 'static,
+    {
+        self.enqueue(Job::new(image_path.into(), callback), true)
+    }
+
+    /// 尝试提交任务，队列已满时立即返回 [`YoloError::QueueFull`]
+    pub fn try_submit(&self, image_path: impl Into<String>) -> YoloResult<JobHandle> {
+        let (sender, receiver) = mpsc::channel();
+        let job = Job::new(image_path.into(), move |result| {
+            let _ = sender.send(result);
+        });
+        self.try_submit_job(job)?;
+        Ok(JobHandle { receiver })
+    }
+
+    /// 非阻塞地提交任务
+    pub(crate) fn try_submit_job(&self, job: Job) -> YoloResult<()> {
+        self.enqueue(job, false)
+    }
+
+    /// 注册在队列出现空位时需要唤醒的任务
+    #[cfg(feature = "async")]
+    pub(crate) fn register_capacity_waker(&self, waker: &Waker) {
+        let mut waiters = self
+            .capacity_waiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !waiters.iter().any(|w| w.will_wake(waker)) {
+            waiters.push(waker.clone());
+        }
+    }
+
+    fn enqueue(&self, job: Job, blocking: bool) -> YoloResult<()> {
+        let worker = &self.workers[self.select_worker()];
+        worker.pending.fetch_add(1, Ordering::SeqCst);
+        worker.queued.fetch_add(1, Ordering::SeqCst);
+
+        let sent = if blocking {
+            worker
+                .sender()
+                .send(job)
+                .map_err(|_| YoloError::ShutDown("工作线程已退出".to_string()))
+        } else {
+            worker.sender().try_send(job).map_err(|e| match e {
+                TrySendError::Full(_) => YoloError::QueueFull("推理队列已满".to_string()),
+                TrySendError::Disconnected(_) => YoloError::ShutDown("工作线程已退出".to_string()),
+            })
+        };
+
+        if sent.is_err() {
+            worker.queued.fetch_sub(1, Ordering::SeqCst);
+            worker.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+        sent
+    }
+
+    /// 工作线程数量
+    pub fn workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// 所有队列中等待执行的任务总数
+    pub fn queue_depth(&self) -> usize {
+        self.workers
+            .iter()
+            .map(|w| w.queued.load(Ordering::SeqCst))
+            .sum()
+    }
+
+    /// 每个工作线程排队和执行中的任务数
+    pub fn worker_loads(&self) -> Vec<usize> {
+        self.workers
+            .iter()
+            .map(|w| w.pending.load(Ordering::SeqCst))
+            .collect()
+    }
+
+    /// 关闭工作池
+    ///
+    /// 停止接收新任务，等待所有已提交的任务处理完毕后返回。
+    pub fn shutdown(mut self) {
+        self.close();
+    }
+
+    fn select_worker(&self) -> usize {
+        match self.scheduling {
+            Scheduling::RoundRobin => {
+                self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len()
+            }
+            Scheduling::LeastLoaded => {
+                // 负载相同时从轮转位置开始选择，避免总是压在第一个工作线程上
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..self.workers.len())
+                    .map(|offset| (start + offset) % self.workers.len())
+                    .min_by_key(|&i| self.workers[i].pending.load(Ordering::SeqCst))
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        for worker in &mut self.workers {
+            worker.sender.take();
+        }
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+impl Drop for YoloPool {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Worker {
+    fn sender(&self) -> &SyncSender<Job> {
+        self.sender
+            .as_ref()
+            .expect("sender is only taken while closing the pool")
+    }
+}
+
+fn spawn_worker<B: InferenceBackend + 'static>(
+    index: usize,
+    mut backend: B,
+    queue_capacity: usize,
+    capacity_waiters: CapacityWaiters,
+) -> YoloResult<Worker> {
+    let (sender, receiver) = mpsc::sync_channel::<Job>(queue_capacity);
+    let queued = Arc::new(AtomicUsize::new(0));
+    let pending = Arc::new(AtomicUsize::new(0));
+
+    let thread = {
+        let queued = Arc::clone(&queued);
+        let pending = Arc::clone(&pending);
+        thread::Builder::new()
+            .name(format!("yolo-pool-{}", index))
+            .spawn(move || {
+                // 所有发送端关闭后，recv 会在队列清空后才返回错误，从而完成排空
+                while let Ok(job) = receiver.recv() {
+                    queued.fetch_sub(1, Ordering::SeqCst);
+                    wake_all(&capacity_waiters);
+
+                    if job.is_cancelled() {
+                        pending.fetch_sub(1, Ordering::SeqCst);
+                        continue;
+                    }
+
+                    let queue_wait_ms = job.created.elapsed().as_secs_f64() * 1000.0;
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        backend.inference_queued(&job.image_path, queue_wait_ms)
+                    }))
+                    .unwrap_or_else(|_| {
+                        Err(YoloError::Inference(format!(
+                            "推理线程 panic: {}",
+                            job.image_path
+                        )))
+                    });
+                    let failed = result.is_err();
+                    pending.fetch_sub(1, Ordering::SeqCst);
+                    // 回调由调用方提供，它的 panic 不能带走工作线程
+                    let image_path = job.image_path;
+                    let callback = job.callback;
+                    if panic::catch_unwind(AssertUnwindSafe(|| callback(result))).is_err() {
+                        log::error!("工作线程 {} 的回调 panic: {}", index, image_path);
+                    }
+
+                    // CUDA 错误使后端失效时立即恢复，后面排队的任务才能继续执行
+                    if failed && backend.is_poisoned() {
+                        if let Err(e) = backend.recover() {
+                            log::error!("工作线程 {} 恢复推理器失败: {}", index, e);
+                        }
+                    }
+                }
+            })?
+    };
+
+    Ok(Worker {
+        sender: Some(sender),
+        queued,
+        pending,
+        thread: Some(thread),
+    })
+}
+
+fn wake_all(waiters: &CapacityWaiters) {
+    let waiters = std::mem::take(&mut *waiters.lock().unwrap_or_else(|e| e.into_inner()));
+    for waker in waiters {
+        waker.wake();
+    }
+}
@@ -0,0 +1,283 @@
+//! Rust 后处理
+//!
+//! 配置了 [`Config::nms`](crate::Config::nms) 或 [`Config::classes`](crate::Config::classes) 时，
+//! C++ 核心只执行预处理、引擎推理和结果拷贝，由这里解析原始输出、按类别过滤、执行 NMS 并解码分割掩码。
+
+use std::borrow::Cow;
+use std::os::raw::c_int;
+use std::time::Instant;
+
+use crate::classes::ResolvedClasses;
+use crate::decode::{decode_masks, Proto, RawCandidate, RawOutput};
+use crate::error::{YoloError, YoloResult};
+use crate::nms::{NmsConfig, Overlap};
+use crate::types::{
+    Config, Detection, Frame, InferenceResult, ModelInfo, Task, YoloInferenceHandle,
+    YoloResult as YoloResultRaw,
+};
+#[cfg(feature = "tracing")]
+use crate::yolo::stage_spans;
+use crate::yolo::{last_native_error, take_raw_result, Yolo};
+
+/// Rust 后处理的规则
+pub(crate) struct Rules<'a> {
+    nms: Cow<'a, NmsConfig>,
+    classes: &'a ResolvedClasses,
+    task: Task,
+}
+
+impl<'a> Rules<'a> {
+    /// 配置了 NMS 或类别规则，或者任务不是分割时返回 `Some`，否则使用 C++ 核心的后处理
+    ///
+    /// `classes` 是 `config.classes` 解析后的结果。
+    pub(crate) fn new(config: &'a Config, classes: &'a ResolvedClasses) -> Option<Self> {
+        let needed =
+            config.nms.is_some() || !config.classes.is_empty() || config.task != Task::Segment;
+        needed.then(|| Self::always(config, classes))
+    }
+
+    /// 总是由 Rust 完成后处理的规则，没有配置 NMS 时使用默认参数
+    fn always(config: &'a Config, classes: &'a ResolvedClasses) -> Self {
+        let mut nms = match &config.nms {
+            Some(nms) => Cow::Borrowed(nms),
+            None => Cow::Owned(NmsConfig::default()),
+        };
+        // 旋转框的 bbox 是中心和宽高，只有旋转框交并比有意义
+        if config.task == Task::Obb && nms.overlap != Overlap::Rotated {
+            nms.to_mut().overlap = Overlap::Rotated;
+        }
+        Self {
+            nms,
+            classes,
+            task: config.task,
+        }
+    }
+}
+
+/// 对单张图片保存下来的原始输出执行 Rust 后处理
+///
+/// 与推理器内部的 Rust 后处理相同：解析原始输出、按 `config` 的类别规则过滤、
+/// 执行 NMS，分割模型再解码掩码。没有配置 NMS 时使用默认参数。
+/// `proto` 为 `None`（引擎没有分割原型输出）时不生成掩码。
+///
+/// # 示例
+///
+/// ```no_run
+/// # use yolo11s_tensorrt_rs::{postprocess_output, Config, ModelInfo};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let (output, proto) = (vec![0.0f32; ModelInfo::default().output_size()], None);
+/// let config = Config::new("models/yolo11s-seg.engine");
+/// let result = postprocess_output(&output, proto, &ModelInfo::default(), &config)?;
+/// println!("检测到 {} 个目标", result.detection_count());
+/// # Ok(())
+/// # }
+/// ```
+pub fn postprocess_output(
+    output: &[f32],
+    proto: Option<&[f32]>,
+    model: &ModelInfo,
+    config: &Config,
+) -> YoloResult<InferenceResult> {
+    if let Some(nms) = &config.nms {
+        nms.validate()?;
+    }
+    let classes = config.classes.resolve()?;
+    let rules = Rules::always(config, &classes);
+    let mut result = InferenceResult::new();
+    finish(&mut result, output, proto, model, true, &rules)?;
+    Ok(result)
+}
+
+/// 批量推理，由 Rust 完成后处理
+pub(crate) fn inference_batch(
+    yolo: &Yolo,
+    frames: &[Frame],
+    rules: &Rules<'_>,
+) -> YoloResult<Vec<InferenceResult>> {
+    let images: Vec<*const u8> = frames.iter().map(|f| f.data().as_ptr()).collect();
+    let widths: Vec<c_int> = frames.iter().map(|f| f.width()).collect();
+    let heights: Vec<c_int> = frames.iter().map(|f| f.height()).collect();
+    let mut raw_results: Vec<YoloResultRaw> =
+        frames.iter().map(|_| YoloResultRaw::empty()).collect();
+
+    let ok = unsafe {
+        yolo_inference_raw_batch_from_memory(
+            yolo.handle(),
+            images.as_ptr(),
+            widths.as_ptr(),
+            heights.as_ptr(),
+            frames.len() as c_int,
+            raw_results.as_mut_ptr(),
+        )
+    };
+    if !ok {
+        return Err(last_native_error(YoloError::Inference));
+    }
+
+    let model = yolo.model_info();
+    let (output, proto) = host_outputs(yolo, frames.len())?;
+    let proto_size = model.proto_size();
+    raw_results
+        .iter_mut()
+        .zip(output.chunks_exact(model.output_size()))
+        .enumerate()
+        .map(|(i, (raw_result, output))| {
+            let proto = proto.map(|proto| &proto[i * proto_size..(i + 1) * proto_size]);
+            #[cfg(feature = "tracing")]
+            stage_spans(raw_result, false);
+            let mut result = take_raw_result(raw_result, rules.task);
+            finish(&mut result, output, proto, model, true, rules)?;
+            Ok(result)
+        })
+        .collect()
+}
+
+/// 推理器最近一次推理拷贝回主机的原始输出，下一次推理前有效
+///
+/// 引擎没有分割原型输出时，分割原型为 `None`。
+pub(crate) fn host_outputs(yolo: &Yolo, batch_size: usize) -> YoloResult<(&[f32], Option<&[f32]>)> {
+    let mut output = std::ptr::null();
+    let mut proto = std::ptr::null();
+    let ok = unsafe { yolo_get_host_outputs(yolo.handle(), &mut output, &mut proto) };
+    if !ok {
+        return Err(last_native_error(YoloError::Inference));
+    }
+    let model = yolo.model_info();
+    // SAFETY: 主机缓冲区按最大批次分配，在推理器重新创建前一直有效；调用方在下一次推理前用完
+    Ok(unsafe {
+        (
+            std::slice::from_raw_parts(output, batch_size * model.output_size()),
+            (!proto.is_null())
+                .then(|| std::slice::from_raw_parts(proto, batch_size * model.proto_size())),
+        )
+    })
+}
+
+/// 对单张图片的原始输出执行 NMS 和掩码解码，写入检测结果并累加后处理耗时
+///
+/// `decode_masks` 为 `false` 时跳过掩码解码，分割原型仍用于掩码交并比 NMS。
+/// 没有分割原型（检测模型）时不生成掩码。
+pub(crate) fn finish(
+    result: &mut InferenceResult,
+    output: &[f32],
+    proto: Option<&[f32]>,
+    model: &ModelInfo,
+    decode_masks: bool,
+    rules: &Rules<'_>,
+) -> YoloResult<()> {
+    let start = Instant::now();
+    let proto = proto.map(|proto| proto_view(proto, model)).transpose()?;
+    let candidates = {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("nms").entered();
+        select(output, proto.as_ref(), model, rules)?
+    };
+    result.detections = {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::info_span!("mask_decode", detection_count = candidates.len()).entered();
+        to_detections(candidates, proto.as_ref().filter(|_| decode_masks), rules)
+    };
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    result.postprocess_time_ms += elapsed_ms;
+    result.total_time_ms += elapsed_ms;
+    Ok(())
+}
+
+/// 单张图片的分割原型
+pub(crate) fn proto_view<'a>(proto: &'a [f32], model: &ModelInfo) -> YoloResult<Proto<'a>> {
+    Ok(Proto::new(proto, model.proto_width, model.proto_height)?.with_stride(model.proto_stride()))
+}
+
+/// 解析原始输出，按类别过滤后执行 NMS
+pub(crate) fn select(
+    output: &[f32],
+    proto: Option<&Proto<'_>>,
+    model: &ModelInfo,
+    rules: &Rules<'_>,
+) -> YoloResult<Vec<RawCandidate>> {
+    let output =
+        RawOutput::parse_with_capacity(output, model.max_detections)?.with_task(rules.task);
+    if output.overflowed() {
+        log::warn!(
+            "候选框数量 {} 超过输出缓冲区容量 {}，多余的已被丢弃",
+            output.reported_count(),
+            output.len()
+        );
+    }
+
+    let mut invalid = 0;
+    let candidates = output
+        .iter()
+        .filter_map(|candidate| {
+            candidate
+                .map_err(|e| {
+                    invalid += 1;
+                    log::debug!("{}", e);
+                })
+                .ok()
+        })
+        .collect();
+    if invalid > 0 {
+        log::warn!("跳过 {} 个包含无效数值的候选框", invalid);
+    }
+    Ok(rules.classes.apply(candidates, proto, &rules.nms))
+}
+
+/// 按任务类型把候选框转换为检测结果，分割模型的 `proto` 不为 `None` 时并行解码分割掩码
+pub(crate) fn to_detections(
+    candidates: Vec<RawCandidate>,
+    proto: Option<&Proto<'_>>,
+    rules: &Rules<'_>,
+) -> Vec<Detection> {
+    let detection = |candidate: &RawCandidate| to_detection(candidate, rules.task);
+    match proto.filter(|_| rules.task == Task::Segment) {
+        Some(proto) => {
+            let width = (proto.width() * proto.stride()) as i32;
+            let height = (proto.height() * proto.stride()) as i32;
+            candidates
+                .iter()
+                .zip(decode_masks(&candidates, proto))
+                .map(|(candidate, mask)| detection(candidate).with_mask(mask, width, height))
+                .collect()
+        }
+        None => candidates.iter().map(detection).collect(),
+    }
+}
+
+/// 按任务类型把单个候选框转换为不带掩码的检测结果
+pub(crate) fn to_detection(candidate: &RawCandidate, task: Task) -> Detection {
+    match task {
+        Task::Detect | Task::Segment => {
+            Detection::new(candidate.bbox, candidate.confidence, candidate.class_id)
+        }
+        Task::Pose => Detection::new(candidate.bbox, candidate.confidence, candidate.class_id)
+            .with_keypoints(candidate.keypoints()),
+        Task::Obb => {
+            let rotated_box = candidate.rotated_box();
+            Detection::new(
+                rotated_box.bounding_box(),
+                candidate.confidence,
+                candidate.class_id,
+            )
+            .with_rotated_box(rotated_box)
+        }
+    }
+}
+
+// C API 函数声明
+extern "C" {
+    fn yolo_inference_raw_batch_from_memory(
+        handle: YoloInferenceHandle,
+        images: *const *const u8,
+        widths: *const c_int,
+        heights: *const c_int,
+        batch_size: c_int,
+        results: *mut YoloResultRaw,
+    ) -> bool;
+    fn yolo_get_host_outputs(
+        handle: YoloInferenceHandle,
+        output: *mut *const f32,
+        proto: *mut *const f32,
+    ) -> bool;
+}
@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::backend::InferenceBackend;
+use crate::error::YoloResult;
+use crate::types::{Config, InferenceResult};
+use crate::yolo::Yolo;
+
+/// 可在线程间共享的推理器
+///
+/// 内部用互斥锁串行化对同一执行上下文的访问，实现了 `Send + Sync + Clone`，
+/// 可以放进 `Arc` 或直接克隆到多个工作线程中使用。
+/// 所有克隆共享同一个后端，同一时刻只有一个线程在执行推理。
+///
+/// 需要真正并行推理时，应为每个线程创建独立的 [`Yolo`] 实例。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::thread;
+/// use yolo11s_tensorrt_rs::{Config, SharedYolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = SharedYolo::new(Config::new("models/yolo11s-seg.engine"))?;
+///
+///     let handles: Vec<_> = ["images/test1.jpg", "images/test2.jpg"]
+///         .into_iter()
+///         .map(|path| {
+///             let yolo = yolo.clone();
+///             thread::spawn(move || yolo.inference(path))
+///         })
+///         .collect();
+///
+///     for handle in handles {
+///         let result = handle.join().unwrap()?;
+///         println!("检测到 {} 个目标", result.detection_count());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct SharedYolo<B: InferenceBackend = Yolo> {
+    inner: Arc<Mutex<B>>,
+}
+
+impl SharedYolo<Yolo> {
+    /// 创建新的共享推理器
+    pub fn new(config: Config) -> YoloResult<Self> {
+        Ok(Self::from_backend(Yolo::new(config)?))
+    }
+}
+
+impl<B: InferenceBackend> SharedYolo<B> {
+    /// 用已有的后端创建共享推理器
+    pub fn from_backend(backend: B) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(backend)),
+        }
+    }
+
+    /// 执行推理
+    ///
+    /// 如果其他线程正在推理，会阻塞等待直到获得执行上下文。
+    pub fn inference(&self, image_path: &str) -> YoloResult<InferenceResult> {
+        self.lock().inference(image_path)
+    }
+
+    /// 在持有锁的情况下访问后端
+    ///
+    /// 用于调用 trait 之外的方法，例如 [`Yolo::save_result_image`]。
+    pub fn with_backend<R>(&self, f: impl FnOnce(&mut B) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, B> {
+        // 推理过程中发生 panic 不会破坏原生上下文，忽略锁中毒继续使用
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<B: InferenceBackend> Clone for SharedYolo<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
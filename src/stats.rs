@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::YoloError;
+use crate::types::InferenceResult;
+
+/// 直方图桶的上界（毫秒），最后还有一个 `+Inf` 桶
+pub const LATENCY_BUCKETS_MS: [f64; 12] = [
+    0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0,
+];
+
+/// 吞吐量滑动窗口的最大长度（秒）
+const THROUGHPUT_WINDOW_SECS: usize = 60;
+
+/// 按秒计数的槽位数：窗口内的完整秒再加上正在计数的当前秒
+const COMPLETION_SLOTS: usize = THROUGHPUT_WINDOW_SECS + 1;
+
+/// 统计的推理阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// 端到端总时间
+    Total,
+    /// 图片读取
+    ImageRead,
+    /// 预处理（主机侧）
+    Preprocess,
+    /// TensorRT 推理提交（主机侧）
+    Tensorrt,
+    /// 后处理
+    Postprocess,
+    /// 结果复制（主机侧）
+    ResultCopy,
+    /// GPU 预处理
+    GpuPreprocess,
+    /// GPU 引擎执行
+    GpuTensorrt,
+    /// GPU 输出拷贝
+    GpuCopy,
+    /// 排队等待
+    QueueWait,
+}
+
+impl Stage {
+    /// 所有阶段，按流水线顺序排列
+    pub const ALL: [Stage; 10] = [
+        Stage::Total,
+        Stage::ImageRead,
+        Stage::Preprocess,
+        Stage::Tensorrt,
+        Stage::Postprocess,
+        Stage::ResultCopy,
+        Stage::GpuPreprocess,
+        Stage::GpuTensorrt,
+        Stage::GpuCopy,
+        Stage::QueueWait,
+    ];
+
+    /// 阶段名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Total => "total",
+            Stage::ImageRead => "image_read",
+            Stage::Preprocess => "preprocess",
+            Stage::Tensorrt => "tensorrt",
+            Stage::Postprocess => "postprocess",
+            Stage::ResultCopy => "result_copy",
+            Stage::GpuPreprocess => "gpu_preprocess",
+            Stage::GpuTensorrt => "gpu_tensorrt",
+            Stage::GpuCopy => "gpu_copy",
+            Stage::QueueWait => "queue_wait",
+        }
+    }
+
+    fn time_ms(&self, result: &InferenceResult) -> f64 {
+        match self {
+            Stage::Total => result.total_time_ms,
+            Stage::ImageRead => result.image_read_time_ms,
+            Stage::Preprocess => result.preprocess_time_ms,
+            Stage::Tensorrt => result.tensorrt_time_ms,
+            Stage::Postprocess => result.postprocess_time_ms,
+            Stage::ResultCopy => result.result_copy_time_ms,
+            Stage::GpuPreprocess => result.gpu_preprocess_time_ms,
+            Stage::GpuTensorrt => result.gpu_tensorrt_time_ms,
+            Stage::GpuCopy => result.gpu_copy_time_ms,
+            Stage::QueueWait => result.queue_wait_ms,
+        }
+    }
+}
+
+/// 固定桶直方图
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// 每个桶的样本数（非累计），长度为 `LATENCY_BUCKETS_MS.len() + 1`，最后一个是 `+Inf` 桶
+    pub counts: Vec<u64>,
+    /// 样本总数
+    pub count: u64,
+    /// 样本之和（毫秒）
+    pub sum_ms: f64,
+    /// 最大样本（毫秒）
+    pub max_ms: f64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+            count: 0,
+            sum_ms: 0.0,
+            max_ms: 0.0,
+        }
+    }
+}
+
+impl Histogram {
+    /// 记录一个样本
+    pub fn observe(&mut self, value_ms: f64) {
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| value_ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += value_ms;
+        self.max_ms = self.max_ms.max(value_ms);
+    }
+
+    /// 平均值（毫秒）
+    pub fn mean_ms(&self) -> f64 {
+        if self.count > 0 {
+            self.sum_ms / self.count as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// 累计桶计数，`(上界, 小于等于上界的样本数)`，最后一项的上界为无穷大
+    pub fn cumulative(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        LATENCY_BUCKETS_MS
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY))
+            .zip(&self.counts)
+            .map(|(bound, count)| {
+                total += count;
+                (bound, total)
+            })
+            .collect()
+    }
+
+    /// 估算分位数（毫秒），返回样本所在桶的上界，落在 `+Inf` 桶时返回最大样本
+    pub fn quantile_ms(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        self.cumulative()
+            .into_iter()
+            .find(|&(_, total)| total >= rank)
+            .map_or(self.max_ms, |(bound, _)| bound.min(self.max_ms))
+    }
+}
+
+/// 某一时刻的统计快照
+#[derive(Debug, Clone)]
+pub struct PerfSnapshot {
+    /// 自创建或上次重置以来的时间
+    pub uptime: Duration,
+    /// 成功的推理次数
+    pub inferences: u64,
+    /// 失败的推理次数
+    pub errors: u64,
+    /// 按错误类别（[`YoloError::kind`]）统计的失败次数
+    pub errors_by_kind: BTreeMap<&'static str, u64>,
+    /// 检测到的目标总数
+    pub detections: u64,
+    /// 每个类别的检测数量
+    pub detections_per_class: BTreeMap<i32, u64>,
+    /// 各阶段的耗时直方图
+    pub stages: BTreeMap<Stage, Histogram>,
+    /// 最近 1 秒的吞吐量（次/秒）
+    pub throughput_1s: f64,
+    /// 最近 10 秒的吞吐量（次/秒）
+    pub throughput_10s: f64,
+    /// 最近 60 秒的吞吐量（次/秒）
+    pub throughput_60s: f64,
+}
+
+impl PerfSnapshot {
+    /// 指定阶段的直方图
+    pub fn stage(&self, stage: Stage) -> &Histogram {
+        &self.stages[&stage]
+    }
+
+    /// 错误率
+    pub fn error_rate(&self) -> f64 {
+        let total = self.inferences + self.errors;
+        if total > 0 {
+            self.errors as f64 / total as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    started: Instant,
+    inferences: u64,
+    errors: u64,
+    errors_by_kind: BTreeMap<&'static str, u64>,
+    detections: u64,
+    detections_per_class: BTreeMap<i32, u64>,
+    stages: BTreeMap<Stage, Histogram>,
+    /// 按秒计数的环形缓冲区：`(秒序号, 完成次数)`
+    completions: [(u64, u64); COMPLETION_SLOTS],
+}
+
+impl Inner {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            inferences: 0,
+            errors: 0,
+            errors_by_kind: BTreeMap::new(),
+            detections: 0,
+            detections_per_class: BTreeMap::new(),
+            stages: Stage::ALL
+                .iter()
+                .map(|&stage| (stage, Histogram::default()))
+                .collect(),
+            completions: [(u64::MAX, 0); COMPLETION_SLOTS],
+        }
+    }
+
+    fn current_second(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+
+    fn count_completion(&mut self) {
+        self.count_completion_at(self.current_second());
+    }
+
+    fn count_completion_at(&mut self, second: u64) {
+        let slot = &mut self.completions[second as usize % COMPLETION_SLOTS];
+        if slot.0 != second {
+            *slot = (second, 0);
+        }
+        slot.1 += 1;
+    }
+
+    fn throughput(&self, window: Duration) -> f64 {
+        self.throughput_at(window, self.started.elapsed().as_secs_f64())
+    }
+
+    /// 创建后经过 `elapsed` 秒时的吞吐量
+    fn throughput_at(&self, window: Duration, elapsed: f64) -> f64 {
+        let secs = (window.as_secs() as usize).clamp(1, THROUGHPUT_WINDOW_SECS) as u64;
+        let now = elapsed as u64;
+
+        // 运行时间不足一个窗口时，用已运行的时间作分母
+        if elapsed < secs as f64 {
+            let count: u64 = self
+                .completions
+                .iter()
+                .filter(|&&(second, _)| second != u64::MAX)
+                .map(|&(_, count)| count)
+                .sum();
+            return if elapsed > 0.0 {
+                count as f64 / elapsed
+            } else {
+                0.0
+            };
+        }
+
+        // 只统计已经结束的完整秒
+        let count: u64 = self
+            .completions
+            .iter()
+            .filter(|&&(second, _)| second != u64::MAX && second >= now - secs && second < now)
+            .map(|&(_, count)| count)
+            .sum();
+        count as f64 / secs as f64
+    }
+}
+
+/// 推理性能统计收集器
+///
+/// 推理器在每次推理后自动记录各阶段耗时、检测数量和错误次数。
+/// 多个推理器可以共享同一个收集器（见 [`Config::with_stats`](crate::Config::with_stats)），
+/// 例如让工作池中的所有工作线程汇总到一起。
+///
+/// 内部使用一把互斥锁，每次记录只做几十次整数加法，开销远小于一次推理。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use yolo11s_tensorrt_rs::{Stage, Yolo};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let yolo = Yolo::with_engine("models/yolo11s-seg.engine")?;
+///     yolo.inference("images/test.jpg")?;
+///
+///     let snapshot = yolo.stats().snapshot();
+///     println!("推理次数: {}", snapshot.inferences);
+///     println!("GPU 推理 p95: {:.2}ms", snapshot.stage(Stage::GpuTensorrt).quantile_ms(0.95));
+///     println!("最近 10 秒 FPS: {:.1}", yolo.stats().throughput(Duration::from_secs(10)));
+///
+///     yolo.stats().reset();
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PerfStats {
+    inner: Mutex<Inner>,
+}
+
+impl Default for PerfStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerfStats {
+    /// 创建空的收集器
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner::new()),
+        }
+    }
+
+    /// 记录一次成功的推理
+    pub fn record(&self, result: &InferenceResult) {
+        let mut inner = self.lock();
+        inner.inferences += 1;
+        inner.detections += result.detections.len() as u64;
+        for detection in &result.detections {
+            *inner
+                .detections_per_class
+                .entry(detection.class_id)
+                .or_insert(0) += 1;
+        }
+        for (stage, histogram) in inner.stages.iter_mut() {
+            histogram.observe(stage.time_ms(result));
+        }
+        inner.count_completion();
+    }
+
+    /// 记录一次失败的推理
+    pub fn record_error(&self, error: &YoloError) {
+        let mut inner = self.lock();
+        inner.errors += 1;
+        *inner.errors_by_kind.entry(error.kind()).or_insert(0) += 1;
+    }
+
+    /// 最近 `window` 内的吞吐量（次/秒），窗口按整秒计算，最长 60 秒
+    pub fn throughput(&self, window: Duration) -> f64 {
+        self.lock().throughput(window)
+    }
+
+    /// 获取统计快照
+    pub fn snapshot(&self) -> PerfSnapshot {
+        let inner = self.lock();
+        PerfSnapshot {
+            uptime: inner.started.elapsed(),
+            inferences: inner.inferences,
+            errors: inner.errors,
+            errors_by_kind: inner.errors_by_kind.clone(),
+            detections: inner.detections,
+            detections_per_class: inner.detections_per_class.clone(),
+            stages: inner.stages.clone(),
+            throughput_1s: inner.throughput(Duration::from_secs(1)),
+            throughput_10s: inner.throughput(Duration::from_secs(10)),
+            throughput_60s: inner.throughput(Duration::from_secs(60)),
+        }
+    }
+
+    /// 清空所有统计
+    pub fn reset(&self) {
+        *self.lock() = Inner::new();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_window_keeps_oldest_second() {
+        // 每秒完成一次，当前秒（第 60 秒）的计数不能覆盖窗口内最早的第 0 秒
+        let mut inner = Inner::new();
+        for second in 0..=60 {
+            inner.count_completion_at(second);
+        }
+        assert_eq!(inner.throughput_at(Duration::from_secs(60), 60.5), 1.0);
+        assert_eq!(inner.throughput_at(Duration::from_secs(10), 60.5), 1.0);
+
+        inner.count_completion_at(61);
+        assert_eq!(inner.throughput_at(Duration::from_secs(60), 61.5), 1.0);
+    }
+}
@@ -0,0 +1,431 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::backend::InferenceBackend;
+use crate::error::{YoloError, YoloResult};
+use crate::types::{Config, InferenceResult};
+use crate::yolo::Yolo;
+
+/// 监管器配置
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// 失败后最多重试的次数（不含第一次）
+    pub max_retries: usize,
+    /// 第一次重试前的等待时间，之后每次加倍
+    pub initial_backoff: Duration,
+    /// 重试等待时间的上限
+    pub max_backoff: Duration,
+    /// 连续失败多少次后重建推理器
+    pub recreate_after: usize,
+    /// 单次推理的超时时间，`None` 表示不检测
+    pub watchdog: Option<Duration>,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            recreate_after: 3,
+            watchdog: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// 设置最大重试次数
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// 设置重试等待时间及其上限
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// 设置连续失败多少次后重建推理器
+    pub fn with_recreate_after(mut self, recreate_after: usize) -> Self {
+        self.recreate_after = recreate_after;
+        self
+    }
+
+    /// 设置单次推理的超时时间
+    pub fn with_watchdog(mut self, watchdog: Option<Duration>) -> Self {
+        self.watchdog = watchdog;
+        self
+    }
+
+    fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// 推理器健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthState {
+    /// 最近一次推理成功
+    Healthy,
+    /// 最近的推理失败，正在重试
+    Degraded,
+    /// 正在恢复或重建推理器
+    Recovering,
+    /// 重建推理器失败，下次推理时会再次尝试
+    Failed,
+}
+
+/// 健康状态变化
+#[derive(Debug, Clone)]
+pub struct HealthTransition {
+    /// 原状态
+    pub from: HealthState,
+    /// 新状态
+    pub to: HealthState,
+    /// 触发变化的错误，恢复正常时为 `None`
+    pub error: Option<YoloError>,
+}
+
+/// 监管统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SupervisorStats {
+    /// 重试次数
+    pub retries: u64,
+    /// 看门狗超时次数
+    pub timeouts: u64,
+    /// 原地恢复（[`InferenceBackend::recover`]）成功的次数
+    pub recoveries: u64,
+    /// 重建推理器的次数
+    pub recreations: u64,
+}
+
+type Factory<B> = Box<dyn FnMut() -> YoloResult<B> + Send>;
+type HealthCallback = Box<dyn Fn(&HealthTransition) + Send>;
+
+enum Request {
+    Inference(String, Sender<Reply>),
+    Recover(Sender<YoloResult<()>>),
+}
+
+/// 推理结果和推理后后端是否失效
+type Reply = (YoloResult<InferenceResult>, bool);
+
+/// 独占后端的工作线程，使看门狗可以放弃卡住的推理
+struct Worker {
+    sender: Option<Sender<Request>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn spawn<B: InferenceBackend + 'static>(mut backend: B) -> YoloResult<Self> {
+        let (sender, receiver) = mpsc::channel::<Request>();
+        let thread = thread::Builder::new()
+            .name("yolo-supervised".to_string())
+            .spawn(move || serve(&mut backend, receiver))?;
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    fn send(&self, request: Request) -> YoloResult<()> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(request).ok())
+            .ok_or_else(|| YoloError::ShutDown("推理线程已退出".to_string()))
+    }
+
+    /// 放弃卡住的线程：断开通道但不等待，推理返回后线程自行退出并释放后端
+    fn abandon(mut self) {
+        self.sender.take();
+        self.thread.take();
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve<B: InferenceBackend>(backend: &mut B, receiver: Receiver<Request>) {
+    while let Ok(request) = receiver.recv() {
+        match request {
+            Request::Inference(image_path, reply) => {
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| backend.inference(&image_path)))
+                        .unwrap_or_else(|_| {
+                            Err(YoloError::Inference(format!(
+                                "推理线程 panic: {}",
+                                image_path
+                            )))
+                        });
+                let _ = reply.send((result, backend.is_poisoned()));
+            }
+            Request::Recover(reply) => {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| backend.recover()))
+                    .unwrap_or_else(|_| Err(YoloError::Unknown("恢复时 panic".to_string())));
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+/// 带重试、看门狗和自动重建的推理器监管
+///
+/// 推理在独占后端的工作线程上执行，调用方按 [`SupervisorConfig`] 等待结果：
+///
+/// - 可重试的错误（CUDA、TensorRT、内存不足、超时等）按指数退避重试，
+///   文件不存在、图片解码失败和参数错误直接返回；
+/// - 后端因 CUDA 错误失效时先原地恢复，恢复失败再用工厂函数重建；
+/// - 连续失败达到 `recreate_after` 次，或推理超过看门狗时间，都会重建推理器。
+///   卡住的工作线程会被放弃，等 GPU 返回后自行退出，期间旧推理器仍占用显存；
+/// - 健康状态变化通过 [`on_health_change`](Self::on_health_change) 注册的回调通知。
+///
+/// 监管器本身实现了 [`InferenceBackend`]，可以放进 [`YoloPool`](crate::YoloPool) 等并发封装中。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use yolo11s_tensorrt_rs::{Config, Supervisor, SupervisorConfig};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let config = SupervisorConfig::default()
+///         .with_max_retries(3)
+///         .with_watchdog(Some(Duration::from_millis(500)));
+///     let mut yolo = Supervisor::new(Config::new("models/yolo11s-seg.engine"), config)?;
+///     yolo.on_health_change(|t| eprintln!("{:?} -> {:?}: {:?}", t.from, t.to, t.error));
+///
+///     let result = yolo.inference("images/test.jpg")?;
+///     println!("检测到 {} 个目标", result.detection_count());
+///     Ok(())
+/// }
+/// ```
+pub struct Supervisor<B: InferenceBackend + 'static> {
+    factory: Factory<B>,
+    config: SupervisorConfig,
+    worker: Option<Worker>,
+    health: HealthState,
+    consecutive_failures: usize,
+    stats: SupervisorStats,
+    callbacks: Vec<HealthCallback>,
+}
+
+impl Supervisor<Yolo> {
+    /// 按配置创建受监管的推理器，重建时使用同一份配置
+    pub fn new(config: Config, supervisor_config: SupervisorConfig) -> YoloResult<Self> {
+        Self::from_factory(move || Yolo::new(config.clone()), supervisor_config)
+    }
+}
+
+impl<B: InferenceBackend + 'static> Supervisor<B> {
+    /// 用工厂函数创建监管器，工厂函数会立即调用一次，之后每次重建时再调用
+    pub fn from_factory<F>(factory: F, config: SupervisorConfig) -> YoloResult<Self>
+    where
+        F: FnMut() -> YoloResult<B> + Send + 'static,
+    {
+        let mut factory: Factory<B> = Box::new(factory);
+        let worker = Worker::spawn(factory()?)?;
+        Ok(Self {
+            factory,
+            config,
+            worker: Some(worker),
+            health: HealthState::Healthy,
+            consecutive_failures: 0,
+            stats: SupervisorStats::default(),
+            callbacks: Vec::new(),
+        })
+    }
+
+    /// 注册健康状态变化的回调，回调在调用推理的线程上执行
+    pub fn on_health_change<F>(&mut self, callback: F)
+    where
+        F: Fn(&HealthTransition) + Send + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// 当前健康状态
+    pub fn health(&self) -> HealthState {
+        self.health
+    }
+
+    /// 监管统计
+    pub fn stats(&self) -> SupervisorStats {
+        self.stats
+    }
+
+    /// 对图片执行推理，失败时按策略重试
+    pub fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.attempt(image_path) {
+                Ok(result) => {
+                    self.consecutive_failures = 0;
+                    self.transition(HealthState::Healthy, None);
+                    return Ok(result);
+                }
+                Err(error) if !is_retryable(&error) => return Err(error),
+                Err(error) => error,
+            };
+
+            if attempt >= self.config.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+            self.stats.retries += 1;
+            thread::sleep(self.config.backoff(attempt));
+        }
+    }
+
+    /// 执行一次推理，并根据结果处理失效、超时和连续失败
+    fn attempt(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        if self.worker.is_none() {
+            self.recreate(None)?;
+        }
+
+        let (result, poisoned) = self.run(image_path);
+        let error = match result {
+            Ok(result) => return Ok(result),
+            Err(error) if !is_retryable(&error) => return Err(error),
+            Err(error) => error,
+        };
+
+        self.consecutive_failures += 1;
+        if matches!(error, YoloError::Timeout(_)) {
+            self.stats.timeouts += 1;
+            if let Some(worker) = self.worker.take() {
+                worker.abandon();
+            }
+            self.recreate(Some(&error))?;
+        } else if poisoned {
+            self.recover(&error)?;
+        } else if self.consecutive_failures >= self.config.recreate_after.max(1) {
+            self.recreate(Some(&error))?;
+        } else {
+            self.transition(HealthState::Degraded, Some(&error));
+        }
+        Err(error)
+    }
+
+    fn run(&mut self, image_path: &str) -> Reply {
+        let Some(worker) = self.worker.as_ref() else {
+            return (Err(YoloError::ShutDown("推理器不可用".to_string())), false);
+        };
+        let (reply, receiver) = mpsc::channel();
+        if let Err(e) = worker.send(Request::Inference(image_path.to_string(), reply)) {
+            return (Err(e), true);
+        }
+
+        let received = match self.config.watchdog {
+            Some(timeout) => receiver.recv_timeout(timeout),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(reply) => reply,
+            Err(RecvTimeoutError::Timeout) => (
+                Err(YoloError::Timeout(format!(
+                    "推理超过 {:?} 未完成: {}",
+                    self.config.watchdog.unwrap_or_default(),
+                    image_path
+                ))),
+                false,
+            ),
+            // 工作线程意外退出，视为后端失效
+            Err(RecvTimeoutError::Disconnected) => {
+                (Err(YoloError::ShutDown("推理线程已退出".to_string())), true)
+            }
+        }
+    }
+
+    /// 原地恢复失效的后端，失败时重建
+    fn recover(&mut self, error: &YoloError) -> YoloResult<()> {
+        self.transition(HealthState::Recovering, Some(error));
+        let recovered = self.worker.as_ref().and_then(|worker| {
+            let (reply, receiver) = mpsc::channel();
+            worker.send(Request::Recover(reply)).ok()?;
+            let received = match self.config.watchdog {
+                Some(timeout) => receiver.recv_timeout(timeout).ok(),
+                None => receiver.recv().ok(),
+            };
+            received?.ok()
+        });
+
+        if recovered.is_some() {
+            self.stats.recoveries += 1;
+            self.consecutive_failures = 0;
+            Ok(())
+        } else {
+            if let Some(worker) = self.worker.take() {
+                worker.abandon();
+            }
+            self.recreate(Some(error))
+        }
+    }
+
+    /// 用工厂函数重建后端和工作线程
+    fn recreate(&mut self, error: Option<&YoloError>) -> YoloResult<()> {
+        self.transition(HealthState::Recovering, error);
+        self.worker = None;
+
+        let worker = panic::catch_unwind(AssertUnwindSafe(|| (self.factory)()))
+            .unwrap_or_else(|_| Err(YoloError::Initialization("重建推理器时 panic".to_string())))
+            .and_then(Worker::spawn);
+        match worker {
+            Ok(worker) => {
+                self.worker = Some(worker);
+                self.stats.recreations += 1;
+                self.consecutive_failures = 0;
+                Ok(())
+            }
+            Err(e) => {
+                self.transition(HealthState::Failed, Some(&e));
+                Err(e)
+            }
+        }
+    }
+
+    fn transition(&mut self, to: HealthState, error: Option<&YoloError>) {
+        if self.health == to {
+            return;
+        }
+        let transition = HealthTransition {
+            from: self.health,
+            to,
+            error: error.cloned(),
+        };
+        self.health = to;
+        for callback in &self.callbacks {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(&transition)));
+        }
+    }
+}
+
+impl<B: InferenceBackend + 'static> InferenceBackend for Supervisor<B> {
+    fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        Supervisor::inference(self, image_path)
+    }
+}
+
+/// 输入本身有问题的错误重试也不会成功
+fn is_retryable(error: &YoloError) -> bool {
+    !matches!(
+        error,
+        YoloError::File(_)
+            | YoloError::FileNotFound(_)
+            | YoloError::ImageDecode(_)
+            | YoloError::InvalidParameter(_)
+            | YoloError::InvalidArgument(_)
+    )
+}
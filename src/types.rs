@@ -1,9 +1,20 @@
-use std::os::raw::{c_int, c_void};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use crate::classes::ClassFilter;
+use crate::decode::{DETECTION_STRIDE, MASK_COEFFICIENTS, MAX_NUM_OUTPUT_BBOX, PROTO_STRIDE};
+use crate::error::{ErrorCode, NativeError, YoloError};
+use crate::mask::{BinaryMask, MaskRefinement};
+use crate::nms::NmsConfig;
+use crate::stats::PerfStats;
 
 /// 检测结果结构
 #[derive(Debug, Clone)]
 pub struct Detection {
-    /// 边界框 [x, y, width, height]
+    /// 边界框，网络输入坐标系下的 [x1, y1, x2, y2]
     pub bbox: [f32; 4],
     /// 置信度
     pub confidence: f32,
@@ -15,6 +26,10 @@ pub struct Detection {
     pub mask_width: i32,
     /// 掩码高度
     pub mask_height: i32,
+    /// 姿态关键点，只有姿态模型才有
+    pub keypoints: Vec<Keypoint>,
+    /// 旋转框，只有旋转框模型才有；此时 `bbox` 为旋转框的外接矩形
+    pub rotated_box: Option<RotatedBox>,
 }
 
 impl Detection {
@@ -27,9 +42,23 @@ impl Detection {
             mask_data: Vec::new(),
             mask_width: 0,
             mask_height: 0,
+            keypoints: Vec::new(),
+            rotated_box: None,
         }
     }
 
+    /// 设置姿态关键点
+    pub fn with_keypoints(mut self, keypoints: Vec<Keypoint>) -> Self {
+        self.keypoints = keypoints;
+        self
+    }
+
+    /// 设置旋转框
+    pub fn with_rotated_box(mut self, rotated_box: RotatedBox) -> Self {
+        self.rotated_box = Some(rotated_box);
+        self
+    }
+
     /// 设置分割掩码
     pub fn with_mask(mut self, mask_data: Vec<f32>, width: i32, height: i32) -> Self {
         self.mask_data = mask_data;
@@ -67,6 +96,127 @@ impl Detection {
     pub fn mask_size(&self) -> (i32, i32) {
         (self.mask_width, self.mask_height)
     }
+
+    /// 获取姿态关键点
+    pub fn keypoints(&self) -> &[Keypoint] {
+        &self.keypoints
+    }
+
+    /// 获取旋转框
+    pub fn rotated_box(&self) -> Option<&RotatedBox> {
+        self.rotated_box.as_ref()
+    }
+
+    /// 按阈值二值化分割掩码，没有掩码或掩码长度与尺寸不一致时返回 `None`
+    pub fn binary_mask(&self, threshold: f32) -> Option<BinaryMask> {
+        if !self.has_mask() {
+            return None;
+        }
+        let width = usize::try_from(self.mask_width).ok()?;
+        let height = usize::try_from(self.mask_height).ok()?;
+        BinaryMask::from_probabilities(&self.mask_data, width, height, threshold).ok()
+    }
+
+    /// 细化分割掩码，细化后的掩码为 0/1；没有掩码或掩码尺寸无效时不做任何处理
+    ///
+    /// 启用 [`MaskRefinement::tighten_boxes`] 时把边界框收紧到掩码的外接矩形。
+    /// 返回细化后的掩码是否非空，没有处理掩码时返回 `true`。
+    pub fn refine_mask(&mut self, refinement: &MaskRefinement) -> bool {
+        let Some(mask) = self.binary_mask(refinement.threshold) else {
+            return true;
+        };
+        let mask = refinement.apply(mask);
+        let extent = mask.bounding_box();
+        if let (true, Some([x1, y1, x2, y2])) = (refinement.tighten_boxes, extent) {
+            // 掩码与边界框同为网络输入坐标系
+            self.bbox = [x1 as f32, y1 as f32, x2 as f32, y2 as f32];
+        }
+        self.mask_data = mask.to_probabilities();
+        extent.is_some()
+    }
+}
+
+/// 姿态关键点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keypoint {
+    /// x 坐标（网络输入坐标系）
+    pub x: f32,
+    /// y 坐标（网络输入坐标系）
+    pub y: f32,
+    /// 可见度（关键点置信度），被插件过滤掉的关键点为 0
+    pub visibility: f32,
+}
+
+impl Keypoint {
+    /// 从插件输出的 `[x, y, 置信度]` 创建，无效的关键点（`-1`）可见度为 0
+    pub fn from_raw([x, y, confidence]: [f32; 3]) -> Self {
+        Self {
+            x,
+            y,
+            visibility: confidence.max(0.0),
+        }
+    }
+
+    /// 可见度是否大于阈值
+    pub fn is_visible(&self, threshold: f32) -> bool {
+        self.visibility > threshold
+    }
+}
+
+/// 旋转框
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotatedBox {
+    /// 中心 x（网络输入坐标系）
+    pub cx: f32,
+    /// 中心 y（网络输入坐标系）
+    pub cy: f32,
+    /// 宽度
+    pub width: f32,
+    /// 高度
+    pub height: f32,
+    /// 旋转角度（弧度），宽度方向相对 x 轴顺时针旋转（图像坐标系 y 轴向下）
+    pub angle: f32,
+}
+
+impl RotatedBox {
+    /// 面积
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// 四个顶点，按顺序相连
+    pub fn corners(&self) -> [[f32; 2]; 4] {
+        let (sin, cos) = self.angle.sin_cos();
+        let (dx, dy) = (self.width / 2.0, self.height / 2.0);
+        [(-dx, -dy), (dx, -dy), (dx, dy), (-dx, dy)]
+            .map(|(x, y)| [self.cx + x * cos - y * sin, self.cy + x * sin + y * cos])
+    }
+
+    /// 外接矩形 `[x1, y1, x2, y2]`
+    pub fn bounding_box(&self) -> [f32; 4] {
+        self.corners().iter().fold(
+            [f32::MAX, f32::MAX, f32::MIN, f32::MIN],
+            |[x1, y1, x2, y2], &[x, y]| [x1.min(x), y1.min(y), x2.max(x), y2.max(y)],
+        )
+    }
+}
+
+/// 模型任务类型
+///
+/// 决定 Rust 后处理如何解释引擎输出：分割模型解码掩码，姿态模型输出关键点，
+/// 旋转框模型输出旋转框并使用旋转框交并比做 NMS。
+/// 除分割外的任务总是使用 Rust 后处理。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Task {
+    /// 目标检测
+    Detect,
+    /// 实例分割
+    #[default]
+    Segment,
+    /// 姿态估计，17 个关键点
+    Pose,
+    /// 旋转框检测
+    Obb,
 }
 
 /// 推理结果结构
@@ -78,14 +228,25 @@ pub struct InferenceResult {
     pub total_time_ms: f64,
     /// 图片读取时间（毫秒）
     pub image_read_time_ms: f64,
-    /// 预处理时间（毫秒）
+    /// 预处理时间（毫秒，主机侧，含图像拷贝和内核提交）
     pub preprocess_time_ms: f64,
-    /// TensorRT 推理时间（毫秒）
+    /// TensorRT 推理提交时间（毫秒，主机侧，不等待 GPU 完成）
     pub tensorrt_time_ms: f64,
     /// 后处理时间（毫秒）
     pub postprocess_time_ms: f64,
-    /// 结果复制时间（毫秒）
+    /// 结果复制时间（毫秒，主机侧，含等待 GPU 完成）
     pub result_copy_time_ms: f64,
+    /// GPU 上图像上传和预处理内核的执行时间（毫秒）
+    pub gpu_preprocess_time_ms: f64,
+    /// GPU 上引擎的执行时间（毫秒）
+    pub gpu_tensorrt_time_ms: f64,
+    /// GPU 上输出拷贝回主机的时间（毫秒）
+    pub gpu_copy_time_ms: f64,
+    /// 开始处理前的排队时间（毫秒）
+    ///
+    /// 工作池和批处理器中为任务在队列中等待的时间。流水线只在有空闲槽位时才取下一帧，
+    /// 帧不会排队，与直接调用推理器一样为 0。
+    pub queue_wait_ms: f64,
 }
 
 impl InferenceResult {
@@ -99,6 +260,10 @@ impl InferenceResult {
             tensorrt_time_ms: 0.0,
             postprocess_time_ms: 0.0,
             result_copy_time_ms: 0.0,
+            gpu_preprocess_time_ms: 0.0,
+            gpu_tensorrt_time_ms: 0.0,
+            gpu_copy_time_ms: 0.0,
+            queue_wait_ms: 0.0,
         }
     }
 
@@ -107,6 +272,21 @@ impl InferenceResult {
         self.detections.push(detection);
     }
 
+    /// 并行细化所有检测的分割掩码
+    ///
+    /// 启用 [`MaskRefinement::tighten_boxes`] 时丢弃细化后掩码为空的检测。
+    pub fn refine_masks(&mut self, refinement: &MaskRefinement) {
+        let non_empty: Vec<bool> = self
+            .detections
+            .par_iter_mut()
+            .map(|detection| detection.refine_mask(refinement))
+            .collect();
+        if refinement.tighten_boxes {
+            let mut non_empty = non_empty.into_iter();
+            self.detections.retain(|_| non_empty.next().unwrap_or(true));
+        }
+    }
+
     /// 获取检测结果数量
     pub fn detection_count(&self) -> usize {
         self.detections.len()
@@ -131,6 +311,10 @@ impl InferenceResult {
             tensorrt_time_ms: self.tensorrt_time_ms,
             postprocess_time_ms: self.postprocess_time_ms,
             result_copy_time_ms: self.result_copy_time_ms,
+            gpu_preprocess_time_ms: self.gpu_preprocess_time_ms,
+            gpu_tensorrt_time_ms: self.gpu_tensorrt_time_ms,
+            gpu_copy_time_ms: self.gpu_copy_time_ms,
+            queue_wait_ms: self.queue_wait_ms,
         }
     }
 }
@@ -142,14 +326,25 @@ pub struct PerformanceBreakdown {
     pub total_time_ms: f64,
     /// 图片读取时间（毫秒）
     pub image_read_time_ms: f64,
-    /// 预处理时间（毫秒）
+    /// 预处理时间（毫秒，主机侧，含图像拷贝和内核提交）
     pub preprocess_time_ms: f64,
-    /// TensorRT 推理时间（毫秒）
+    /// TensorRT 推理提交时间（毫秒，主机侧，不等待 GPU 完成）
     pub tensorrt_time_ms: f64,
     /// 后处理时间（毫秒）
     pub postprocess_time_ms: f64,
-    /// 结果复制时间（毫秒）
+    /// 结果复制时间（毫秒，主机侧，含等待 GPU 完成）
     pub result_copy_time_ms: f64,
+    /// GPU 上图像上传和预处理内核的执行时间（毫秒）
+    pub gpu_preprocess_time_ms: f64,
+    /// GPU 上引擎的执行时间（毫秒）
+    pub gpu_tensorrt_time_ms: f64,
+    /// GPU 上输出拷贝回主机的时间（毫秒）
+    pub gpu_copy_time_ms: f64,
+    /// 开始处理前的排队时间（毫秒）
+    ///
+    /// 工作池和批处理器中为任务在队列中等待的时间。流水线只在有空闲槽位时才取下一帧，
+    /// 帧不会排队，与直接调用推理器一样为 0。
+    pub queue_wait_ms: f64,
 }
 
 impl PerformanceBreakdown {
@@ -163,13 +358,25 @@ impl PerformanceBreakdown {
     }
 
     /// 获取 TensorRT 推理占比
+    ///
+    /// 有设备侧计时时使用 GPU 上的引擎执行时间，否则使用主机侧提交时间。
     pub fn tensorrt_percentage(&self) -> f64 {
+        let tensorrt_time_ms = if self.gpu_tensorrt_time_ms > 0.0 {
+            self.gpu_tensorrt_time_ms
+        } else {
+            self.tensorrt_time_ms
+        };
         if self.total_time_ms > 0.0 {
-            (self.tensorrt_time_ms / self.total_time_ms) * 100.0
+            (tensorrt_time_ms / self.total_time_ms) * 100.0
         } else {
             0.0
         }
     }
+
+    /// GPU 上各阶段执行时间之和
+    pub fn gpu_time_ms(&self) -> f64 {
+        self.gpu_preprocess_time_ms + self.gpu_tensorrt_time_ms + self.gpu_copy_time_ms
+    }
 }
 
 /// TensorRT 缓冲区信息
@@ -179,8 +386,133 @@ pub struct TensorRtInfo {
     pub input_size: i32,
     /// 输出缓冲区大小
     pub output_size: i32,
-    /// 分割输出缓冲区大小
+    /// 分割输出缓冲区大小，引擎没有分割原型输出时为 0
     pub output_seg_size: i32,
+    /// 加载时从引擎发现的输入输出张量
+    pub tensors: Vec<TensorInfo>,
+}
+
+impl TensorRtInfo {
+    /// 按用途查找张量
+    pub fn tensor(&self, role: TensorRole) -> Option<&TensorInfo> {
+        self.tensors.iter().find(|t| t.role == role)
+    }
+
+    /// 引擎是否有分割原型输出，检测模型没有
+    pub fn has_proto(&self) -> bool {
+        self.tensor(TensorRole::Proto).is_some()
+    }
+}
+
+/// 从引擎读出的模型信息
+///
+/// 输入尺寸、检测输出容量和分割原型尺寸取自引擎张量的形状，
+/// 引擎中没有的信息由引擎旁的 `<引擎路径>.meta` 文件补充（每行一个 `key=value`）：
+/// `gpu_id`、`num_classes`，以及动态输入时的 `input_width`、`input_height`。
+/// 默认值与 C++ 核心 `config.h` 中的常量一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// 推理所在的 GPU
+    pub gpu_id: i32,
+    /// 网络输入宽度
+    pub input_width: usize,
+    /// 网络输入高度
+    pub input_height: usize,
+    /// 类别数，引擎和元数据都没有给出时为 `None`
+    pub num_classes: Option<usize>,
+    /// 检测输出最多容纳的候选框数量
+    pub max_detections: usize,
+    /// 引擎支持的最大批次
+    pub max_batch_size: usize,
+    /// 分割原型宽度，没有分割原型输出时为 0
+    pub proto_width: usize,
+    /// 分割原型高度，没有分割原型输出时为 0
+    pub proto_height: usize,
+}
+
+impl Default for ModelInfo {
+    fn default() -> Self {
+        Self {
+            gpu_id: 0,
+            input_width: 640,
+            input_height: 640,
+            num_classes: None,
+            max_detections: MAX_NUM_OUTPUT_BBOX,
+            max_batch_size: 1,
+            proto_width: 640 / PROTO_STRIDE,
+            proto_height: 640 / PROTO_STRIDE,
+        }
+    }
+}
+
+impl ModelInfo {
+    /// 引擎是否有分割原型输出
+    pub fn has_proto(&self) -> bool {
+        self.proto_width > 0 && self.proto_height > 0
+    }
+
+    /// 单张图片的检测输出长度（float 数）
+    pub fn output_size(&self) -> usize {
+        self.max_detections * DETECTION_STRIDE + 1
+    }
+
+    /// 分割原型相对网络输入的下采样倍数，与 C++ 核心一样取 `input_width / proto_width`
+    ///
+    /// 没有分割原型输出时为 [`PROTO_STRIDE`]。
+    pub fn proto_stride(&self) -> usize {
+        match self.input_width.checked_div(self.proto_width) {
+            Some(stride) if stride > 0 => stride,
+            _ => PROTO_STRIDE,
+        }
+    }
+
+    /// 单张图片的分割原型长度（float 数），没有分割原型输出时为 0
+    pub fn proto_size(&self) -> usize {
+        MASK_COEFFICIENTS * self.proto_width * self.proto_height
+    }
+}
+
+/// 张量在推理器中的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TensorRole {
+    /// 图片输入
+    Input,
+    /// 检测输出
+    Output,
+    /// 分割原型（仅分割模型）
+    Proto,
+}
+
+impl TensorRole {
+    /// 从 C API 的 `YoloTensorRole` 转换，未知的取值返回 `None`
+    pub fn from_raw(role: i32) -> Option<Self> {
+        match role {
+            0 => Some(TensorRole::Input),
+            1 => Some(TensorRole::Output),
+            2 => Some(TensorRole::Proto),
+            _ => None,
+        }
+    }
+
+    /// 对应的 C API 取值
+    pub fn as_raw(&self) -> i32 {
+        match self {
+            TensorRole::Input => 0,
+            TensorRole::Output => 1,
+            TensorRole::Proto => 2,
+        }
+    }
+}
+
+/// 引擎的输入或输出张量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorInfo {
+    /// 张量名称
+    pub name: String,
+    /// 用途
+    pub role: TensorRole,
+    /// 引擎中的形状，动态维度为 -1
+    pub shape: Vec<i64>,
 }
 
 /// TensorRT 缓冲区指针
@@ -190,10 +522,89 @@ pub struct TensorRtBuffers {
     pub input_buffer: *mut c_void,
     /// 输出缓冲区指针
     pub output_buffer: *mut c_void,
-    /// 分割输出缓冲区指针
+    /// 分割输出缓冲区指针，引擎没有分割原型输出时为空指针
     pub output_seg_buffer: *mut c_void,
 }
 
+/// 内存中的图片帧
+///
+/// 像素按 BGR 顺序、3 通道、行优先紧密排列，与 OpenCV 的 `CV_8UC3` 一致。
+#[derive(Debug, Clone)]
+pub struct Frame {
+    data: Vec<u8>,
+    width: i32,
+    height: i32,
+}
+
+impl Frame {
+    /// 用 BGR 像素数据创建图片帧
+    pub fn from_bgr(data: Vec<u8>, width: i32, height: i32) -> crate::error::YoloResult<Self> {
+        if width <= 0 || height <= 0 || data.len() != width as usize * height as usize * 3 {
+            return Err(YoloError::InvalidParameter(format!(
+                "图片数据长度 {} 与尺寸 {}x{} 不匹配",
+                data.len(),
+                width,
+                height
+            )));
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+        })
+    }
+
+    /// 从 `image` crate 的图片创建图片帧
+    pub fn from_image(image: &image::DynamicImage) -> Self {
+        let rgb = image.to_rgb8();
+        let (width, height) = rgb.dimensions();
+        let mut data = rgb.into_raw();
+        for pixel in data.chunks_exact_mut(3) {
+            pixel.swap(0, 2);
+        }
+        Self {
+            data,
+            width: width as i32,
+            height: height as i32,
+        }
+    }
+
+    /// 读取图片文件
+    pub fn open(path: &str) -> crate::error::YoloResult<Self> {
+        let image = image::open(path).map_err(|e| {
+            let code = match &e {
+                image::ImageError::IoError(io) if io.kind() == std::io::ErrorKind::NotFound => {
+                    ErrorCode::FileNotFound
+                }
+                image::ImageError::IoError(_) => {
+                    return YoloError::File(format!("{}: {}", path, e))
+                }
+                _ => ErrorCode::ImageDecode,
+            };
+            YoloError::from_native(
+                NativeError::new(code, e.to_string()).with_path(path),
+                YoloError::File,
+            )
+        })?;
+        Ok(Self::from_image(&image))
+    }
+
+    /// 像素数据
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 图片宽度
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// 图片高度
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+}
+
 /// 配置选项
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -201,10 +612,18 @@ pub struct Config {
     pub engine_path: String,
     /// 标签文件路径
     pub labels_path: String,
-    /// 是否启用详细日志
+    /// 是否转发 TensorRT 的详细日志（info/verbose），默认只转发警告及以上
     pub verbose: bool,
     /// 推理批次大小
     pub batch_size: usize,
+    /// 共享的性能统计收集器，为 `None` 时每个推理器使用自己的收集器
+    pub stats: Option<Arc<PerfStats>>,
+    /// Rust 后处理的 NMS 配置，为 `None` 时使用 C++ 核心的 NMS 和掩码解码
+    pub nms: Option<NmsConfig>,
+    /// 按类别的过滤规则，不为空时同样使用 Rust 后处理
+    pub classes: ClassFilter,
+    /// 模型任务类型
+    pub task: Task,
 }
 
 impl Default for Config {
@@ -214,6 +633,10 @@ impl Default for Config {
             labels_path: String::new(),
             verbose: false,
             batch_size: 1,
+            stats: None,
+            nms: None,
+            classes: ClassFilter::default(),
+            task: Task::default(),
         }
     }
 }
@@ -226,6 +649,10 @@ impl Config {
             labels_path: String::new(),
             verbose: false,
             batch_size: 1,
+            stats: None,
+            nms: None,
+            classes: ClassFilter::default(),
+            task: Task::default(),
         }
     }
 
@@ -246,6 +673,37 @@ impl Config {
         self.batch_size = batch_size;
         self
     }
+
+    /// 设置共享的性能统计收集器
+    ///
+    /// 用同一份配置创建的推理器（例如工作池中的各个工作线程）会汇总到同一个收集器。
+    pub fn with_stats(mut self, stats: Arc<PerfStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// 改用 Rust 后处理并设置 NMS 配置
+    ///
+    /// C++ 核心只执行预处理、引擎推理和结果拷贝，NMS 和掩码解码在 Rust 中完成，
+    /// 对单张、批量、流水线和分阶段推理都生效。
+    pub fn with_nms(mut self, nms: NmsConfig) -> Self {
+        self.nms = Some(nms);
+        self
+    }
+
+    /// 设置按类别的过滤规则
+    ///
+    /// 规则在 Rust 后处理中、NMS 之前执行；未设置 NMS 配置时使用默认的 [`NmsConfig`]。
+    pub fn with_classes(mut self, classes: ClassFilter) -> Self {
+        self.classes = classes;
+        self
+    }
+
+    /// 设置模型任务类型
+    pub fn with_task(mut self, task: Task) -> Self {
+        self.task = task;
+        self
+    }
 }
 
 // 内部使用的 C API 结构
@@ -257,6 +715,76 @@ pub(crate) struct YoloDetection {
     pub mask_data: *mut f32,
     pub mask_width: c_int,
     pub mask_height: c_int,
+    pub keypoints: [f32; 51],
+    pub angle: f32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub(crate) struct YoloModelInfo {
+    pub gpu_id: c_int,
+    pub input_width: c_int,
+    pub input_height: c_int,
+    pub num_classes: c_int,
+    pub max_detections: c_int,
+    pub max_batch_size: c_int,
+    pub proto_width: c_int,
+    pub proto_height: c_int,
+}
+
+impl From<&YoloModelInfo> for ModelInfo {
+    fn from(raw: &YoloModelInfo) -> Self {
+        let size = |value: c_int| value.max(0) as usize;
+        Self {
+            gpu_id: raw.gpu_id,
+            input_width: size(raw.input_width),
+            input_height: size(raw.input_height),
+            num_classes: (raw.num_classes > 0).then(|| size(raw.num_classes)),
+            max_detections: size(raw.max_detections),
+            max_batch_size: size(raw.max_batch_size).max(1),
+            proto_width: size(raw.proto_width),
+            proto_height: size(raw.proto_height),
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct YoloTensorInfo {
+    pub name: [c_char; 64],
+    pub role: c_int,
+    pub num_dims: c_int,
+    pub dims: [i64; 8],
+}
+
+impl YoloTensorInfo {
+    /// 传给 C API 填充的空张量信息
+    pub(crate) fn empty() -> Self {
+        Self {
+            name: [0; 64],
+            role: 0,
+            num_dims: 0,
+            dims: [0; 8],
+        }
+    }
+
+    /// 转换为 [`TensorInfo`]，用途未知时返回错误
+    pub(crate) fn to_tensor_info(&self) -> crate::error::YoloResult<TensorInfo> {
+        // SAFETY: C API 保证名称以 '\0' 结尾
+        let name = unsafe { CStr::from_ptr(self.name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        let role = TensorRole::from_raw(self.role).ok_or_else(|| {
+            YoloError::TensorRt(NativeError::new(
+                ErrorCode::TensorRt,
+                format!("张量 {} 的用途未知: {}", name, self.role),
+            ))
+        })?;
+        Ok(TensorInfo {
+            name,
+            role,
+            shape: self.dims[..self.num_dims.clamp(0, 8) as usize].to_vec(),
+        })
+    }
 }
 
 #[repr(C)]
@@ -269,6 +797,30 @@ pub(crate) struct YoloResult {
     pub tensorrt_time_ms: f64,
     pub postprocess_time_ms: f64,
     pub result_copy_time_ms: f64,
+    pub gpu_preprocess_time_ms: f64,
+    pub gpu_tensorrt_time_ms: f64,
+    pub gpu_copy_time_ms: f64,
+    pub mask_decode_time_ms: f64,
+}
+
+impl YoloResult {
+    /// 传给 C API 填充的空结果
+    pub(crate) fn empty() -> Self {
+        Self {
+            detections: std::ptr::null_mut(),
+            num_detections: 0,
+            inference_time_ms: 0.0,
+            image_read_time_ms: 0.0,
+            preprocess_time_ms: 0.0,
+            tensorrt_time_ms: 0.0,
+            postprocess_time_ms: 0.0,
+            result_copy_time_ms: 0.0,
+            gpu_preprocess_time_ms: 0.0,
+            gpu_tensorrt_time_ms: 0.0,
+            gpu_copy_time_ms: 0.0,
+            mask_decode_time_ms: 0.0,
+        }
+    }
 }
 
 pub(crate) type YoloInferenceHandle = *mut c_void;
@@ -1,16 +1,30 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int, c_void};
-
-use crate::error::{YoloError, YoloResult};
+use std::sync::Arc;
+
+use crate::benchmark::{Benchmark, BenchmarkMode};
+use crate::classes::ResolvedClasses;
+use crate::decode::RawCandidate;
+use crate::error::{ErrorCode, NativeError, YoloError, YoloResult};
+use crate::logging;
+use crate::postprocess::{self, Rules};
+use crate::stats::PerfStats;
 use crate::types::{
-    Config, Detection, InferenceResult, PerformanceBreakdown, TensorRtBuffers, TensorRtInfo,
-    YoloInferenceHandle, YoloResult as YoloResultRaw,
+    Config, Frame, InferenceResult, ModelInfo, PerformanceBreakdown, Task, TensorInfo,
+    TensorRtBuffers, TensorRtInfo, YoloInferenceHandle, YoloModelInfo, YoloResult as YoloResultRaw,
+    YoloTensorInfo,
 };
 
 /// YOLO11s 推理器
 ///
 /// 提供高性能的目标检测和实例分割功能。
 ///
+/// 每个实例独立持有 TensorRT 上下文、CUDA 流和预处理缓冲区，
+/// 同一进程内可以同时创建多个推理器（不同引擎或同一引擎的多个副本）。
+///
+/// `Yolo` 实现了 `Send`，可以移动到工作线程中使用，但不实现 `Sync`；
+/// 需要在多个线程间共享同一个推理器时请使用 [`SharedYolo`](crate::SharedYolo)。
+///
 /// # 示例
 ///
 /// ```rust
@@ -32,6 +46,9 @@ use crate::types::{
 pub struct Yolo {
     handle: YoloInferenceHandle,
     config: Config,
+    stats: Arc<PerfStats>,
+    classes: ResolvedClasses,
+    model: ModelInfo,
 }
 
 impl Yolo {
@@ -49,17 +66,37 @@ impl Yolo {
     /// let yolo = Yolo::new(Config::new("models/yolo11s-seg.engine"))?;
     /// ```
     pub fn new(config: Config) -> YoloResult<Self> {
+        if let Some(nms) = &config.nms {
+            nms.validate()?;
+        }
+        let classes = config.classes.resolve()?;
         let engine_c = CString::new(&*config.engine_path)
             .map_err(|e| YoloError::InvalidParameter(e.to_string()))?;
         let labels_c = CString::new(&*config.labels_path)
             .map_err(|e| YoloError::InvalidParameter(e.to_string()))?;
 
+        // 引擎反序列化时 TensorRT 就会输出日志，先安装回调
+        logging::install(config.verbose);
         let handle = unsafe { yolo_create_inference(engine_c.as_ptr(), labels_c.as_ptr()) };
         if handle.is_null() {
-            return Err(YoloError::Initialization(last_error()));
+            return Err(last_native_error(YoloError::Initialization));
         }
 
-        Ok(Yolo { handle, config })
+        let stats = config.stats.clone().unwrap_or_default();
+        let mut yolo = Yolo {
+            handle,
+            config,
+            stats,
+            classes,
+            model: ModelInfo::default(),
+        };
+        // 引擎不会变化，恢复后无需重新读取
+        let mut raw = YoloModelInfo::default();
+        if !unsafe { yolo_get_model_info(handle, &mut raw) } {
+            return Err(last_native_error(YoloError::Initialization));
+        }
+        yolo.model = ModelInfo::from(&raw);
+        Ok(yolo)
     }
 
     /// 使用默认配置创建推理器
@@ -100,71 +137,258 @@ impl Yolo {
     /// }
     /// ```
     pub fn inference(&self, image_path: &str) -> YoloResult<InferenceResult> {
+        self.inference_queued(image_path, 0.0)
+    }
+
+    /// 执行在队列中等待过的推理，等待时间在记录性能统计之前写入结果
+    pub(crate) fn inference_queued(
+        &self,
+        image_path: &str,
+        queue_wait_ms: f64,
+    ) -> YoloResult<InferenceResult> {
+        #[cfg(feature = "tracing")]
+        let span = self.inference_span();
+        #[cfg(feature = "tracing")]
+        span.record("image_path", image_path);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let outcome = self.run_inference(image_path);
+        #[cfg(feature = "tracing")]
+        record_span(&span, &outcome);
+        self.track(outcome.map(|mut result| {
+            result.queue_wait_ms = queue_wait_ms;
+            result
+        }))
+    }
+
+    fn run_inference(&self, image_path: &str) -> YoloResult<InferenceResult> {
+        if self.rules().is_some() {
+            let start = std::time::Instant::now();
+            let frame = {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::info_span!("image_load", image_path).entered();
+                Frame::open(image_path)?
+            };
+            let image_read_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+            let mut result = self.run_inference_frame(&frame)?;
+            result.image_read_time_ms = image_read_time_ms;
+            result.total_time_ms += image_read_time_ms;
+            return Ok(result);
+        }
+
         let image_c =
             CString::new(image_path).map_err(|e| YoloError::InvalidParameter(e.to_string()))?;
 
-        let mut raw_result = YoloResultRaw {
-            detections: std::ptr::null_mut(),
-            num_detections: 0,
-            inference_time_ms: 0.0,
-            image_read_time_ms: 0.0,
-            preprocess_time_ms: 0.0,
-            tensorrt_time_ms: 0.0,
-            postprocess_time_ms: 0.0,
-            result_copy_time_ms: 0.0,
-        };
+        let mut raw_result = YoloResultRaw::empty();
 
         let ok = unsafe { yolo_inference(self.handle, image_c.as_ptr(), &mut raw_result) };
         if !ok {
-            return Err(YoloError::Inference(last_error()));
+            return Err(last_native_error(YoloError::Inference));
         }
 
-        // 转换结果
-        let mut result = InferenceResult::new();
-        result.total_time_ms = raw_result.inference_time_ms;
-        result.image_read_time_ms = raw_result.image_read_time_ms;
-        result.preprocess_time_ms = raw_result.preprocess_time_ms;
-        result.tensorrt_time_ms = raw_result.tensorrt_time_ms;
-        result.postprocess_time_ms = raw_result.postprocess_time_ms;
-        result.result_copy_time_ms = raw_result.result_copy_time_ms;
-
-        // 转换检测结果
-        if !raw_result.detections.is_null() && raw_result.num_detections > 0 {
-            for i in 0..raw_result.num_detections {
-                let detection_ptr = unsafe { raw_result.detections.offset(i as isize) };
-                let raw_detection = unsafe { &*detection_ptr };
-
-                let mut detection = Detection::new(
-                    raw_detection.bbox,
-                    raw_detection.confidence,
-                    raw_detection.class_id,
-                );
+        #[cfg(feature = "tracing")]
+        stage_spans(&raw_result, true);
+        Ok(take_raw_result(&mut raw_result, self.config.task))
+    }
 
-                // 处理分割掩码
-                if !raw_detection.mask_data.is_null()
-                    && raw_detection.mask_width > 0
-                    && raw_detection.mask_height > 0
-                {
-                    let mask_size = (raw_detection.mask_width * raw_detection.mask_height) as usize;
-                    let mask_data =
-                        unsafe { std::slice::from_raw_parts(raw_detection.mask_data, mask_size) }
-                            .to_vec();
-
-                    detection = detection.with_mask(
-                        mask_data,
-                        raw_detection.mask_width,
-                        raw_detection.mask_height,
-                    );
-                }
+    /// 对内存中的图片帧执行推理
+    ///
+    /// # 参数
+    ///
+    /// * `frame` - BGR 图片帧
+    pub fn inference_frame(&self, frame: &Frame) -> YoloResult<InferenceResult> {
+        #[cfg(feature = "tracing")]
+        let span = self.inference_span();
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let outcome = self.run_inference_frame(frame);
+        #[cfg(feature = "tracing")]
+        record_span(&span, &outcome);
+        self.track(outcome)
+    }
+
+    fn run_inference_frame(&self, frame: &Frame) -> YoloResult<InferenceResult> {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("width", frame.width())
+            .record("height", frame.height());
+
+        if let Some(rules) = self.rules() {
+            let mut results =
+                postprocess::inference_batch(self, std::slice::from_ref(frame), &rules)?;
+            return Ok(results.remove(0));
+        }
+
+        let mut raw_result = YoloResultRaw::empty();
+        let ok = unsafe {
+            yolo_inference_from_memory(
+                self.handle,
+                frame.data().as_ptr(),
+                frame.width(),
+                frame.height(),
+                3,
+                &mut raw_result,
+            )
+        };
+        if !ok {
+            return Err(last_native_error(YoloError::Inference));
+        }
+        #[cfg(feature = "tracing")]
+        stage_spans(&raw_result, true);
+        Ok(take_raw_result(&mut raw_result, self.config.task))
+    }
+
+    /// 批量推理
+    ///
+    /// 整个批次只调用一次引擎，结果顺序与 `frames` 一致。
+    /// 批次大小不能超过 [`max_batch_size`](Self::max_batch_size)。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// # use yolo11s_tensorrt_rs::{Frame, Yolo};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let yolo = Yolo::with_engine("models/yolo11s-seg.engine")?;
+    /// let frames = vec![Frame::open("images/test1.jpg")?, Frame::open("images/test2.jpg")?];
+    /// for result in yolo.inference_batch(&frames)? {
+    ///     println!("检测到 {} 个目标", result.detection_count());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inference_batch(&self, frames: &[Frame]) -> YoloResult<Vec<InferenceResult>> {
+        self.inference_batch_queued(frames, &[])
+    }
 
-                result.add_detection(detection);
+    /// 执行在队列中等待过的批量推理
+    ///
+    /// `queue_wait_ms` 与 `frames` 一一对应，缺少的按 0 处理；
+    /// 等待时间在记录性能统计之前写入各个结果。
+    pub(crate) fn inference_batch_queued(
+        &self,
+        frames: &[Frame],
+        queue_wait_ms: &[f64],
+    ) -> YoloResult<Vec<InferenceResult>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "yolo.inference_batch",
+            engine_path = %self.config.engine_path,
+            batch_size = frames.len(),
+        )
+        .entered();
+
+        let outcome = self.run_inference_batch(frames).map(|mut results| {
+            for (result, &wait) in results.iter_mut().zip(queue_wait_ms) {
+                result.queue_wait_ms = wait;
             }
+            results
+        });
+        match &outcome {
+            Ok(results) => results.iter().for_each(|result| self.stats.record(result)),
+            Err(e) => self.stats.record_error(e),
         }
+        outcome
+    }
 
-        // 释放原始结果
-        unsafe { yolo_free_result(&mut raw_result) };
+    fn run_inference_batch(&self, frames: &[Frame]) -> YoloResult<Vec<InferenceResult>> {
+        if frames.is_empty() {
+            return Ok(Vec::new());
+        }
+        if frames.len() > self.max_batch_size() {
+            return Err(YoloError::InvalidParameter(format!(
+                "批次大小 {} 超过引擎支持的最大值 {}",
+                frames.len(),
+                self.max_batch_size()
+            )));
+        }
+
+        if let Some(rules) = self.rules() {
+            return postprocess::inference_batch(self, frames, &rules);
+        }
+
+        let images: Vec<*const u8> = frames.iter().map(|f| f.data().as_ptr()).collect();
+        let widths: Vec<c_int> = frames.iter().map(|f| f.width()).collect();
+        let heights: Vec<c_int> = frames.iter().map(|f| f.height()).collect();
+        let mut raw_results: Vec<YoloResultRaw> =
+            frames.iter().map(|_| YoloResultRaw::empty()).collect();
 
-        Ok(result)
+        let ok = unsafe {
+            yolo_inference_batch_from_memory(
+                self.handle,
+                images.as_ptr(),
+                widths.as_ptr(),
+                heights.as_ptr(),
+                frames.len() as c_int,
+                raw_results.as_mut_ptr(),
+                false,
+            )
+        };
+        if !ok {
+            // 失败时可能已有部分结果被填充
+            for raw_result in &mut raw_results {
+                unsafe { yolo_free_result(raw_result) };
+            }
+            return Err(last_native_error(YoloError::Inference));
+        }
+
+        Ok(raw_results
+            .iter_mut()
+            .map(|raw_result| {
+                #[cfg(feature = "tracing")]
+                stage_spans(raw_result, true);
+                take_raw_result(raw_result, self.config.task)
+            })
+            .collect())
+    }
+
+    /// 从引擎读出的模型信息：输入尺寸、检测输出容量、分割原型尺寸等
+    pub fn model_info(&self) -> &ModelInfo {
+        &self.model
+    }
+
+    /// 引擎支持的最大批次大小
+    pub fn max_batch_size(&self) -> usize {
+        unsafe { yolo_get_max_batch_size(self.handle) }.max(1) as usize
+    }
+
+    /// 推理器是否因 CUDA 错误失效
+    ///
+    /// CUDA 调用失败后执行上下文和流的状态未知，之后的推理都会返回
+    /// [`YoloError::Cuda`]，直到 [`recover`](Self::recover) 成功。
+    pub fn is_poisoned(&self) -> bool {
+        unsafe { yolo_is_poisoned(self.handle) }
+    }
+
+    /// 释放并重新创建引擎、执行上下文、CUDA 流和缓冲区
+    ///
+    /// 同一进程中的恢复是串行的。CUDA 上下文已损坏时需要重置整个设备，
+    /// 如果同一 GPU 上还有其他存活的推理器，会拒绝重置并返回 [`YoloError::Cuda`]。
+    /// 失败时推理器保持失效状态，可以稍后重试。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use yolo11s_tensorrt_rs::{Yolo, YoloError};
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut yolo = Yolo::with_engine("models/yolo11s-seg.engine")?;
+    ///     match yolo.inference("images/test.jpg") {
+    ///         Err(YoloError::Cuda(_)) if yolo.is_poisoned() => yolo.recover()?,
+    ///         other => println!("{:?}", other.map(|r| r.detection_count())),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn recover(&mut self) -> YoloResult<()> {
+        if unsafe { yolo_recover(self.handle) } {
+            Ok(())
+        } else {
+            Err(last_native_error(|msg| {
+                YoloError::Cuda(NativeError::new(ErrorCode::Cuda, msg))
+            }))
+        }
     }
 
     /// 保存推理结果图片
@@ -193,21 +417,12 @@ impl Yolo {
         let output_c =
             CString::new(output_path).map_err(|e| YoloError::InvalidParameter(e.to_string()))?;
 
-        let mut raw_result = YoloResultRaw {
-            detections: std::ptr::null_mut(),
-            num_detections: 0,
-            inference_time_ms: 0.0,
-            image_read_time_ms: 0.0,
-            preprocess_time_ms: 0.0,
-            tensorrt_time_ms: 0.0,
-            postprocess_time_ms: 0.0,
-            result_copy_time_ms: 0.0,
-        };
+        let mut raw_result = YoloResultRaw::empty();
 
         // 重新执行推理以获取原始结果
         let ok = unsafe { yolo_inference(self.handle, image_c.as_ptr(), &mut raw_result) };
         if !ok {
-            return Err(YoloError::Inference(last_error()));
+            return Err(last_native_error(YoloError::Inference));
         }
 
         // 保存结果
@@ -219,13 +434,12 @@ impl Yolo {
                 output_c.as_ptr(),
             )
         };
-        if !save_ok {
-            return Err(YoloError::File(last_error()));
-        }
-
         // 释放结果
         unsafe { yolo_free_result(&mut raw_result) };
 
+        if !save_ok {
+            return Err(last_native_error(YoloError::File));
+        }
         Ok(())
     }
 
@@ -233,7 +447,7 @@ impl Yolo {
     ///
     /// # 返回值
     ///
-    /// 返回包含缓冲区大小信息的 `TensorRtInfo`
+    /// 返回包含缓冲区大小和引擎输入输出张量的 `TensorRtInfo`
     pub fn get_tensorrt_info(&self) -> YoloResult<TensorRtInfo> {
         let mut input_size = 0;
         let mut output_size = 0;
@@ -249,16 +463,35 @@ impl Yolo {
         };
 
         if !ok {
-            return Err(YoloError::TensorRt(last_error()));
+            return Err(last_native_error(|msg| {
+                YoloError::TensorRt(NativeError::new(ErrorCode::TensorRt, msg))
+            }));
         }
 
         Ok(TensorRtInfo {
             input_size,
             output_size,
             output_seg_size,
+            tensors: self.tensors()?,
         })
     }
 
+    /// 加载时从引擎发现的输入输出张量
+    fn tensors(&self) -> YoloResult<Vec<TensorInfo>> {
+        let count = unsafe { yolo_get_tensor_count(self.handle) };
+        (0..count.max(0))
+            .map(|index| {
+                let mut raw = YoloTensorInfo::empty();
+                if !unsafe { yolo_get_tensor_info(self.handle, index, &mut raw) } {
+                    return Err(last_native_error(|msg| {
+                        YoloError::TensorRt(NativeError::new(ErrorCode::TensorRt, msg))
+                    }));
+                }
+                raw.to_tensor_info()
+            })
+            .collect()
+    }
+
     /// 获取 TensorRT 缓冲区指针
     ///
     /// # 返回值
@@ -279,7 +512,9 @@ impl Yolo {
         };
 
         if !ok {
-            return Err(YoloError::TensorRt(last_error()));
+            return Err(last_native_error(|msg| {
+                YoloError::TensorRt(NativeError::new(ErrorCode::TensorRt, msg))
+            }));
         }
 
         Ok(TensorRtBuffers {
@@ -297,11 +532,25 @@ impl Yolo {
     pub fn get_cuda_stream(&self) -> YoloResult<*mut c_void> {
         let stream = unsafe { yolo_get_cuda_stream(self.handle) };
         if stream.is_null() {
-            return Err(YoloError::Cuda("Failed to get CUDA stream".to_string()));
+            return Err(last_native_error(YoloError::Unknown));
         }
         Ok(stream)
     }
 
+    /// 等待 CUDA 流上已提交的工作全部完成
+    ///
+    /// [`tensorrt_inference_only`](Self::tensorrt_inference_only) 只负责提交，
+    /// 计时前需要调用本方法同步。
+    pub fn synchronize(&self) -> YoloResult<()> {
+        let ok = unsafe { yolo_synchronize(self.handle) };
+        if !ok {
+            return Err(last_native_error(|msg| {
+                YoloError::Cuda(NativeError::new(ErrorCode::Cuda, msg))
+            }));
+        }
+        Ok(())
+    }
+
     /// 执行纯 TensorRT 推理
     ///
     /// 这个函数只执行 TensorRT 推理部分，不包含预处理和后处理。
@@ -311,7 +560,7 @@ impl Yolo {
     ///
     /// * `input_buffer` - 输入缓冲区指针
     /// * `output_buffer` - 输出缓冲区指针
-    /// * `output_seg_buffer` - 分割输出缓冲区指针
+    /// * `output_seg_buffer` - 分割输出缓冲区指针，引擎没有分割原型输出时可以为空指针
     /// * `stream` - CUDA 流指针
     ///
     /// # 示例
@@ -346,7 +595,9 @@ impl Yolo {
         };
 
         if !ok {
-            return Err(YoloError::TensorRt(last_error()));
+            return Err(last_native_error(|msg| {
+                YoloError::TensorRt(NativeError::new(ErrorCode::TensorRt, msg))
+            }));
         }
 
         Ok(())
@@ -357,63 +608,92 @@ impl Yolo {
         &self.config
     }
 
+    /// 性能统计收集器
+    ///
+    /// 每次推理后自动更新，可用于运行时查询或重置。
+    pub fn stats(&self) -> &Arc<PerfStats> {
+        &self.stats
+    }
+
+    /// 原生推理器句柄
+    pub(crate) fn handle(&self) -> YoloInferenceHandle {
+        self.handle
+    }
+
+    /// 单张推理的 span，阶段耗时在推理结束后作为字段和子 span 记录
+    #[cfg(feature = "tracing")]
+    fn inference_span(&self) -> tracing::Span {
+        use tracing::field::Empty;
+        tracing::info_span!(
+            "yolo.inference",
+            engine_path = %self.config.engine_path,
+            image_path = Empty,
+            width = Empty,
+            height = Empty,
+            detection_count = Empty,
+            image_load_ms = Empty,
+            preprocess_ms = Empty,
+            execute_ms = Empty,
+            copy_back_ms = Empty,
+            postprocess_ms = Empty,
+        )
+    }
+
+    /// Rust 后处理的规则，使用创建时解析好的类别规则
+    pub(crate) fn rules(&self) -> Option<Rules<'_>> {
+        Rules::new(&self.config, &self.classes)
+    }
+
+    /// 把推理结果记入统计
+    pub(crate) fn track(
+        &self,
+        outcome: YoloResult<InferenceResult>,
+    ) -> YoloResult<InferenceResult> {
+        match &outcome {
+            Ok(result) => self.stats.record(result),
+            Err(e) => self.stats.record_error(e),
+        }
+        outcome
+    }
+
     /// 执行批量推理测试
     ///
+    /// 以纯引擎模式运行 [`Benchmark`](crate::Benchmark)，返回各阶段的平均耗时。
+    ///
     /// # 参数
     ///
-    /// * `_image_path` - 测试图片路径
+    /// * `_image_path` - 未使用，纯引擎模式不读取图片
     /// * `iterations` - 推理次数
     ///
     /// # 返回值
     ///
     /// 返回性能统计信息
-    ///
-    /// # 示例
-    ///
-    /// ```rust
-    /// let stats = yolo.batch_inference_test("images/test.jpg", 1000)?;
-    /// println!("平均 FPS: {:.1}", stats.fps());
-    /// ```
+    #[deprecated(
+        since = "0.2.0",
+        note = "请使用 `Benchmark`，它支持完整流水线、分位数延迟和 JSON 输出"
+    )]
     pub fn batch_inference_test(
         &self,
         _image_path: &str,
         iterations: usize,
     ) -> YoloResult<PerformanceBreakdown> {
-        // 获取 TensorRT 缓冲区和流
-        let buffers = self.get_tensorrt_buffers()?;
-        let stream = self.get_cuda_stream()?;
-
-        // 预热
-        for _ in 0..10 {
-            self.tensorrt_inference_only(
-                buffers.input_buffer,
-                buffers.output_buffer,
-                buffers.output_seg_buffer,
-                stream,
-            )?;
-        }
-
-        // 批量推理
-        let start = std::time::Instant::now();
-        for _ in 0..iterations {
-            self.tensorrt_inference_only(
-                buffers.input_buffer,
-                buffers.output_buffer,
-                buffers.output_seg_buffer,
-                stream,
-            )?;
-        }
-        let total_time = start.elapsed();
-
-        let avg_time = total_time.as_millis() as f64 / iterations as f64;
+        let report = Benchmark::new(self)
+            .with_mode(BenchmarkMode::EngineOnly)
+            .with_iterations(iterations)
+            .run()?;
 
+        let mean = |stage: &str| report.stage(stage).map_or(0.0, |s| s.mean_ms);
         Ok(PerformanceBreakdown {
-            total_time_ms: avg_time,
+            total_time_ms: report.latency.mean_ms,
             image_read_time_ms: 0.0,
             preprocess_time_ms: 0.0,
-            tensorrt_time_ms: avg_time,
+            tensorrt_time_ms: mean("tensorrt"),
             postprocess_time_ms: 0.0,
             result_copy_time_ms: 0.0,
+            gpu_preprocess_time_ms: 0.0,
+            gpu_tensorrt_time_ms: 0.0,
+            gpu_copy_time_ms: 0.0,
+            queue_wait_ms: 0.0,
         })
     }
 }
@@ -426,6 +706,12 @@ impl Drop for Yolo {
     }
 }
 
+// SAFETY: 原生句柄由 `Yolo` 独占，所有状态（执行上下文、CUDA 流、预处理缓冲区）都属于该实例，
+// 错误信息按线程保存。CUDA 的当前设备按线程保存，C API 每个访问 GPU 的入口（包括释放）
+// 都先切换到推理器所在的 GPU，因此可以把推理器移动到其他线程。
+// `Yolo` 不实现 `Sync`：同一执行上下文不能被并发使用，共享访问请使用 `SharedYolo`。
+unsafe impl Send for Yolo {}
+
 // C API 函数声明
 extern "C" {
     fn yolo_create_inference(
@@ -444,8 +730,38 @@ extern "C" {
         result: *const YoloResultRaw,
         output_path: *const c_char,
     ) -> bool;
+    fn yolo_inference_from_memory(
+        handle: YoloInferenceHandle,
+        image_data: *const u8,
+        width: c_int,
+        height: c_int,
+        channels: c_int,
+        result: *mut YoloResultRaw,
+    ) -> bool;
+    fn yolo_inference_batch_from_memory(
+        handle: YoloInferenceHandle,
+        images: *const *const u8,
+        widths: *const c_int,
+        heights: *const c_int,
+        batch_size: c_int,
+        results: *mut YoloResultRaw,
+        skip_mask_copy: bool,
+    ) -> bool;
+    fn yolo_is_poisoned(handle: YoloInferenceHandle) -> bool;
+    fn yolo_recover(handle: YoloInferenceHandle) -> bool;
+    fn yolo_get_max_batch_size(handle: YoloInferenceHandle) -> c_int;
+    fn yolo_get_model_info(handle: YoloInferenceHandle, info: *mut YoloModelInfo) -> bool;
+    fn yolo_get_tensor_count(handle: YoloInferenceHandle) -> c_int;
+    fn yolo_get_tensor_info(
+        handle: YoloInferenceHandle,
+        index: c_int,
+        info: *mut YoloTensorInfo,
+    ) -> bool;
     fn yolo_free_result(result: *mut YoloResultRaw);
     fn yolo_get_last_error() -> *const c_char;
+    fn yolo_get_last_error_code() -> c_int;
+    fn yolo_get_last_error_path() -> *const c_char;
+    fn yolo_get_last_cuda_error() -> c_int;
     fn yolo_tensorrt_inference_only(
         handle: YoloInferenceHandle,
         input_buffer: *mut c_void,
@@ -466,9 +782,126 @@ extern "C" {
         output_seg_buffer: *mut *mut c_void,
     ) -> bool;
     fn yolo_get_cuda_stream(handle: YoloInferenceHandle) -> *mut c_void;
+    fn yolo_synchronize(handle: YoloInferenceHandle) -> bool;
+}
+
+/// 把推理结果的检测数量和各阶段耗时写入 span
+#[cfg(feature = "tracing")]
+fn record_span(span: &tracing::Span, outcome: &YoloResult<InferenceResult>) {
+    if let Ok(result) = outcome {
+        span.record("detection_count", result.detection_count());
+        span.record("image_load_ms", result.image_read_time_ms);
+        span.record("preprocess_ms", result.gpu_preprocess_time_ms);
+        span.record("execute_ms", result.gpu_tensorrt_time_ms);
+        span.record("copy_back_ms", result.gpu_copy_time_ms);
+        span.record("postprocess_ms", result.postprocess_time_ms);
+    }
+}
+
+/// 按 C API 返回的阶段耗时在当前 span 下创建 `preprocess`、`execute`、`copy_back` 子 span
+///
+/// C++ 核心在一次调用中完成各阶段，这些子 span 在调用返回后才创建，
+/// 实际耗时记录在 `duration_ms` 字段中。`native_postprocess` 为 `true` 时
+/// 还创建 `nms` 和 `mask_decode`，由 Rust 完成后处理时这两个 span 在后处理中实时创建。
+#[cfg(feature = "tracing")]
+pub(crate) fn stage_spans(raw_result: &YoloResultRaw, native_postprocess: bool) {
+    let parent = tracing::Span::current();
+    tracing::info_span!(
+        parent: &parent,
+        "preprocess",
+        duration_ms = raw_result.gpu_preprocess_time_ms,
+    );
+    tracing::info_span!(
+        parent: &parent,
+        "execute",
+        duration_ms = raw_result.gpu_tensorrt_time_ms,
+    );
+    tracing::info_span!(
+        parent: &parent,
+        "copy_back",
+        duration_ms = raw_result.gpu_copy_time_ms,
+    );
+    if native_postprocess {
+        tracing::info_span!(
+            parent: &parent,
+            "nms",
+            duration_ms = raw_result.postprocess_time_ms,
+        );
+        tracing::info_span!(
+            parent: &parent,
+            "mask_decode",
+            duration_ms = raw_result.mask_decode_time_ms,
+            detection_count = raw_result.num_detections,
+        );
+    }
 }
 
-fn last_error() -> String {
+/// 把 C API 返回的原始结果转换为 `InferenceResult`，并释放原始结果
+///
+/// 姿态模型带上关键点，旋转框模型带上旋转框，`task` 应与引擎一致。
+pub(crate) fn take_raw_result(raw_result: &mut YoloResultRaw, task: Task) -> InferenceResult {
+    // 转换结果
+    let mut result = InferenceResult::new();
+    result.total_time_ms = raw_result.inference_time_ms;
+    result.image_read_time_ms = raw_result.image_read_time_ms;
+    result.preprocess_time_ms = raw_result.preprocess_time_ms;
+    result.tensorrt_time_ms = raw_result.tensorrt_time_ms;
+    result.postprocess_time_ms = raw_result.postprocess_time_ms;
+    result.result_copy_time_ms = raw_result.result_copy_time_ms;
+    result.gpu_preprocess_time_ms = raw_result.gpu_preprocess_time_ms;
+    result.gpu_tensorrt_time_ms = raw_result.gpu_tensorrt_time_ms;
+    result.gpu_copy_time_ms = raw_result.gpu_copy_time_ms;
+
+    // 转换检测结果
+    if !raw_result.detections.is_null() && raw_result.num_detections > 0 {
+        for i in 0..raw_result.num_detections {
+            let detection_ptr = unsafe { raw_result.detections.offset(i as isize) };
+            let raw_detection = unsafe { &*detection_ptr };
+
+            // 按任务类型解释关键点和旋转角度，与 Rust 后处理的结果一致
+            let mut candidate = RawCandidate::new(
+                raw_detection.bbox,
+                raw_detection.confidence,
+                raw_detection.class_id,
+            );
+            for (keypoint, raw) in candidate
+                .keypoints
+                .iter_mut()
+                .zip(raw_detection.keypoints.chunks_exact(3))
+            {
+                keypoint.copy_from_slice(raw);
+            }
+            candidate.angle = raw_detection.angle;
+            let mut detection = postprocess::to_detection(&candidate, task);
+
+            // 处理分割掩码
+            if !raw_detection.mask_data.is_null()
+                && raw_detection.mask_width > 0
+                && raw_detection.mask_height > 0
+            {
+                let mask_size = (raw_detection.mask_width * raw_detection.mask_height) as usize;
+                let mask_data =
+                    unsafe { std::slice::from_raw_parts(raw_detection.mask_data, mask_size) }
+                        .to_vec();
+
+                detection = detection.with_mask(
+                    mask_data,
+                    raw_detection.mask_width,
+                    raw_detection.mask_height,
+                );
+            }
+
+            result.add_detection(detection);
+        }
+    }
+
+    // 释放原始结果
+    unsafe { yolo_free_result(raw_result) };
+
+    result
+}
+
+pub(crate) fn last_error() -> String {
     unsafe {
         let error_ptr = yolo_get_last_error();
         if error_ptr.is_null() {
@@ -480,3 +913,24 @@ fn last_error() -> String {
         }
     }
 }
+
+/// 读取当前线程最后一次原生错误，按错误码转换为对应的错误
+///
+/// 没有错误码（旧版本核心库或未分类的错误）时用 `fallback` 包装错误信息。
+pub(crate) fn last_native_error(fallback: impl FnOnce(String) -> YoloError) -> YoloError {
+    let code = unsafe { yolo_get_last_error_code() };
+    let message = last_error();
+    let Some(code) = ErrorCode::from_raw(code) else {
+        return fallback(message);
+    };
+
+    let mut error = NativeError::new(code, message);
+    let path = unsafe { CStr::from_ptr(yolo_get_last_error_path()) }.to_string_lossy();
+    if !path.is_empty() {
+        error = error.with_path(path);
+    }
+    if code == ErrorCode::Cuda {
+        error = error.with_cuda_error(unsafe { yolo_get_last_cuda_error() });
+    }
+    YoloError::from_native(error, fallback)
+}
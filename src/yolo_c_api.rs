@@ -9,6 +9,8 @@ pub struct YoloDetection {
     pub mask_data: *mut f32,
     pub mask_width: c_int,
     pub mask_height: c_int,
+    pub keypoints: [f32; 51],
+    pub angle: f32,
 }
 
 #[repr(C)]
@@ -23,6 +25,13 @@ pub struct YoloResult {
     pub tensorrt_time_ms: f64,    // TensorRT推理时间
     pub postprocess_time_ms: f64, // 后处理时间
     pub result_copy_time_ms: f64, // 结果复制时间
+
+    // 设备侧时间（CUDA事件测量）
+    pub gpu_preprocess_time_ms: f64, // 图像上传和预处理内核
+    pub gpu_tensorrt_time_ms: f64,   // 引擎执行
+    pub gpu_copy_time_ms: f64,       // 输出拷贝回主机
+
+    pub mask_decode_time_ms: f64, // 掩码解码时间
 }
 
 pub type YoloInferenceHandle = *mut c_void;
@@ -96,6 +105,10 @@ impl Yolo {
             tensorrt_time_ms: 0.0,
             postprocess_time_ms: 0.0,
             result_copy_time_ms: 0.0,
+            gpu_preprocess_time_ms: 0.0,
+            gpu_tensorrt_time_ms: 0.0,
+            gpu_copy_time_ms: 0.0,
+            mask_decode_time_ms: 0.0,
         };
         let ok = unsafe { yolo_inference(self.handle, image_c.as_ptr(), &mut result) };
         if !ok {
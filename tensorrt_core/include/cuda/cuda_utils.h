@@ -2,18 +2,39 @@
 #define TRTX_CUDA_UTILS_H_
 
 #include <cuda_runtime_api.h>
-#include <iostream>
-#include <cassert>
+#include <string>
+#include "yolo/error.h"
+#include "yolo/logging.h"
+
+// 把失败的CUDA调用转换为异常，由 C API 入口转换为错误码并标记推理器失效
+inline void throw_cuda_error(cudaError_t error_code, const char* call, const char* file, int line) {
+    std::string message = std::string(call) + " failed: " + cudaGetErrorString(error_code) + " (" +
+                          cudaGetErrorName(error_code) + ") at " + file + ":" + std::to_string(line);
+    YoloErrorCode code = error_code == cudaErrorMemoryAllocation ? YOLO_ERROR_OUT_OF_MEMORY : YOLO_ERROR_CUDA;
+    throw YoloException(code, message, "", static_cast<int>(error_code));
+}
 
 #ifndef CUDA_CHECK
-#define CUDA_CHECK(callstr)                                                                    \
-    {                                                                                          \
-        cudaError_t error_code = callstr;                                                      \
-        if (error_code != cudaSuccess) {                                                       \
-            std::cerr << "CUDA error " << error_code << " at " << __FILE__ << ":" << __LINE__; \
-            assert(0);                                                                         \
-        }                                                                                      \
+#define CUDA_CHECK(callstr)                                                  \
+    {                                                                        \
+        cudaError_t error_code = callstr;                                    \
+        if (error_code != cudaSuccess) {                                     \
+            throw_cuda_error(error_code, #callstr, __FILE__, __LINE__);      \
+        }                                                                    \
     }
 #endif  // CUDA_CHECK
 
+// 不能抛出异常的位置（析构、TensorRT 插件回调）只记录错误，
+// 失败会在后续的流同步中以 CUDA_CHECK 的形式暴露出来
+#ifndef CUDA_WARN
+#define CUDA_WARN(callstr)                                                                        \
+    {                                                                                             \
+        cudaError_t error_code = callstr;                                                         \
+        if (error_code != cudaSuccess) {                                                          \
+            gLogger.log(nvinfer1::ILogger::Severity::kERROR,                                      \
+                        (std::string(#callstr) + " failed: " + cudaGetErrorString(error_code)).c_str()); \
+        }                                                                                         \
+    }
+#endif  // CUDA_WARN
+
 #endif  // TRTX_CUDA_UTILS_H_
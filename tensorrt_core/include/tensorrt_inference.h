@@ -58,7 +58,8 @@ bool tensorrt_get_output_sizes(TensorRTHandle handle,
 
 /**
  * 获取错误信息
- * @return 最后一次错误的描述字符串
+ * 错误信息按线程保存，需在失败调用所在的线程上读取
+ * @return 当前线程最后一次错误的描述字符串
  */
 const char* tensorrt_get_last_error(void);
 
@@ -4,15 +4,17 @@
 // #define USE_FP32
 // #define USE_INT8
 
-const static char* kInputTensorName = "images";
+// 优先匹配的输出张量名称，名称不同的引擎按形状区分检测输出和分割原型
 const static char* kOutputTensorName = "output";
 const static char* kProtoTensorName = "proto";
-const static int kNumClass = 80;
 const static int kBatchSize = 1;
-const static int kGpuId = 0;
-const static int kInputH = 640;
-const static int kInputW = 640;
 const static float kNmsThresh = 0.45f;
 const static float kConfThresh = 0.5f;
 const static int kMaxInputImageSize = 3000 * 3000;
+
+// 以下只是回退默认值：加载时优先取引擎张量的形状和 .meta 元数据，
+// 只有引擎和元数据都没有给出时才会使用，修改它们不会改变已有引擎的输入尺寸或输出容量
+const static int kGpuId = 0;
+const static int kInputH = 640;
+const static int kInputW = 640;
 const static int kMaxNumOutputBbox = 1000;
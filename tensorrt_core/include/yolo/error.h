@@ -0,0 +1,22 @@
+#pragma once
+
+#include "yolo_c_api.h"
+#include <stdexcept>
+#include <string>
+
+// 核心库内部抛出的带错误码的异常，由 C API 入口统一转换为线程局部的错误信息
+class YoloException : public std::runtime_error {
+public:
+    YoloException(YoloErrorCode code, const std::string& message, const std::string& path = "",
+                  int cuda_error = 0)
+        : std::runtime_error(message), code_(code), path_(path), cuda_error_(cuda_error) {}
+
+    YoloErrorCode code() const noexcept { return code_; }
+    const std::string& path() const noexcept { return path_; }
+    int cuda_error() const noexcept { return cuda_error_; }
+
+private:
+    YoloErrorCode code_;
+    std::string path_;
+    int cuda_error_;
+};
@@ -1,15 +1,23 @@
 #pragma once
 
 #include "NvInfer.h"
-#include <iostream>
+#include "yolo_c_api.h"
+#include <atomic>
 
+// 核心库的全局日志器：TensorRT 运行时和 C API 的错误信息都经由它输出。
+// 设置了回调时转发给调用方（Rust 侧接入 log/tracing），否则写到标准输出/标准错误。
 class Logger : public nvinfer1::ILogger {
 public:
-    void log(Severity severity, const char* msg) noexcept override {
-        // suppress info-level messages
-        if (severity <= Severity::kWARNING)
-            std::cout << msg << std::endl;
-    }
+    void log(Severity severity, const char* msg) noexcept override;
+
+    void set_callback(YoloLogCallback callback) noexcept { callback_.store(callback); }
+
+    void set_max_severity(int max_severity) noexcept { max_severity_.store(max_severity); }
+
+private:
+    std::atomic<YoloLogCallback> callback_{nullptr};
+    // 默认只输出警告及以上
+    std::atomic<int> max_severity_{static_cast<int>(Severity::kWARNING)};
 };
 
 extern Logger gLogger;
@@ -5,10 +5,10 @@
 #include "types.h"
 
 // Preprocessing functions
-cv::Rect get_rect(cv::Mat& img, float bbox[4]);
+cv::Rect get_rect(cv::Mat& img, float bbox[4], int input_w, int input_h);
 
 // NMS functions
-void nms(std::vector<Detection>& res, float* output, float conf_thresh, float nms_thresh = 0.5);
+void nms(std::vector<Detection>& res, float* output, int output_size, float conf_thresh, float nms_thresh = 0.5);
 
 void batch_nms(std::vector<std::vector<Detection>>& batch_res, float* output, int batch_size, int output_size,
                float conf_thresh, float nms_thresh = 0.5);
@@ -21,4 +21,4 @@ void cuda_nms(float* parray, float nms_threshold, int max_objects, cudaStream_t
 
 // Drawing functions
 void draw_mask_bbox(cv::Mat& img, std::vector<Detection>& dets, std::vector<cv::Mat>& masks,
-                    std::unordered_map<int, std::string>& labels_map);
+                    std::unordered_map<int, std::string>& labels_map, int input_w, int input_h);
@@ -5,12 +5,19 @@
 #include "NvInfer.h"
 #include "types.h"
 
-void cuda_preprocess_init(int max_image_size);
+// 每个推理器独立持有的预处理中转缓冲区
+struct PreprocessBuffers {
+    uint8_t* img_buffer_host = nullptr;    // pinned memory
+    uint8_t* img_buffer_device = nullptr;  // device memory
+    int max_image_size = 0;                // 最大像素数
+};
 
-void cuda_preprocess_destroy();
+void cuda_preprocess_init(PreprocessBuffers& buffers, int max_image_size);
 
-void cuda_preprocess(uint8_t* src, int src_width, int src_height, float* dst, int dst_width, int dst_height,
-                     cudaStream_t stream);
+void cuda_preprocess_destroy(PreprocessBuffers& buffers);
 
-void cuda_batch_preprocess(std::vector<cv::Mat>& img_batch, float* dst, int dst_width, int dst_height,
-                           cudaStream_t stream);
+void cuda_preprocess(PreprocessBuffers& buffers, uint8_t* src, int src_width, int src_height, float* dst,
+                     int dst_width, int dst_height, cudaStream_t stream);
+
+void cuda_batch_preprocess(PreprocessBuffers& buffers, std::vector<cv::Mat>& img_batch, float* dst, int dst_width,
+                           int dst_height, cudaStream_t stream);
@@ -2,7 +2,7 @@
 #include "config.h"
 
 struct alignas(float) Detection {
-    //center_x center_y w h
+    // x1 y1 x2 y2，旋转框为 center_x center_y w h
     float bbox[4];
     float conf;  // bbox_conf * cls_conf
     float class_id;
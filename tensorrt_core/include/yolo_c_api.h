@@ -10,12 +10,14 @@ extern "C" {
 
 // 检测结果结构体
 typedef struct {
-    float bbox[4];      // x, y, w, h
+    float bbox[4];      // x1, y1, x2, y2（旋转框模型为 cx, cy, w, h）
     float confidence;   // 置信度
     int class_id;       // 类别ID
     float* mask_data;   // 掩码数据指针
     int mask_width;     // 掩码宽度
     int mask_height;    // 掩码高度
+    float keypoints[17 * 3];  // 姿态关键点 x, y, 置信度，无效的关键点为 -1（仅姿态模型）
+    float angle;              // 旋转框角度，弧度（仅旋转框模型）
 } YoloDetection;
 
 // 推理结果结构体
@@ -26,17 +28,98 @@ typedef struct {
     
     // 详细时间分解
     double image_read_time_ms;      // 图片读取时间
-    double preprocess_time_ms;      // 预处理时间
-    double tensorrt_time_ms;        // TensorRT推理时间
+    double preprocess_time_ms;      // 预处理时间（主机侧，含图像拷贝和内核提交）
+    double tensorrt_time_ms;        // TensorRT推理提交时间（主机侧，不等待GPU完成）
     double postprocess_time_ms;     // 后处理时间
-    double result_copy_time_ms;     // 结果复制时间
+    double result_copy_time_ms;     // 结果复制时间（主机侧，含等待GPU完成）
+    
+    // 设备侧时间（CUDA事件测量）
+    double gpu_preprocess_time_ms;  // 图像上传和预处理内核
+    double gpu_tensorrt_time_ms;    // 引擎执行
+    double gpu_copy_time_ms;        // 输出拷贝回主机
+
+    double mask_decode_time_ms;     // 掩码解码时间（不计入 inference_time_ms）
 } YoloResult;
 
+// 张量的最大维数，与 nvinfer1::Dims::MAX_DIMS 一致
+#define YOLO_MAX_TENSOR_DIMS 8
+
+// 张量名称的最大长度（含结尾的 '\0'）
+#define YOLO_MAX_TENSOR_NAME 64
+
+// 张量在推理器中的用途
+typedef enum {
+    YOLO_TENSOR_INPUT = 0,     // 图片输入
+    YOLO_TENSOR_OUTPUT = 1,    // 检测输出
+    YOLO_TENSOR_PROTO = 2      // 分割原型（仅分割模型）
+} YoloTensorRole;
+
+// 引擎输入输出张量
+typedef struct {
+    char name[YOLO_MAX_TENSOR_NAME];  // 张量名称
+    YoloTensorRole role;              // 用途
+    int num_dims;                     // 维数
+    int64_t dims[YOLO_MAX_TENSOR_DIMS];  // 引擎中的形状，动态维度为 -1
+} YoloTensorInfo;
+
+// 模型信息，从引擎张量形状读取，引擎中没有的由 sidecar 元数据文件补充
+typedef struct {
+    int gpu_id;           // 推理所在的GPU
+    int input_width;      // 网络输入宽度
+    int input_height;     // 网络输入高度
+    int num_classes;      // 类别数，引擎和元数据都没有给出时为 0
+    int max_detections;   // 检测输出最多容纳的候选框数量
+    int max_batch_size;   // 引擎支持的最大批次
+    int proto_width;      // 分割原型宽度，没有分割原型输出时为 0
+    int proto_height;     // 分割原型高度，没有分割原型输出时为 0
+} YoloModelInfo;
+
+// 错误码，失败的调用通过 yolo_get_last_error_code 读取
+typedef enum {
+    YOLO_OK = 0,
+    YOLO_ERROR_INVALID_ARGUMENT = 1,         // 参数无效（空指针、越界的批次或槽位等）
+    YOLO_ERROR_FILE_NOT_FOUND = 2,           // 文件不存在或无法打开
+    YOLO_ERROR_IMAGE_DECODE = 3,             // 图片解码失败
+    YOLO_ERROR_ENGINE_DESERIALIZATION = 4,   // 引擎反序列化失败
+    YOLO_ERROR_CUDA = 5,                     // CUDA 调用失败，具体错误见 yolo_get_last_cuda_error
+    YOLO_ERROR_TENSORRT = 6,                 // TensorRT 调用失败
+    YOLO_ERROR_OUT_OF_MEMORY = 7,            // 主机或显存不足
+    YOLO_ERROR_IO = 8,                       // 文件写入失败
+    YOLO_ERROR_UNKNOWN = 99                  // 其他错误
+} YoloErrorCode;
+
 // YOLO推理器句柄
 typedef void* YoloInferenceHandle;
 
+// 流水线句柄
+typedef void* YoloPipelineHandle;
+
+/**
+ * 日志回调
+ * @param severity 日志级别，与 TensorRT 的 ILogger::Severity 一致：
+ *                 0 内部错误，1 错误，2 警告，3 信息，4 详细
+ * @param message 日志内容，只在回调期间有效
+ */
+typedef void (*YoloLogCallback)(int severity, const char* message);
+
+/**
+ * 设置日志回调（进程级，对 TensorRT 运行时和所有推理器生效）
+ * 回调可能在任意线程上被调用，传 NULL 恢复输出到标准输出/标准错误
+ * @param callback 日志回调
+ */
+void yolo_set_log_callback(YoloLogCallback callback);
+
+/**
+ * 设置输出的最高日志级别（进程级），默认 2，即只输出警告及以上
+ * @param max_severity 最高日志级别，取值同 YoloLogCallback 的 severity
+ */
+void yolo_set_log_level(int max_severity);
+
 /**
  * 创建YOLO推理器
+ * 输入尺寸、检测输出容量和分割原型尺寸从引擎张量形状读取。
+ * 引擎旁的 <engine_path>.meta 文件（可选，每行一个 key=value，# 开头为注释）
+ * 提供引擎中没有的信息：gpu_id、num_classes，以及动态输入时的 input_width、input_height
  * @param engine_path TensorRT引擎文件路径
  * @param labels_path 标签文件路径
  * @return 推理器句柄，失败返回NULL
@@ -49,6 +132,26 @@ YoloInferenceHandle yolo_create_inference(const char* engine_path, const char* l
  */
 void yolo_destroy_inference(YoloInferenceHandle handle);
 
+/**
+ * 查询推理器是否已失效
+ * CUDA调用失败后推理器进入失效状态，之后的推理调用都会返回 YOLO_ERROR_CUDA，
+ * 直到 yolo_recover 成功
+ * @param handle 推理器句柄
+ * @return 已失效返回true
+ */
+bool yolo_is_poisoned(YoloInferenceHandle handle);
+
+/**
+ * 恢复推理器：释放并按原路径重新创建引擎、执行上下文、CUDA流和缓冲区
+ * 同一进程中的恢复是串行的。如果CUDA上下文已损坏（粘滞错误），需要重置整个设备，
+ * 此时同一GPU上还有其他存活的推理器会返回 YOLO_ERROR_CUDA 并保持失效，
+ * 销毁其他推理器后再重试，或者重新创建进程。
+ * 调用前必须销毁基于该推理器创建的所有流水线
+ * @param handle 推理器句柄
+ * @return 成功返回true，失败返回false（推理器保持失效状态，可以稍后重试）
+ */
+bool yolo_recover(YoloInferenceHandle handle);
+
 /**
  * 执行推理
  * @param handle 推理器句柄
@@ -99,6 +202,102 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
                                      int width, int height, int channels,
                                      YoloResult* result, bool skip_mask_copy);
 
+/**
+ * 批量执行推理（从内存数据，BGR 3通道）
+ * 一次引擎调用处理整个批次，每张图片的结果分别写入 results
+ * @param handle 推理器句柄
+ * @param images 图片数据指针数组
+ * @param widths 图片宽度数组
+ * @param heights 图片高度数组
+ * @param batch_size 批次大小，不能超过 yolo_get_max_batch_size 的返回值
+ * @param results 输出结果数组（batch_size 个），每个结果需调用 yolo_free_result 释放
+ * @param skip_mask_copy 是否跳过掩码数据拷贝
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_inference_batch_from_memory(YoloInferenceHandle handle,
+                                      const uint8_t* const* images,
+                                      const int* widths,
+                                      const int* heights,
+                                      int batch_size,
+                                      YoloResult* results,
+                                      bool skip_mask_copy);
+
+/**
+ * 批量执行预处理、引擎推理和结果拷贝，不做 NMS 和掩码解码（从内存数据，BGR 3通道）
+ * 供调用方自行后处理：成功后用 yolo_get_host_outputs 读取拷贝回主机的原始输出
+ * @param handle 推理器句柄
+ * @param images 图片数据指针数组
+ * @param widths 图片宽度数组
+ * @param heights 图片高度数组
+ * @param batch_size 批次大小，不能超过 yolo_get_max_batch_size 的返回值
+ * @param results 输出结果数组（batch_size 个），只填充耗时，不包含检测结果
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_inference_raw_batch_from_memory(YoloInferenceHandle handle,
+                                          const uint8_t* const* images,
+                                          const int* widths,
+                                          const int* heights,
+                                          int batch_size,
+                                          YoloResult* results);
+
+/**
+ * 获取最近一次推理拷贝回主机的原始输出
+ * 缓冲区属于推理器，下一次推理时会被覆盖
+ * @param handle 推理器句柄
+ * @param output 输出检测输出缓冲区，每张图片 1 + max_detections * sizeof(Detection) / sizeof(float) 个 float
+ * @param proto 输出分割原型缓冲区，每张图片 32 * proto_height * proto_width 个 float，
+ *              引擎没有分割原型输出时为 NULL
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_get_host_outputs(YoloInferenceHandle handle, const float** output, const float** proto);
+
+/**
+ * 在主机上解码单个检测框的掩码，与推理时的掩码后处理走同一实现，不需要GPU
+ * @param proto 单张图片的分割原型，32 * proto_height * proto_width 个 float
+ * @param proto_w 原型宽度
+ * @param proto_h 原型高度
+ * @param input_w 网络输入宽度
+ * @param input_h 网络输入高度
+ * @param bbox 网络输入坐标系下的边界框 [x1, y1, x2, y2]
+ * @param coefficients 32 个掩码系数
+ * @param mask 输出掩码，input_w * input_h 个 float
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_decode_mask(const float* proto, int proto_w, int proto_h, int input_w, int input_h,
+                      const float bbox[4], const float* coefficients, float* mask);
+
+/**
+ * 获取引擎支持的最大批次大小
+ * @param handle 推理器句柄
+ * @return 最大批次大小，失败返回0
+ */
+int yolo_get_max_batch_size(YoloInferenceHandle handle);
+
+/**
+ * 获取加载时从引擎发现的输入输出张量数量
+ * 检测模型没有分割原型输出，只有输入和检测输出两个张量
+ * @param handle 推理器句柄
+ * @return 张量数量，失败返回-1
+ */
+int yolo_get_tensor_count(YoloInferenceHandle handle);
+
+/**
+ * 获取加载时从引擎读出的模型信息
+ * @param handle 推理器句柄
+ * @param info 输出模型信息
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_get_model_info(YoloInferenceHandle handle, YoloModelInfo* info);
+
+/**
+ * 获取加载时从引擎发现的输入输出张量
+ * @param handle 推理器句柄
+ * @param index 张量索引，0 到 yolo_get_tensor_count 的返回值减一
+ * @param info 输出张量信息
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_get_tensor_info(YoloInferenceHandle handle, int index, YoloTensorInfo* info);
+
 /**
  * 保存推理结果图片
  * @param handle 推理器句柄
@@ -120,16 +319,36 @@ void yolo_free_result(YoloResult* result);
 
 /**
  * 获取错误信息
- * @return 最后一次错误的描述字符串
+ * 错误信息按线程保存，需在失败调用所在的线程上读取
+ * @return 当前线程最后一次错误的描述字符串
  */
 const char* yolo_get_last_error(void);
 
+/**
+ * 获取错误码
+ * 与 yolo_get_last_error 一样按线程保存，成功的调用不会清除
+ * @return 当前线程最后一次错误的错误码，没有错误时返回 YOLO_OK
+ */
+YoloErrorCode yolo_get_last_error_code(void);
+
+/**
+ * 获取与错误相关的文件路径（引擎、图片或标签文件）
+ * @return 当前线程最后一次错误涉及的路径，没有时返回空字符串
+ */
+const char* yolo_get_last_error_path(void);
+
+/**
+ * 获取CUDA错误码
+ * @return 错误码为 YOLO_ERROR_CUDA 时对应的 cudaError_t，否则返回0
+ */
+int yolo_get_last_cuda_error(void);
+
 /**
  * 执行推理（纯TensorRT推理，无预处理和后处理）
  * @param handle 推理器句柄
  * @param input_buffer GPU输入缓冲区指针
  * @param output_buffer GPU输出缓冲区指针
- * @param output_seg_buffer GPU分割输出缓冲区指针
+ * @param output_seg_buffer GPU分割输出缓冲区指针，引擎没有分割原型输出时可以为 NULL
  * @param stream CUDA流
  * @return 成功返回true，失败返回false
  */
@@ -144,7 +363,7 @@ bool yolo_tensorrt_inference_only(YoloInferenceHandle handle,
  * @param handle 推理器句柄
  * @param input_size 输出输入大小
  * @param output_size 输出检测输出大小
- * @param output_seg_size 输出分割输出大小
+ * @param output_seg_size 输出分割输出大小，引擎没有分割原型输出时为 0
  * @return 成功返回true，失败返回false
  */
 bool yolo_get_tensorrt_info(YoloInferenceHandle handle,
@@ -157,7 +376,7 @@ bool yolo_get_tensorrt_info(YoloInferenceHandle handle,
  * @param handle 推理器句柄
  * @param input_buffer 输出输入缓冲区地址
  * @param output_buffer 输出检测输出缓冲区地址
- * @param output_seg_buffer 输出分割输出缓冲区地址
+ * @param output_seg_buffer 输出分割输出缓冲区地址，引擎没有分割原型输出时为 NULL
  * @return 成功返回true，失败返回false
  */
 bool yolo_get_tensorrt_buffers(YoloInferenceHandle handle,
@@ -172,6 +391,71 @@ bool yolo_get_tensorrt_buffers(YoloInferenceHandle handle,
  */
 void* yolo_get_cuda_stream(YoloInferenceHandle handle);
 
+/**
+ * 等待推理器CUDA流上已提交的工作全部完成
+ * 用于在纯TensorRT推理后获得准确的计时
+ * @param handle 推理器句柄
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_synchronize(YoloInferenceHandle handle);
+
+/**
+ * 创建推理流水线
+ * 每个槽位独立持有执行上下文、CUDA流和双缓冲的输入输出，
+ * 使一帧的预处理可以与其他帧的推理、拷贝和后处理重叠执行
+ * @param handle 推理器句柄，必须比流水线存活更久
+ * @param depth 槽位数量（同时在途的帧数）
+ * @return 流水线句柄，失败返回NULL
+ */
+YoloPipelineHandle yolo_pipeline_create(YoloInferenceHandle handle, int depth);
+
+/**
+ * 销毁推理流水线
+ * @param pipeline 流水线句柄
+ */
+void yolo_pipeline_destroy(YoloPipelineHandle pipeline);
+
+/**
+ * 向槽位提交一帧（BGR，3通道），异步执行预处理、推理和结果拷贝
+ * 返回后即可释放 image_data
+ * @param pipeline 流水线句柄
+ * @param slot 槽位索引，该槽位不能有未取回的帧
+ * @param image_data 图片数据指针
+ * @param width 图片宽度
+ * @param height 图片高度
+ * @param channels 图片通道数（必须为3）
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_pipeline_submit(YoloPipelineHandle pipeline,
+                          int slot,
+                          const uint8_t* image_data,
+                          int width, int height, int channels);
+
+/**
+ * 等待槽位中的帧完成并执行后处理
+ * @param pipeline 流水线句柄
+ * @param slot 槽位索引
+ * @param result 输出结果指针，使用后需调用 yolo_free_result
+ * @param skip_mask_copy 是否跳过掩码数据拷贝
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_pipeline_collect(YoloPipelineHandle pipeline, int slot, YoloResult* result, bool skip_mask_copy);
+
+/**
+ * 等待槽位中的帧完成，不做 NMS 和掩码解码，直接返回拷贝回主机的原始输出
+ * @param pipeline 流水线句柄
+ * @param slot 槽位索引
+ * @param result 输出结果指针，只填充耗时，不包含检测结果
+ * @param output 输出检测输出缓冲区，布局同 yolo_get_host_outputs，槽位再次提交前有效
+ * @param proto 输出分割原型缓冲区，槽位再次提交前有效，引擎没有分割原型输出时为 NULL
+ * @return 成功返回true，失败返回false
+ */
+bool yolo_pipeline_collect_raw(YoloPipelineHandle pipeline,
+                               int slot,
+                               YoloResult* result,
+                               const float** output,
+                               const float** proto);
+
 #ifdef __cplusplus
 }
 #endif
@@ -1,8 +1,6 @@
 #include "cuda/cuda_utils.h"
 #include "yolo/preprocess.h"
-
-static uint8_t* img_buffer_host = nullptr;
-static uint8_t* img_buffer_device = nullptr;
+#include <stdexcept>
 
 __global__ void warpaffine_kernel(uint8_t* src, int src_line_size, int src_width, int src_height, float* dst,
                                   int dst_width, int dst_height, uint8_t const_value_st, AffineMatrix d2s, int edge) {
@@ -86,13 +84,17 @@ __global__ void warpaffine_kernel(uint8_t* src, int src_line_size, int src_width
     *pdst_c2 = c2;
 }
 
-void cuda_preprocess(uint8_t* src, int src_width, int src_height, float* dst, int dst_width, int dst_height,
-                     cudaStream_t stream) {
+void cuda_preprocess(PreprocessBuffers& buffers, uint8_t* src, int src_width, int src_height, float* dst,
+                     int dst_width, int dst_height, cudaStream_t stream) {
+    if (src_width * src_height > buffers.max_image_size) {
+        throw std::invalid_argument("Image exceeds preprocess buffer size");
+    }
     int img_size = src_width * src_height * 3;
     // copy data to pinned memory
-    memcpy(img_buffer_host, src, img_size);
+    memcpy(buffers.img_buffer_host, src, img_size);
     // copy data to device memory
-    CUDA_CHECK(cudaMemcpyAsync(img_buffer_device, img_buffer_host, img_size, cudaMemcpyHostToDevice, stream));
+    CUDA_CHECK(cudaMemcpyAsync(buffers.img_buffer_device, buffers.img_buffer_host, img_size, cudaMemcpyHostToDevice,
+                               stream));
 
     AffineMatrix s2d, d2s;
     float scale = std::min(dst_height / (float)src_height, dst_width / (float)src_width);
@@ -112,28 +114,36 @@ void cuda_preprocess(uint8_t* src, int src_width, int src_height, float* dst, in
     int jobs = dst_height * dst_width;
     int threads = 256;
     int blocks = ceil(jobs / (float)threads);
-    warpaffine_kernel<<<blocks, threads, 0, stream>>>(img_buffer_device, src_width * 3, src_width, src_height, dst,
+    warpaffine_kernel<<<blocks, threads, 0, stream>>>(buffers.img_buffer_device, src_width * 3, src_width, src_height, dst,
                                                       dst_width, dst_height, 128, d2s, jobs);
 }
 
-void cuda_batch_preprocess(std::vector<cv::Mat>& img_batch, float* dst, int dst_width, int dst_height,
-                           cudaStream_t stream) {
+void cuda_batch_preprocess(PreprocessBuffers& buffers, std::vector<cv::Mat>& img_batch, float* dst, int dst_width,
+                           int dst_height, cudaStream_t stream) {
     int dst_size = dst_width * dst_height * 3;
     for (size_t i = 0; i < img_batch.size(); i++) {
-        cuda_preprocess(img_batch[i].ptr(), img_batch[i].cols, img_batch[i].rows, &dst[dst_size * i], dst_width,
-                        dst_height, stream);
+        cuda_preprocess(buffers, img_batch[i].ptr(), img_batch[i].cols, img_batch[i].rows, &dst[dst_size * i],
+                        dst_width, dst_height, stream);
         CUDA_CHECK(cudaStreamSynchronize(stream));
     }
 }
 
-void cuda_preprocess_init(int max_image_size) {
+void cuda_preprocess_init(PreprocessBuffers& buffers, int max_image_size) {
     // prepare input data in pinned memory
-    CUDA_CHECK(cudaMallocHost((void**)&img_buffer_host, max_image_size * 3));
+    CUDA_CHECK(cudaMallocHost((void**)&buffers.img_buffer_host, max_image_size * 3));
     // prepare input data in device memory
-    CUDA_CHECK(cudaMalloc((void**)&img_buffer_device, max_image_size * 3));
+    CUDA_CHECK(cudaMalloc((void**)&buffers.img_buffer_device, max_image_size * 3));
+    buffers.max_image_size = max_image_size;
 }
 
-void cuda_preprocess_destroy() {
-    CUDA_CHECK(cudaFree(img_buffer_device));
-    CUDA_CHECK(cudaFreeHost(img_buffer_host));
+void cuda_preprocess_destroy(PreprocessBuffers& buffers) {
+    if (buffers.img_buffer_device) {
+        CUDA_WARN(cudaFree(buffers.img_buffer_device));
+        buffers.img_buffer_device = nullptr;
+    }
+    if (buffers.img_buffer_host) {
+        CUDA_WARN(cudaFreeHost(buffers.img_buffer_host));
+        buffers.img_buffer_host = nullptr;
+    }
+    buffers.max_image_size = 0;
 }
@@ -5,23 +5,12 @@
 #include <memory>
 #include <cuda_runtime.h>
 #include <NvInfer.h>
+#include "yolo/logging.h"
 
 using namespace nvinfer1;
 
-// 全局错误信息
-static std::string g_last_error;
-
-// 简单的Logger
-class SimpleLogger : public ILogger {
-public:
-    void log(Severity severity, const char* msg) noexcept override {
-        if (severity <= Severity::kWARNING) {
-            std::cout << msg << std::endl;
-        }
-    }
-};
-
-static SimpleLogger gLogger;
+// 错误信息按线程保存，避免并发调用互相覆盖
+static thread_local std::string g_last_error;
 
 // TensorRT推理器类
 class TensorRTInference {
@@ -66,7 +55,7 @@ public:
 // 设置错误信息
 static void set_error(const std::string& error) {
     g_last_error = error;
-    std::cerr << "TensorRT Error: " << error << std::endl;
+    gLogger.log(ILogger::Severity::kERROR, ("TensorRT Error: " + error).c_str());
 }
 
 TensorRTHandle tensorrt_create(const char* engine_path, 
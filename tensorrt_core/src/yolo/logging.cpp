@@ -1,3 +1,28 @@
 #include "yolo/logging.h"
 
+#include <iostream>
+
 Logger gLogger;
+
+void Logger::log(Severity severity, const char* msg) noexcept {
+    if (static_cast<int>(severity) > max_severity_.load()) {
+        return;
+    }
+
+    YoloLogCallback callback = callback_.load();
+    if (callback) {
+        callback(static_cast<int>(severity), msg);
+    } else if (severity <= Severity::kERROR) {
+        std::cerr << msg << std::endl;
+    } else {
+        std::cout << msg << std::endl;
+    }
+}
+
+void yolo_set_log_callback(YoloLogCallback callback) {
+    gLogger.set_callback(callback);
+}
+
+void yolo_set_log_level(int max_severity) {
+    gLogger.set_max_severity(max_severity);
+}
@@ -2,23 +2,23 @@
 #include "yolo/utils.h"
 #include "yolo/config.h"
 
-cv::Rect get_rect(cv::Mat& img, float bbox[4]) {
+cv::Rect get_rect(cv::Mat& img, float bbox[4], int input_w, int input_h) {
     float l, r, t, b;
-    float r_w = kInputW / (img.cols * 1.0);
-    float r_h = kInputH / (img.rows * 1.0);
+    float r_w = input_w / (img.cols * 1.0);
+    float r_h = input_h / (img.rows * 1.0);
 
     if (r_h > r_w) {
         l = bbox[0];
         r = bbox[2];
-        t = bbox[1] - (kInputH - r_w * img.rows) / 2;
-        b = bbox[3] - (kInputH - r_w * img.rows) / 2;
+        t = bbox[1] - (input_h - r_w * img.rows) / 2;
+        b = bbox[3] - (input_h - r_w * img.rows) / 2;
         l = l / r_w;
         r = r / r_w;
         t = t / r_w;
         b = b / r_w;
     } else {
-        l = bbox[0] - (kInputW - r_h * img.cols) / 2;
-        r = bbox[2] - (kInputW - r_h * img.cols) / 2;
+        l = bbox[0] - (input_w - r_h * img.cols) / 2;
+        r = bbox[2] - (input_w - r_h * img.cols) / 2;
         t = bbox[1];
         b = bbox[3];
         l = l / r_h;
@@ -57,11 +57,14 @@ static bool cmp(const Detection& a, const Detection& b) {
     return a.conf > b.conf;
 }
 
-void nms(std::vector<Detection>& res, float* output, float conf_thresh, float nms_thresh) {
+void nms(std::vector<Detection>& res, float* output, int output_size, float conf_thresh, float nms_thresh) {
     int det_size = sizeof(Detection) / sizeof(float);
     std::map<float, std::vector<Detection>> m;
 
-    for (int i = 0; i < output[0]; i++) {
+    // 插件报告的数量可能超过输出缓冲区容量（或为 NaN），按容量截断，避免越界读到下一张图片
+    const int capacity = (output_size - 1) / det_size;
+    const int count = output[0] > 0 ? (int)(std::min)(output[0], (float)capacity) : 0;
+    for (int i = 0; i < count; i++) {
         if (output[1 + det_size * i + 4] <= conf_thresh || isnan(output[1 + det_size * i + 4]))
             continue;
         Detection det;
@@ -90,23 +93,23 @@ void batch_nms(std::vector<std::vector<Detection>>& res_batch, float* output, in
                float conf_thresh, float nms_thresh) {
     res_batch.resize(batch_size);
     for (int i = 0; i < batch_size; i++) {
-        nms(res_batch[i], &output[i * output_size], conf_thresh, nms_thresh);
+        nms(res_batch[i], &output[i * output_size], output_size, conf_thresh, nms_thresh);
     }
 }
 
-static cv::Mat scale_mask(cv::Mat mask, cv::Mat img) {
+static cv::Mat scale_mask(cv::Mat mask, cv::Mat img, int input_w, int input_h) {
     int w, h, x, y;
-    float r_w = kInputW / (img.cols * 1.0);
-    float r_h = kInputH / (img.rows * 1.0);
+    float r_w = input_w / (img.cols * 1.0);
+    float r_h = input_h / (img.rows * 1.0);
     if (r_h > r_w) {
-        w = kInputW;
+        w = input_w;
         h = r_w * img.rows;
         x = 0;
-        y = (kInputH - h) / 2;
+        y = (input_h - h) / 2;
     } else {
         w = r_h * img.cols;
-        h = kInputH;
-        x = (kInputW - w) / 2;
+        h = input_h;
+        x = (input_w - w) / 2;
         y = 0;
     }
     cv::Rect r(x, y, w, h);
@@ -116,17 +119,19 @@ static cv::Mat scale_mask(cv::Mat mask, cv::Mat img) {
 }
 
 void draw_mask_bbox(cv::Mat& img, std::vector<Detection>& dets, std::vector<cv::Mat>& masks,
-                    std::unordered_map<int, std::string>& labels_map) {
+                    std::unordered_map<int, std::string>& labels_map, int input_w, int input_h) {
     static std::vector<uint32_t> colors = {0xFF3838, 0xFF9D97, 0xFF701F, 0xFFB21D, 0xCFD231, 0x48F90A, 0x92CC17,
                                            0x3DDB86, 0x1A9334, 0x00D4BB, 0x2C99A8, 0x00C2FF, 0x344593, 0x6473FF,
                                            0x0018EC, 0x8438FF, 0x520085, 0xCB38FF, 0xFF95C8, 0xFF37C7};
     for (size_t i = 0; i < dets.size(); i++) {
-        cv::Mat img_mask = scale_mask(masks[i], img);
         auto color = colors[(int)dets[i].class_id % colors.size()];
         auto bgr = cv::Scalar(color & 0xFF, color >> 8 & 0xFF, color >> 16 & 0xFF);
 
-        cv::Rect r = get_rect(img, dets[i].bbox);
-        for (int x = r.x; x < r.x + r.width; x++) {
+        cv::Rect r = get_rect(img, dets[i].bbox, input_w, input_h);
+        // 检测模型没有掩码，只画边界框
+        bool has_mask = i < masks.size() && !masks[i].empty();
+        cv::Mat img_mask = has_mask ? scale_mask(masks[i], img, input_w, input_h) : cv::Mat();
+        for (int x = r.x; has_mask && x < r.x + r.width; x++) {
             for (int y = r.y; y < r.y + r.height; y++) {
                 float val = img_mask.at<float>(y, x);
                 if (val <= 0.5)
@@ -1,10 +1,16 @@
 #include "yolo_c_api.h"
+#include <algorithm>
+#include <cstring>
+#include <fstream>
 #include <iostream>
 #include <string>
 #include <vector>
 #include <memory>
+#include <mutex>
+#include <unordered_map>
 #include <opencv2/opencv.hpp>
 #include "cuda/cuda_utils.h"
+#include "yolo/error.h"
 #include "yolo/logging.h"
 #include "yolo/postprocess.h"
 #include "yolo/preprocess.h"
@@ -14,8 +20,93 @@
 
 using namespace nvinfer1;
 
-// 全局错误信息
-static std::string g_last_error;
+// 错误信息按线程保存，避免并发调用互相覆盖
+struct LastError {
+    YoloErrorCode code = YOLO_OK;
+    std::string message;
+    std::string path;
+    int cuda_error = 0;
+};
+
+static thread_local LastError g_last_error;
+
+// 每个GPU上存活的推理器数量，重置设备前据此判断是否会破坏其他推理器。
+// 恢复全程持有同一把锁，同一时刻只有一个推理器在恢复
+static std::mutex g_device_mutex;
+static std::unordered_map<int, int> g_live_handles;
+
+static void register_handle(int device) {
+    std::lock_guard<std::mutex> lock(g_device_mutex);
+    g_live_handles[device]++;
+}
+
+static void unregister_handle(int device) {
+    std::lock_guard<std::mutex> lock(g_device_mutex);
+    if (--g_live_handles[device] <= 0) {
+        g_live_handles.erase(device);
+    }
+}
+
+// 设备侧阶段计时：在流上依次记录事件，同步后读取相邻事件之间的GPU耗时
+struct StageEvents {
+    enum { kStart, kPreprocessed, kExecuted, kCopied, kCount };
+    cudaEvent_t events[kCount] = {};
+
+    void create() {
+        for (auto& event : events) {
+            CUDA_CHECK(cudaEventCreate(&event));
+        }
+    }
+
+    void destroy() {
+        for (auto& event : events) {
+            if (event) {
+                cudaEventDestroy(event);
+                event = nullptr;
+            }
+        }
+    }
+
+    void record(int index, cudaStream_t stream) {
+        CUDA_CHECK(cudaEventRecord(events[index], stream));
+    }
+
+    double elapsed_ms(int from, int to) const {
+        float ms = 0.0f;
+        if (cudaEventElapsedTime(&ms, events[from], events[to]) != cudaSuccess) {
+            return 0.0;
+        }
+        return ms;
+    }
+
+    // 必须在流同步之后调用
+    void fill(YoloResult* result) const {
+        result->gpu_preprocess_time_ms = elapsed_ms(kStart, kPreprocessed);
+        result->gpu_tensorrt_time_ms = elapsed_ms(kPreprocessed, kExecuted);
+        result->gpu_copy_time_ms = elapsed_ms(kExecuted, kCopied);
+    }
+};
+
+// 模型信息：优先取引擎张量的形状，引擎中没有的由 sidecar 元数据补充，最后才回退到 config.h 的常量
+struct ModelDims {
+    int gpu_id = kGpuId;
+    int input_w = kInputW;
+    int input_h = kInputH;
+    int num_classes = 0;  // 插件输出中不含类别数，只能由元数据给出，0 表示未知
+    int max_num_output_bbox = kMaxNumOutputBbox;
+    int proto_w = 0;  // 没有分割原型输出时为0
+    int proto_h = 0;
+
+    // 单张图片的检测输出长度（float 数）
+    int output_size() const {
+        return max_num_output_bbox * sizeof(Detection) / sizeof(float) + 1;
+    }
+
+    // 单张图片的分割原型长度（float 数）
+    int proto_size() const {
+        return 32 * proto_w * proto_h;
+    }
+};
 
 // YOLO推理器类
 class YoloInference {
@@ -23,118 +114,322 @@ public:
     IRuntime* runtime = nullptr;
     ICudaEngine* engine = nullptr;
     IExecutionContext* context = nullptr;
-    cudaStream_t stream;
+    cudaStream_t stream = nullptr;
     
-    float* device_buffers[3];
+    float* device_buffers[3] = {nullptr, nullptr, nullptr};
     float* output_buffer_host = nullptr;
     float* output_seg_buffer_host = nullptr;
     
+    // 加载时从引擎发现的输入输出张量，按输入、检测输出、分割原型的顺序排列
+    std::vector<YoloTensorInfo> tensors;
+    std::string input_name;
+    std::string output_name;
+    std::string proto_name;  // 检测模型没有分割原型输出时为空
+    
+    // 从引擎和元数据读出的模型信息
+    ModelDims model;
+    
+    // 引擎支持的最大批次，动态批次引擎需要在推理前设置输入形状
+    int max_batch_size = 1;
+    bool dynamic_batch = false;
+    
+    // 预处理中转缓冲区（每个实例独立）
+    PreprocessBuffers preprocess_buffers;
+    
+    // 设备侧阶段计时事件
+    StageEvents stage_events;
+    
     std::unordered_map<int, std::string> labels_map;
     
+    // 恢复时按原路径重新创建
+    std::string engine_path;
+    std::string labels_path;
+    
+    // CUDA调用失败后上下文和流的状态未知，拒绝继续推理，直到 yolo_recover 成功
+    bool poisoned = false;
+    int poison_cuda_error = 0;
+    
     bool initialized = false;
     
+    // 计入存活推理器的GPU，未登记时为-1
+    int registered_device = -1;
+    
+    bool has_proto() const {
+        return !proto_name.empty();
+    }
+    
+    // 单张图片的输入长度（float 数）
+    int input_size() const {
+        return 3 * model.input_h * model.input_w;
+    }
+    
+    int output_size() const {
+        return model.output_size();
+    }
+    
+    // 没有分割原型输出时为0
+    int proto_size() const {
+        return model.proto_size();
+    }
+    
     ~YoloInference() {
         cleanup();
+        if (registered_device >= 0) {
+            unregister_handle(registered_device);
+        }
     }
     
+    // 释放所有资源，初始化中途失败或CUDA出错后也可以安全调用
     void cleanup() {
-        if (initialized) {
+        // 可能在其他线程上释放，先切换到资源所在的GPU
+        cudaSetDevice(model.gpu_id);
+        if (stream) {
             cudaStreamDestroy(stream);
-            CUDA_CHECK(cudaFree(device_buffers[0]));
-            CUDA_CHECK(cudaFree(device_buffers[1]));
-            CUDA_CHECK(cudaFree(device_buffers[2]));
-            delete[] output_buffer_host;
-            delete[] output_seg_buffer_host;
-            cuda_preprocess_destroy();
-            delete context;
-            delete engine;
-            delete runtime;
-            initialized = false;
+            stream = nullptr;
+        }
+        for (auto& buffer : device_buffers) {
+            if (buffer) {
+                cudaFree(buffer);
+                buffer = nullptr;
+            }
         }
+        delete[] output_buffer_host;
+        output_buffer_host = nullptr;
+        delete[] output_seg_buffer_host;
+        output_seg_buffer_host = nullptr;
+        delete context;
+        context = nullptr;
+        delete engine;
+        engine = nullptr;
+        delete runtime;
+        runtime = nullptr;
+        cuda_preprocess_destroy(preprocess_buffers);
+        stage_events.destroy();
+        initialized = false;
     }
 };
 
 // 辅助函数声明
 static bool deserialize_engine(const std::string& engine_name, YoloInference* inference);
+static bool discover_tensors(YoloInference* inference);
+static void read_metadata(const std::string& path, ModelDims& model);
+static bool read_model_info(YoloInference* inference);
+static void bind_tensors(IExecutionContext* context, const YoloInference* inference, float* const buffers[3]);
 static bool prepare_buffer(YoloInference* inference);
-static cv::Rect get_downscale_rect(float bbox[4], float scale);
-static std::vector<cv::Mat> process_mask(const float* proto, int proto_size, std::vector<Detection>& dets);
+static void detect_batch_size(YoloInference* inference);
+static bool set_batch_size(IExecutionContext* context, YoloInference* inference, int batch_size);
+static bool init_inference(YoloInference* inference);
+static cv::Rect get_downscale_rect(float bbox[4], float scale, const ModelDims& model);
+static std::vector<cv::Mat> process_mask(const ModelDims& model, const float* proto, std::vector<Detection>& dets);
+static void fill_detections(const ModelDims& model, YoloResult* result, std::vector<Detection>& res,
+                            const float* proto_host, bool skip_mask_copy);
 
 // 设置错误信息
-static void set_error(const std::string& error) {
-    g_last_error = error;
-    std::cerr << "YOLO C API Error: " << error << std::endl;
+static void set_error(YoloErrorCode code, const std::string& error, const std::string& path = "",
+                      int cuda_error = 0) {
+    g_last_error.code = code;
+    g_last_error.message = error;
+    g_last_error.path = path;
+    g_last_error.cuda_error = cuda_error;
+    gLogger.log(ILogger::Severity::kERROR, ("YOLO C API Error: " + error).c_str());
+}
+
+// 把正在处理的异常转换为错误信息，只能在 catch 块中调用
+// CUDA错误会让 inference 进入失效状态
+static void set_exception_error(const std::string& where, YoloInference* inference = nullptr) {
+    try {
+        throw;
+    } catch (const YoloException& e) {
+        set_error(e.code(), std::string(e.what()), e.path(), e.cuda_error());
+        if (inference && e.code() == YOLO_ERROR_CUDA) {
+            inference->poisoned = true;
+            inference->poison_cuda_error = e.cuda_error();
+        }
+    } catch (const std::bad_alloc& e) {
+        set_error(YOLO_ERROR_OUT_OF_MEMORY, "Out of memory in " + where + ": " + std::string(e.what()));
+    } catch (const std::exception& e) {
+        set_error(YOLO_ERROR_UNKNOWN, "Exception in " + where + ": " + std::string(e.what()));
+    } catch (...) {
+        set_error(YOLO_ERROR_UNKNOWN, "Unknown exception in " + where);
+    }
+}
+
+// 失效的推理器拒绝执行，避免在状态未知的上下文和流上继续提交工作。
+// CUDA 的当前设备按线程保存，推理器可能在创建它的线程之外使用，因此每次都切换到推理器所在的GPU
+static void ensure_usable(const YoloInference* inference) {
+    if (inference->poisoned) {
+        throw YoloException(YOLO_ERROR_CUDA,
+                            "Inference context is poisoned by a previous CUDA error, call yolo_recover",
+                            "", inference->poison_cuda_error);
+    }
+    CUDA_CHECK(cudaSetDevice(inference->model.gpu_id));
+}
+
+// 读取图片失败时区分文件不存在和解码失败
+static void set_image_read_error(const char* image_path) {
+    if (std::ifstream(image_path).good()) {
+        set_error(YOLO_ERROR_IMAGE_DECODE, "Failed to decode image: " + std::string(image_path), image_path);
+    } else {
+        set_error(YOLO_ERROR_FILE_NOT_FOUND, "Failed to read image: " + std::string(image_path), image_path);
+    }
 }
 
 YoloInferenceHandle yolo_create_inference(const char* engine_path, const char* labels_path) {
+    if (!engine_path || !labels_path) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return nullptr;
+    }
+
     try {
         auto inference = std::make_unique<YoloInference>();
+        inference->engine_path = engine_path;
+        inference->labels_path = labels_path;
         
-        // 设置CUDA设备
-        cudaSetDevice(kGpuId);
-        
-        // 反序列化引擎
-        if (!deserialize_engine(engine_path, inference.get())) {
-            set_error("Failed to deserialize engine: " + std::string(engine_path));
-            return nullptr;
-        }
-        
-        // 创建CUDA流
-        CUDA_CHECK(cudaStreamCreate(&inference->stream));
-        
-        // 初始化预处理
-        cuda_preprocess_init(kMaxInputImageSize);
-        
-        // 准备缓冲区
-        if (!prepare_buffer(inference.get())) {
-            set_error("Failed to prepare buffers");
-            return nullptr;
-        }
+        // 引擎旁的元数据（可选）可能指定GPU，必须在设置设备之前读取
+        read_metadata(inference->engine_path + ".meta", inference->model);
         
-        // 设置张量地址
-        inference->context->setTensorAddress(kInputTensorName, inference->device_buffers[0]);
-        inference->context->setTensorAddress(kOutputTensorName, inference->device_buffers[1]);
-        inference->context->setTensorAddress(kProtoTensorName, inference->device_buffers[2]);
+        // 设置CUDA设备
+        CUDA_CHECK(cudaSetDevice(inference->model.gpu_id));
         
-        // 读取标签
-        if (read_labels(labels_path, inference->labels_map) != 0) {
-            set_error("Failed to read labels file: " + std::string(labels_path));
+        if (!init_inference(inference.get())) {
             return nullptr;
         }
-        
-        inference->initialized = true;
+        register_handle(inference->model.gpu_id);
+        inference->registered_device = inference->model.gpu_id;
         return inference.release();
         
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_create_inference: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_create_inference");
         return nullptr;
     }
 }
 
+// 按保存的路径创建引擎、上下文、流和缓冲区，失败时已设置错误信息
+static bool init_inference(YoloInference* inference) {
+    // 反序列化引擎
+    if (!deserialize_engine(inference->engine_path, inference)) {
+        return false;
+    }
+    
+    // 按名称和形状发现输入输出张量
+    if (!discover_tensors(inference)) {
+        return false;
+    }
+    
+    // 从张量形状读取输入尺寸、输出容量和分割原型尺寸
+    if (!read_model_info(inference)) {
+        return false;
+    }
+    
+    // 从引擎读取最大批次
+    detect_batch_size(inference);
+    if (!set_batch_size(inference->context, inference, 1)) {
+        set_error(YOLO_ERROR_TENSORRT, "Failed to set input shape");
+        return false;
+    }
+    
+    // 创建CUDA流
+    CUDA_CHECK(cudaStreamCreate(&inference->stream));
+    inference->stage_events.create();
+    
+    // 初始化预处理
+    cuda_preprocess_init(inference->preprocess_buffers, kMaxInputImageSize);
+    
+    // 准备缓冲区
+    if (!prepare_buffer(inference)) {
+        return false;
+    }
+    
+    // 设置张量地址
+    bind_tensors(inference->context, inference, inference->device_buffers);
+    
+    // 读取标签
+    if (read_labels(inference->labels_path, inference->labels_map) != 0) {
+        set_error(YOLO_ERROR_FILE_NOT_FOUND, "Failed to read labels file: " + inference->labels_path,
+                  inference->labels_path);
+        return false;
+    }
+    
+    inference->initialized = true;
+    return true;
+}
+
 void yolo_destroy_inference(YoloInferenceHandle handle) {
     if (handle) {
         delete static_cast<YoloInference*>(handle);
     }
 }
 
+bool yolo_is_poisoned(YoloInferenceHandle handle) {
+    return handle && static_cast<YoloInference*>(handle)->poisoned;
+}
+
+bool yolo_recover(YoloInferenceHandle handle) {
+    if (!handle) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid handle");
+        return false;
+    }
+
+    auto* inference = static_cast<YoloInference*>(handle);
+    std::lock_guard<std::mutex> lock(g_device_mutex);
+    try {
+        inference->cleanup();
+        inference->poisoned = true;
+
+        // 清除非粘滞错误；同步仍然失败说明上下文已损坏，只能重置设备。
+        // 重置会销毁同一GPU上所有推理器的上下文和缓冲区，其他推理器存活时拒绝重置
+        cudaGetLastError();
+        if (cudaDeviceSynchronize() != cudaSuccess) {
+            const int device = inference->registered_device;
+            const int others = g_live_handles.count(device) ? g_live_handles[device] - 1 : 0;
+            if (others > 0) {
+                set_error(YOLO_ERROR_CUDA,
+                          "CUDA context is corrupted and " + std::to_string(others) +
+                          " other inferencer(s) on GPU " + std::to_string(device) +
+                          " are still alive, destroy them before recovering",
+                          "", inference->poison_cuda_error);
+                return false;
+            }
+            gLogger.log(ILogger::Severity::kWARNING, "CUDA context is corrupted, resetting device");
+            CUDA_CHECK(cudaDeviceReset());
+        }
+        CUDA_CHECK(cudaSetDevice(inference->model.gpu_id));
+
+        if (!init_inference(inference)) {
+            inference->cleanup();
+            return false;
+        }
+        inference->poisoned = false;
+        inference->poison_cuda_error = 0;
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_recover");
+        inference->cleanup();
+        inference->poisoned = true;
+        return false;
+    }
+}
+
 bool yolo_inference(YoloInferenceHandle handle, const char* image_path, YoloResult* result) {
     return yolo_inference_fast(handle, image_path, result, false);
 }
 
 bool yolo_inference_fast(YoloInferenceHandle handle, const char* image_path, YoloResult* result, bool skip_mask_copy) {
     if (!handle || !image_path || !result) {
-        set_error("Invalid parameters");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
 
     try {
         auto* inference = static_cast<YoloInference*>(handle);
+        ensure_usable(inference);
 
         // 读取图片时间测量
         auto image_read_start = std::chrono::high_resolution_clock::now();
         cv::Mat img = cv::imread(image_path);
         if (img.empty()) {
-            set_error("Failed to read image: " + std::string(image_path));
+            set_image_read_error(image_path);
             return false;
         }
         auto image_read_end = std::chrono::high_resolution_clock::now();
@@ -149,8 +444,8 @@ bool yolo_inference_fast(YoloInferenceHandle handle, const char* image_path, Yol
         
         return success;
 
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_inference_fast: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_inference_fast", static_cast<YoloInference*>(handle));
         return false;
     }
 }
@@ -168,12 +463,13 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
                                      int width, int height, int channels,
                                      YoloResult* result, bool skip_mask_copy) {
     if (!handle || !image_data || !result) {
-        set_error("Invalid parameters");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
     
     try {
         auto* inference = static_cast<YoloInference*>(handle);
+        ensure_usable(inference);
         
         // 创建OpenCV Mat
         cv::Mat img(height, width, channels == 3 ? CV_8UC3 : CV_8UC1, (void*)image_data);
@@ -181,33 +477,45 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
         
         auto total_start_time = std::chrono::high_resolution_clock::now();
         
+        auto& events = inference->stage_events;
+        
         // 预处理时间测量
         auto preprocess_start = std::chrono::high_resolution_clock::now();
-        cuda_batch_preprocess(img_batch, inference->device_buffers[0], kInputW, kInputH, inference->stream);
+        events.record(StageEvents::kStart, inference->stream);
+        cuda_batch_preprocess(inference->preprocess_buffers, img_batch, inference->device_buffers[0], inference->model.input_w, inference->model.input_h,
+                              inference->stream);
+        events.record(StageEvents::kPreprocessed, inference->stream);
         auto preprocess_end = std::chrono::high_resolution_clock::now();
         auto preprocess_duration = std::chrono::duration_cast<std::chrono::microseconds>(preprocess_end - preprocess_start);
         
         // TensorRT推理时间测量
         auto tensorrt_start = std::chrono::high_resolution_clock::now();
-        inference->context->enqueueV3(inference->stream);
+        if (!inference->context->enqueueV3(inference->stream)) {
+            set_error(YOLO_ERROR_TENSORRT, "Failed to enqueue inference");
+            return false;
+        }
+        events.record(StageEvents::kExecuted, inference->stream);
         auto tensorrt_end = std::chrono::high_resolution_clock::now();
         auto tensorrt_duration = std::chrono::duration_cast<std::chrono::microseconds>(tensorrt_end - tensorrt_start);
         
         // 获取输出
-        void* output_buffer = const_cast<void*>(inference->context->getTensorAddress(kOutputTensorName));
-        void* output_seg_buffer = const_cast<void*>(inference->context->getTensorAddress(kProtoTensorName));
+        void* output_buffer = const_cast<void*>(inference->context->getTensorAddress(inference->output_name.c_str()));
         
-        const int kOutputSize = kMaxNumOutputBbox * sizeof(Detection) / sizeof(float) + 1;
-        const int kOutputSegSize = 32 * (kInputH / 4) * (kInputW / 4);
+        const int output_size = inference->output_size();
         
         // 结果复制时间测量
         auto copy_start = std::chrono::high_resolution_clock::now();
         CUDA_CHECK(cudaMemcpyAsync(inference->output_buffer_host, output_buffer, 
-                                   kBatchSize * kOutputSize * sizeof(float), cudaMemcpyDeviceToHost,
+                                   kBatchSize * output_size * sizeof(float), cudaMemcpyDeviceToHost,
                                    inference->stream));
-        CUDA_CHECK(cudaMemcpyAsync(inference->output_seg_buffer_host, output_seg_buffer, 
-                                   kBatchSize * kOutputSegSize * sizeof(float),
-                                   cudaMemcpyDeviceToHost, inference->stream));
+        if (inference->has_proto()) {
+            void* output_seg_buffer =
+                    const_cast<void*>(inference->context->getTensorAddress(inference->proto_name.c_str()));
+            CUDA_CHECK(cudaMemcpyAsync(inference->output_seg_buffer_host, output_seg_buffer, 
+                                       kBatchSize * inference->proto_size() * sizeof(float),
+                                       cudaMemcpyDeviceToHost, inference->stream));
+        }
+        events.record(StageEvents::kCopied, inference->stream);
         
         CUDA_CHECK(cudaStreamSynchronize(inference->stream));
         auto copy_end = std::chrono::high_resolution_clock::now();
@@ -216,7 +524,7 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
         // 后处理时间测量
         auto postprocess_start = std::chrono::high_resolution_clock::now();
         std::vector<std::vector<Detection>> res_batch;
-        batch_nms(res_batch, inference->output_buffer_host, img_batch.size(), kOutputSize, kConfThresh, kNmsThresh);
+        batch_nms(res_batch, inference->output_buffer_host, img_batch.size(), output_size, kConfThresh, kNmsThresh);
         auto postprocess_end = std::chrono::high_resolution_clock::now();
         auto postprocess_duration = std::chrono::duration_cast<std::chrono::microseconds>(postprocess_end - postprocess_start);
 
@@ -226,7 +534,6 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
         auto total_duration = std::chrono::duration_cast<std::chrono::microseconds>(total_end_time - total_start_time);
 
         // 填充结果
-        result->num_detections = res.size();
         result->inference_time_ms = total_duration.count() / 1000.0;
         
         // 填充详细时间
@@ -235,48 +542,14 @@ bool yolo_inference_from_memory_fast(YoloInferenceHandle handle,
         result->tensorrt_time_ms = tensorrt_duration.count() / 1000.0;
         result->postprocess_time_ms = postprocess_duration.count() / 1000.0;
         result->result_copy_time_ms = copy_duration.count() / 1000.0;
-        
+        events.fill(result);
 
-
-        if (result->num_detections > 0) {
-            result->detections = new YoloDetection[result->num_detections];
-
-            // 只在需要时处理掩码
-            std::vector<cv::Mat> masks;
-            if (!skip_mask_copy) {
-                masks = process_mask(&inference->output_seg_buffer_host[0 * kOutputSegSize], kOutputSegSize, res);
-            }
-
-            for (int i = 0; i < result->num_detections; i++) {
-                result->detections[i].bbox[0] = res[i].bbox[0];
-                result->detections[i].bbox[1] = res[i].bbox[1];
-                result->detections[i].bbox[2] = res[i].bbox[2];
-                result->detections[i].bbox[3] = res[i].bbox[3];
-                result->detections[i].confidence = res[i].conf;
-                result->detections[i].class_id = (int)res[i].class_id;
-
-                // 复制掩码数据（如果需要）
-                if (!skip_mask_copy && i < masks.size()) {
-                    cv::Mat& mask = masks[i];
-                    result->detections[i].mask_width = mask.cols;
-                    result->detections[i].mask_height = mask.rows;
-                    int mask_size = mask.cols * mask.rows;
-                    result->detections[i].mask_data = new float[mask_size];
-                    memcpy(result->detections[i].mask_data, mask.data, mask_size * sizeof(float));
-                } else {
-                    result->detections[i].mask_data = nullptr;
-                    result->detections[i].mask_width = 0;
-                    result->detections[i].mask_height = 0;
-                }
-            }
-        } else {
-            result->detections = nullptr;
-        }
+        fill_detections(inference->model, result, res, inference->output_seg_buffer_host, skip_mask_copy);
         
         return true;
 
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_inference_from_memory: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_inference_from_memory", static_cast<YoloInference*>(handle));
         return false;
     }
 }
@@ -286,7 +559,7 @@ bool yolo_save_result_image(YoloInferenceHandle handle,
                            const YoloResult* result,
                            const char* output_path) {
     if (!handle || !image_path || !result || !output_path) {
-        set_error("Invalid parameters");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
 
@@ -295,7 +568,7 @@ bool yolo_save_result_image(YoloInferenceHandle handle,
 
         cv::Mat img = cv::imread(image_path);
         if (img.empty()) {
-            set_error("Failed to read image: " + std::string(image_path));
+            set_image_read_error(image_path);
             return false;
         }
 
@@ -313,24 +586,30 @@ bool yolo_save_result_image(YoloInferenceHandle handle,
             det.class_id = result->detections[i].class_id;
             dets.push_back(det);
 
-            // 重建掩码
+            // 重建掩码，没有掩码的检测占一个空位，保持与 dets 一一对应
             if (result->detections[i].mask_data) {
                 cv::Mat mask(result->detections[i].mask_height,
                            result->detections[i].mask_width,
                            CV_32FC1,
                            result->detections[i].mask_data);
                 masks.push_back(mask.clone());
+            } else {
+                masks.emplace_back();
             }
         }
 
         // 绘制结果
-        draw_mask_bbox(img, dets, masks, inference->labels_map);
+        draw_mask_bbox(img, dets, masks, inference->labels_map, inference->model.input_w, inference->model.input_h);
 
         // 保存图片
-        return cv::imwrite(output_path, img);
+        if (!cv::imwrite(output_path, img)) {
+            set_error(YOLO_ERROR_IO, "Failed to write image: " + std::string(output_path), output_path);
+            return false;
+        }
+        return true;
 
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_save_result_image: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_save_result_image", static_cast<YoloInference*>(handle));
         return false;
     }
 }
@@ -349,13 +628,26 @@ void yolo_free_result(YoloResult* result) {
 }
 
 const char* yolo_get_last_error(void) {
-    return g_last_error.c_str();
+    return g_last_error.message.c_str();
+}
+
+YoloErrorCode yolo_get_last_error_code(void) {
+    return g_last_error.code;
+}
+
+const char* yolo_get_last_error_path(void) {
+    return g_last_error.path.c_str();
+}
+
+int yolo_get_last_cuda_error(void) {
+    return g_last_error.cuda_error;
 }
 
 // 辅助函数实现
 static bool deserialize_engine(const std::string& engine_name, YoloInference* inference) {
     std::ifstream file(engine_name, std::ios::binary);
     if (!file.good()) {
+        set_error(YOLO_ERROR_FILE_NOT_FOUND, "Failed to open engine file: " + engine_name, engine_name);
         return false;
     }
 
@@ -363,116 +655,389 @@ static bool deserialize_engine(const std::string& engine_name, YoloInference* in
     file.seekg(0, file.end);
     size = file.tellg();
     file.seekg(0, file.beg);
-    char* serialized_engine = new char[size];
-    file.read(serialized_engine, size);
+    std::vector<char> serialized_engine(size);
+    file.read(serialized_engine.data(), size);
     file.close();
 
     inference->runtime = createInferRuntime(gLogger);
     if (!inference->runtime) {
-        delete[] serialized_engine;
+        set_error(YOLO_ERROR_TENSORRT, "Failed to create TensorRT runtime");
         return false;
     }
 
-    inference->engine = inference->runtime->deserializeCudaEngine(serialized_engine, size);
+    inference->engine = inference->runtime->deserializeCudaEngine(serialized_engine.data(), size);
     if (!inference->engine) {
-        delete[] serialized_engine;
+        set_error(YOLO_ERROR_ENGINE_DESERIALIZATION, "Failed to deserialize engine: " + engine_name, engine_name);
         return false;
     }
 
     inference->context = inference->engine->createExecutionContext();
     if (!inference->context) {
-        delete[] serialized_engine;
+        set_error(YOLO_ERROR_TENSORRT, "Failed to create execution context");
+        return false;
+    }
+
+    return true;
+}
+
+// 读取引擎中张量的名称和形状
+static YoloTensorInfo tensor_info(ICudaEngine* engine, const std::string& name, YoloTensorRole role) {
+    YoloTensorInfo info = {};
+    strncpy(info.name, name.c_str(), YOLO_MAX_TENSOR_NAME - 1);
+    info.role = role;
+    Dims dims = engine->getTensorShape(name.c_str());
+    info.num_dims = std::min(dims.nbDims, YOLO_MAX_TENSOR_DIMS);
+    for (int i = 0; i < info.num_dims; i++) {
+        info.dims[i] = dims.d[i];
+    }
+    return info;
+}
+
+// 分割原型的形状为 [N, 32, H/4, W/4]，检测输出的形状为 [N, size, 1, 1]
+static bool looks_like_proto(ICudaEngine* engine, const std::string& name) {
+    Dims dims = engine->getTensorShape(name.c_str());
+    return dims.nbDims == 4 && dims.d[2] > 1 && dims.d[3] > 1;
+}
+
+// 按名称和形状发现引擎的输入输出张量，不要求分割原型输出存在。
+// 名称为 kOutputTensorName / kProtoTensorName 的输出优先，其余输出按形状区分
+static bool discover_tensors(YoloInference* inference) {
+    ICudaEngine* engine = inference->engine;
+    inference->tensors.clear();
+    inference->input_name.clear();
+    inference->output_name.clear();
+    inference->proto_name.clear();
+
+    std::vector<std::string> outputs;
+    for (int i = 0; i < engine->getNbIOTensors(); i++) {
+        std::string name = engine->getIOTensorName(i);
+        if (engine->getTensorIOMode(name.c_str()) != TensorIOMode::kINPUT) {
+            outputs.push_back(name);
+        } else if (inference->input_name.empty()) {
+            inference->input_name = name;
+        } else {
+            set_error(YOLO_ERROR_TENSORRT, "Engine has more than one input tensor: " + inference->input_name + ", " +
+                      name, inference->engine_path);
+            return false;
+        }
+    }
+    if (inference->input_name.empty()) {
+        set_error(YOLO_ERROR_TENSORRT, "Engine has no input tensor", inference->engine_path);
+        return false;
+    }
+
+    for (const auto& name : outputs) {
+        if (name == kOutputTensorName) {
+            inference->output_name = name;
+        } else if (name == kProtoTensorName) {
+            inference->proto_name = name;
+        }
+    }
+    for (const auto& name : outputs) {
+        if (name == inference->output_name || name == inference->proto_name) {
+            continue;
+        }
+        std::string& slot = looks_like_proto(engine, name) ? inference->proto_name : inference->output_name;
+        if (!slot.empty()) {
+            set_error(YOLO_ERROR_TENSORRT, "Unexpected engine output tensor: " + name, inference->engine_path);
+            return false;
+        }
+        slot = name;
+    }
+    if (inference->output_name.empty()) {
+        set_error(YOLO_ERROR_TENSORRT, "Engine has no detection output tensor", inference->engine_path);
+        return false;
+    }
+
+    inference->tensors.push_back(tensor_info(engine, inference->input_name, YOLO_TENSOR_INPUT));
+    inference->tensors.push_back(tensor_info(engine, inference->output_name, YOLO_TENSOR_OUTPUT));
+    if (inference->has_proto()) {
+        inference->tensors.push_back(tensor_info(engine, inference->proto_name, YOLO_TENSOR_PROTO));
+    } else {
+        gLogger.log(ILogger::Severity::kINFO, "Engine has no proto output, masks are disabled");
+    }
+    return true;
+}
+
+// 把输入、检测输出和分割原型（如果有）绑定到上下文
+static void bind_tensors(IExecutionContext* context, const YoloInference* inference, float* const buffers[3]) {
+    context->setTensorAddress(inference->input_name.c_str(), buffers[0]);
+    context->setTensorAddress(inference->output_name.c_str(), buffers[1]);
+    if (inference->has_proto()) {
+        context->setTensorAddress(inference->proto_name.c_str(), buffers[2]);
+    }
+}
+
+// 读取引擎旁的元数据文件，每行一个 key=value，# 开头为注释。文件不存在时保持默认值
+static void read_metadata(const std::string& path, ModelDims& model) {
+    std::ifstream file(path);
+    if (!file.good()) {
+        return;
+    }
+    std::string line;
+    while (std::getline(file, line)) {
+        auto eq = line.find('=');
+        if (line.empty() || line[0] == '#' || eq == std::string::npos) {
+            continue;
+        }
+        auto trim = [](std::string text) {
+            const char* space = " \t\r";
+            text.erase(0, text.find_first_not_of(space));
+            text.erase(text.find_last_not_of(space) + 1);
+            return text;
+        };
+        std::string key = trim(line.substr(0, eq));
+        int value = 0;
+        try {
+            value = std::stoi(trim(line.substr(eq + 1)));
+        } catch (const std::exception&) {
+            gLogger.log(ILogger::Severity::kWARNING, ("Ignoring invalid metadata line: " + line).c_str());
+            continue;
+        }
+        if (key == "gpu_id") {
+            model.gpu_id = value;
+        } else if (key == "input_width") {
+            model.input_w = value;
+        } else if (key == "input_height") {
+            model.input_h = value;
+        } else if (key == "num_classes") {
+            model.num_classes = value;
+        } else {
+            gLogger.log(ILogger::Severity::kWARNING, ("Ignoring unknown metadata key: " + key).c_str());
+        }
+    }
+}
+
+// 从张量形状读取模型信息：输入 [N, 3, H, W]，检测输出 [N, 1 + max * sizeof(Detection) / 4, 1, 1]，
+// 分割原型 [N, 32, H/4, W/4]。动态维度保留元数据或默认值
+static bool read_model_info(YoloInference* inference) {
+    ICudaEngine* engine = inference->engine;
+    ModelDims& model = inference->model;
+
+    Dims input = engine->getTensorShape(inference->input_name.c_str());
+    if (input.nbDims != 4 || (input.d[1] != 3 && input.d[1] != -1)) {
+        set_error(YOLO_ERROR_TENSORRT, "Unsupported input tensor shape for " + inference->input_name,
+                  inference->engine_path);
         return false;
     }
+    if (input.d[2] > 0 && input.d[3] > 0) {
+        model.input_h = input.d[2];
+        model.input_w = input.d[3];
+    }
+
+    const int det_size = sizeof(Detection) / sizeof(float);
+    Dims output = engine->getTensorShape(inference->output_name.c_str());
+    if (output.nbDims >= 2 && output.d[1] > 0) {
+        if ((output.d[1] - 1) % det_size != 0) {
+            set_error(YOLO_ERROR_TENSORRT, "Detection output size " + std::to_string(output.d[1]) +
+                      " is not 1 + N * " + std::to_string(det_size), inference->engine_path);
+            return false;
+        }
+        model.max_num_output_bbox = (output.d[1] - 1) / det_size;
+    }
 
-    delete[] serialized_engine;
+    model.proto_w = 0;
+    model.proto_h = 0;
+    if (inference->has_proto()) {
+        Dims proto = engine->getTensorShape(inference->proto_name.c_str());
+        if (proto.nbDims != 4 || (proto.d[1] != 32 && proto.d[1] != -1)) {
+            set_error(YOLO_ERROR_TENSORRT, "Unsupported proto tensor shape for " + inference->proto_name,
+                      inference->engine_path);
+            return false;
+        }
+        model.proto_h = proto.d[2] > 0 ? proto.d[2] : model.input_h / 4;
+        model.proto_w = proto.d[3] > 0 ? proto.d[3] : model.input_w / 4;
+    }
+
+    gLogger.log(ILogger::Severity::kINFO,
+                ("Model input " + std::to_string(model.input_w) + "x" + std::to_string(model.input_h) +
+                 ", max detections " + std::to_string(model.max_num_output_bbox) + ", proto " +
+                 std::to_string(model.proto_w) + "x" + std::to_string(model.proto_h)).c_str());
     return true;
 }
 
 static bool prepare_buffer(YoloInference* inference) {
-    const int kOutputSize = kMaxNumOutputBbox * sizeof(Detection) / sizeof(float) + 1;
-    const int kOutputSegSize = 32 * (kInputH / 4) * (kInputW / 4);
+    const int output_size = inference->output_size();
 
     try {
-        CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[0], kBatchSize * 3 * kInputH * kInputW * sizeof(float)));
-        CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[1], kBatchSize * kOutputSize * sizeof(float)));
-        CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[2], kBatchSize * kOutputSegSize * sizeof(float)));
+        const int batch = inference->max_batch_size;
+        CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[0], batch * inference->input_size() * sizeof(float)));
+        CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[1], batch * output_size * sizeof(float)));
+        inference->output_buffer_host = new float[batch * output_size];
 
-        inference->output_buffer_host = new float[kBatchSize * kOutputSize];
-        inference->output_seg_buffer_host = new float[kBatchSize * kOutputSegSize];
+        // 检测模型没有分割原型输出，不分配对应的缓冲区
+        if (inference->has_proto()) {
+            CUDA_CHECK(cudaMalloc((void**)&inference->device_buffers[2],
+                                  batch * inference->proto_size() * sizeof(float)));
+            inference->output_seg_buffer_host = new float[batch * inference->proto_size()];
+        }
 
         return true;
     } catch (...) {
+        set_exception_error("prepare_buffer", inference);
         return false;
     }
 }
 
-static cv::Rect get_downscale_rect(float bbox[4], float scale) {
+static void detect_batch_size(YoloInference* inference) {
+    const char* input_name = inference->input_name.c_str();
+    Dims dims = inference->engine->getTensorShape(input_name);
+    if (dims.nbDims > 0 && dims.d[0] == -1) {
+        // 动态批次：以优化配置允许的最大批次分配缓冲区
+        Dims max_dims = inference->engine->getProfileShape(input_name, 0, OptProfileSelector::kMAX);
+        inference->dynamic_batch = true;
+        inference->max_batch_size = max_dims.nbDims > 0 && max_dims.d[0] > 0 ? max_dims.d[0] : 1;
+    } else {
+        inference->dynamic_batch = false;
+        inference->max_batch_size = dims.nbDims > 0 && dims.d[0] > 0 ? dims.d[0] : 1;
+    }
+}
+
+static bool set_batch_size(IExecutionContext* context, YoloInference* inference, int batch_size) {
+    if (!inference->dynamic_batch) {
+        return true;
+    }
+    return context->setInputShape(inference->input_name.c_str(), Dims4{batch_size, 3, inference->model.input_h, inference->model.input_w});
+}
+
+static cv::Rect get_downscale_rect(float bbox[4], float scale, const ModelDims& model) {
     float left = bbox[0];
     float top = bbox[1];
-    float right = bbox[0] + bbox[2];
-    float bottom = bbox[1] + bbox[3];
+    float right = bbox[2];
+    float bottom = bbox[3];
 
     left = left < 0 ? 0 : left;
     top = top < 0 ? 0 : top;
-    right = right > kInputW ? kInputW : right;
-    bottom = bottom > kInputH ? kInputH : bottom;
+    right = right > model.input_w ? model.input_w : right;
+    bottom = bottom > model.input_h ? model.input_h : bottom;
 
     left /= scale;
     top /= scale;
     right /= scale;
     bottom /= scale;
-    return cv::Rect(int(left), int(top), int(right - left), int(bottom - top));
+    // 先取整再求宽高，与 Rust 侧 Proto::crop 覆盖同样的原型像素
+    return cv::Rect(int(left), int(top), int(right) - int(left), int(bottom) - int(top));
 }
 
-static std::vector<cv::Mat> process_mask(const float* proto, int proto_size, std::vector<Detection>& dets) {
-    std::vector<cv::Mat> masks;
+// 所有检测的掩码系数拼成 N×32 矩阵，与 32×(proto_h*proto_w) 的原型做一次矩阵乘法，
+// 再按检测并行地在框内做 sigmoid 并放大到输入尺寸
+static std::vector<cv::Mat> process_mask(const ModelDims& model, const float* proto, std::vector<Detection>& dets) {
+    const int num_coefficients = sizeof(dets[0].mask) / sizeof(dets[0].mask[0]);
+    const float scale = (float)model.input_w / model.proto_w;
+    std::vector<cv::Mat> masks(dets.size());
+    if (dets.empty()) {
+        return masks;
+    }
+
+    cv::Mat coefficients((int)dets.size(), num_coefficients, CV_32FC1);
     for (size_t i = 0; i < dets.size(); i++) {
-        cv::Mat mask_mat = cv::Mat::zeros(kInputH / 4, kInputW / 4, CV_32FC1);
-        auto r = get_downscale_rect(dets[i].bbox, 4);
-
-        for (int x = r.x; x < r.x + r.width; x++) {
-            for (int y = r.y; y < r.y + r.height; y++) {
-                float e = 0.0f;
-                for (int j = 0; j < 32; j++) {
-                    e += dets[i].mask[j] * proto[j * proto_size / 32 + y * mask_mat.cols + x];
-                }
-                e = 1.0f / (1.0f + expf(-e));
-                mask_mat.at<float>(y, x) = e;
+        memcpy(coefficients.ptr<float>((int)i), dets[i].mask, num_coefficients * sizeof(float));
+    }
+    const cv::Mat prototypes(num_coefficients, model.proto_h * model.proto_w, CV_32FC1, const_cast<float*>(proto));
+    const cv::Mat logits = coefficients * prototypes;
+
+    const cv::Rect bounds(0, 0, model.proto_w, model.proto_h);
+    cv::parallel_for_(cv::Range(0, (int)dets.size()), [&](const cv::Range& range) {
+        for (int i = range.start; i < range.end; i++) {
+            cv::Mat mask_mat = cv::Mat::zeros(model.proto_h, model.proto_w, CV_32FC1);
+            auto r = get_downscale_rect(dets[i].bbox, scale, model) & bounds;
+            if (r.area() > 0) {
+                const cv::Mat logit = logits.row(i).reshape(1, model.proto_h);
+                cv::Mat roi = mask_mat(r);
+                cv::exp(-logit(r), roi);
+                roi += 1.0;
+                cv::divide(1.0, roi, roi);
             }
+            cv::resize(mask_mat, masks[i], cv::Size(model.input_w, model.input_h));
         }
-        cv::resize(mask_mat, mask_mat, cv::Size(kInputW, kInputH));
-        masks.push_back(mask_mat);
-    }
+    });
     return masks;
 }
 
+// proto_host 为空（引擎没有分割原型输出）时不生成掩码
+static void fill_detections(const ModelDims& model, YoloResult* result, std::vector<Detection>& res,
+                            const float* proto_host, bool skip_mask_copy) {
+    skip_mask_copy = skip_mask_copy || !proto_host;
+
+    result->num_detections = res.size();
+    if (result->num_detections == 0) {
+        result->detections = nullptr;
+        return;
+    }
+
+    // 值初始化，掩码解码中途抛出异常时释放结果也是安全的
+    result->detections = new YoloDetection[result->num_detections]();
+
+    // 只在需要时处理掩码
+    std::vector<cv::Mat> masks;
+    if (!skip_mask_copy) {
+        auto mask_start = std::chrono::high_resolution_clock::now();
+        masks = process_mask(model, proto_host, res);
+        auto mask_end = std::chrono::high_resolution_clock::now();
+        result->mask_decode_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(mask_end - mask_start).count() / 1000.0;
+    }
+
+    for (int i = 0; i < result->num_detections; i++) {
+        result->detections[i].bbox[0] = res[i].bbox[0];
+        result->detections[i].bbox[1] = res[i].bbox[1];
+        result->detections[i].bbox[2] = res[i].bbox[2];
+        result->detections[i].bbox[3] = res[i].bbox[3];
+        result->detections[i].confidence = res[i].conf;
+        result->detections[i].class_id = (int)res[i].class_id;
+        memcpy(result->detections[i].keypoints, res[i].keypoints, sizeof(res[i].keypoints));
+        result->detections[i].angle = res[i].angle;
+
+        // 复制掩码数据（如果需要）
+        if (!skip_mask_copy && i < (int)masks.size()) {
+            cv::Mat& mask = masks[i];
+            result->detections[i].mask_width = mask.cols;
+            result->detections[i].mask_height = mask.rows;
+            int mask_size = mask.cols * mask.rows;
+            result->detections[i].mask_data = new float[mask_size];
+            memcpy(result->detections[i].mask_data, mask.data, mask_size * sizeof(float));
+        } else {
+            result->detections[i].mask_data = nullptr;
+            result->detections[i].mask_width = 0;
+            result->detections[i].mask_height = 0;
+        }
+    }
+}
+
 // 新增API函数实现
 bool yolo_tensorrt_inference_only(YoloInferenceHandle handle,
                                   void* input_buffer,
                                   void* output_buffer,
                                   void* output_seg_buffer,
                                   void* stream) {
-    if (!handle || !input_buffer || !output_buffer || !output_seg_buffer) {
-        set_error("Invalid parameters");
+    if (!handle || !input_buffer || !output_buffer) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
     
     try {
         auto* inference = static_cast<YoloInference*>(handle);
+        ensure_usable(inference);
+        if (inference->has_proto() && !output_seg_buffer) {
+            set_error(YOLO_ERROR_INVALID_ARGUMENT, "Engine has a proto output but output_seg_buffer is NULL");
+            return false;
+        }
         cudaStream_t cuda_stream = static_cast<cudaStream_t>(stream);
         
         // 设置TensorRT缓冲区地址
-        inference->context->setTensorAddress(kInputTensorName, input_buffer);
-        inference->context->setTensorAddress(kOutputTensorName, output_buffer);
-        inference->context->setTensorAddress(kProtoTensorName, output_seg_buffer);
+        float* buffers[3] = {static_cast<float*>(input_buffer), static_cast<float*>(output_buffer),
+                             static_cast<float*>(output_seg_buffer)};
+        bind_tensors(inference->context, inference, buffers);
         
         // 执行TensorRT推理
-        inference->context->enqueueV3(cuda_stream);
+        if (!inference->context->enqueueV3(cuda_stream)) {
+            set_error(YOLO_ERROR_TENSORRT, "Failed to enqueue inference");
+            return false;
+        }
         
         return true;
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_tensorrt_inference_only: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_tensorrt_inference_only", static_cast<YoloInference*>(handle));
         return false;
     }
 }
@@ -482,20 +1047,20 @@ bool yolo_get_tensorrt_info(YoloInferenceHandle handle,
                             int* output_size,
                             int* output_seg_size) {
     if (!handle || !input_size || !output_size || !output_seg_size) {
-        set_error("Invalid parameters");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
     
     try {
         auto* inference = static_cast<YoloInference*>(handle);
         
-        *input_size = kBatchSize * kInputH * kInputW * 3; // RGB
-        *output_size = kMaxNumOutputBbox * sizeof(Detection) / sizeof(float) + 1;
-        *output_seg_size = 32 * (kInputH / 4) * (kInputW / 4);
+        *input_size = kBatchSize * inference->input_size();
+        *output_size = inference->output_size();
+        *output_seg_size = inference->proto_size();
         
         return true;
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_get_tensorrt_info: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_get_tensorrt_info", static_cast<YoloInference*>(handle));
         return false;
     }
 }
@@ -505,7 +1070,7 @@ bool yolo_get_tensorrt_buffers(YoloInferenceHandle handle,
                                void** output_buffer,
                                void** output_seg_buffer) {
     if (!handle || !input_buffer || !output_buffer || !output_seg_buffer) {
-        set_error("Invalid parameters");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
         return false;
     }
     
@@ -517,23 +1082,557 @@ bool yolo_get_tensorrt_buffers(YoloInferenceHandle handle,
         *output_seg_buffer = inference->device_buffers[2];
         
         return true;
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_get_tensorrt_buffers: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_get_tensorrt_buffers", static_cast<YoloInference*>(handle));
         return false;
     }
 }
 
 void* yolo_get_cuda_stream(YoloInferenceHandle handle) {
     if (!handle) {
-        set_error("Invalid handle");
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid handle");
         return nullptr;
     }
     
     try {
         auto* inference = static_cast<YoloInference*>(handle);
+        if (!inference->stream) {
+            set_error(YOLO_ERROR_CUDA, "CUDA stream is not created");
+            return nullptr;
+        }
         return static_cast<void*>(inference->stream);
-    } catch (const std::exception& e) {
-        set_error("Exception in yolo_get_cuda_stream: " + std::string(e.what()));
+    } catch (...) {
+        set_exception_error("yolo_get_cuda_stream", static_cast<YoloInference*>(handle));
         return nullptr;
     }
 }
+
+bool yolo_synchronize(YoloInferenceHandle handle) {
+    if (!handle) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid handle");
+        return false;
+    }
+
+    auto* inference = static_cast<YoloInference*>(handle);
+    cudaError_t err = cudaSetDevice(inference->model.gpu_id);
+    if (err == cudaSuccess) {
+        err = cudaStreamSynchronize(inference->stream);
+    }
+    if (err != cudaSuccess) {
+        set_error(YOLO_ERROR_CUDA, "cudaStreamSynchronize failed: " + std::string(cudaGetErrorString(err)), "",
+                  static_cast<int>(err));
+        inference->poisoned = true;
+        inference->poison_cuda_error = static_cast<int>(err);
+        return false;
+    }
+    return true;
+}
+
+int yolo_get_max_batch_size(YoloInferenceHandle handle) {
+    if (!handle) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid handle");
+        return 0;
+    }
+    return static_cast<YoloInference*>(handle)->max_batch_size;
+}
+
+int yolo_get_tensor_count(YoloInferenceHandle handle) {
+    if (!handle) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid handle");
+        return -1;
+    }
+    return (int)static_cast<YoloInference*>(handle)->tensors.size();
+}
+
+bool yolo_get_model_info(YoloInferenceHandle handle, YoloModelInfo* info) {
+    if (!handle || !info) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+    auto* inference = static_cast<YoloInference*>(handle);
+    const ModelDims& model = inference->model;
+    info->gpu_id = model.gpu_id;
+    info->input_width = model.input_w;
+    info->input_height = model.input_h;
+    info->num_classes = model.num_classes;
+    info->max_detections = model.max_num_output_bbox;
+    info->max_batch_size = inference->max_batch_size;
+    info->proto_width = model.proto_w;
+    info->proto_height = model.proto_h;
+    return true;
+}
+
+bool yolo_get_tensor_info(YoloInferenceHandle handle, int index, YoloTensorInfo* info) {
+    if (!handle || !info) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+    auto* inference = static_cast<YoloInference*>(handle);
+    if (index < 0 || index >= (int)inference->tensors.size()) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid tensor index: " + std::to_string(index));
+        return false;
+    }
+    *info = inference->tensors[index];
+    return true;
+}
+
+// 对批次执行预处理、引擎和结果拷贝，输出留在 output_buffer_host / output_seg_buffer_host 中，
+// 并在 results 中写入这些阶段的耗时。失败时已设置错误信息
+static bool run_batch(YoloInference* inference,
+                      const uint8_t* const* images,
+                      const int* widths,
+                      const int* heights,
+                      int batch_size,
+                      YoloResult* results) {
+    ensure_usable(inference);
+    if (batch_size > inference->max_batch_size) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Batch size " + std::to_string(batch_size) + " exceeds engine maximum " +
+                  std::to_string(inference->max_batch_size));
+        return false;
+    }
+
+    std::vector<cv::Mat> img_batch;
+    for (int i = 0; i < batch_size; i++) {
+        if (!images[i]) {
+            set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+            return false;
+        }
+        img_batch.emplace_back(heights[i], widths[i], CV_8UC3, (void*)images[i]);
+    }
+
+    const int output_size = inference->output_size();
+
+    auto& events = inference->stage_events;
+
+    auto preprocess_start = std::chrono::high_resolution_clock::now();
+    events.record(StageEvents::kStart, inference->stream);
+    cuda_batch_preprocess(inference->preprocess_buffers, img_batch, inference->device_buffers[0], inference->model.input_w, inference->model.input_h,
+                          inference->stream);
+    events.record(StageEvents::kPreprocessed, inference->stream);
+    auto preprocess_end = std::chrono::high_resolution_clock::now();
+
+    // 静态批次引擎始终按最大批次执行，多余的槽位结果直接忽略
+    int run_batch_size = inference->dynamic_batch ? batch_size : inference->max_batch_size;
+    if (!set_batch_size(inference->context, inference, run_batch_size)) {
+        set_error(YOLO_ERROR_TENSORRT, "Failed to set input shape for batch " + std::to_string(run_batch_size));
+        return false;
+    }
+
+    auto tensorrt_start = std::chrono::high_resolution_clock::now();
+    bool enqueued = inference->context->enqueueV3(inference->stream);
+    events.record(StageEvents::kExecuted, inference->stream);
+    auto tensorrt_end = std::chrono::high_resolution_clock::now();
+    set_batch_size(inference->context, inference, 1);
+    if (!enqueued) {
+        set_error(YOLO_ERROR_TENSORRT, "Failed to enqueue batch inference");
+        return false;
+    }
+
+    auto copy_start = std::chrono::high_resolution_clock::now();
+    CUDA_CHECK(cudaMemcpyAsync(inference->output_buffer_host, inference->device_buffers[1],
+                               batch_size * output_size * sizeof(float), cudaMemcpyDeviceToHost,
+                               inference->stream));
+    if (inference->has_proto()) {
+        CUDA_CHECK(cudaMemcpyAsync(inference->output_seg_buffer_host, inference->device_buffers[2],
+                                   batch_size * inference->proto_size() * sizeof(float), cudaMemcpyDeviceToHost,
+                                   inference->stream));
+    }
+    events.record(StageEvents::kCopied, inference->stream);
+    CUDA_CHECK(cudaStreamSynchronize(inference->stream));
+    auto copy_end = std::chrono::high_resolution_clock::now();
+
+    auto to_ms = [](std::chrono::high_resolution_clock::time_point start,
+                    std::chrono::high_resolution_clock::time_point end) {
+        return std::chrono::duration_cast<std::chrono::microseconds>(end - start).count() / 1000.0;
+    };
+
+    // 批次内的各阶段耗时由所有图片共享
+    for (int i = 0; i < batch_size; i++) {
+        results[i].image_read_time_ms = 0.0;
+        results[i].preprocess_time_ms = to_ms(preprocess_start, preprocess_end);
+        results[i].tensorrt_time_ms = to_ms(tensorrt_start, tensorrt_end);
+        results[i].result_copy_time_ms = to_ms(copy_start, copy_end);
+        events.fill(&results[i]);
+    }
+    return true;
+}
+
+bool yolo_inference_batch_from_memory(YoloInferenceHandle handle,
+                                      const uint8_t* const* images,
+                                      const int* widths,
+                                      const int* heights,
+                                      int batch_size,
+                                      YoloResult* results,
+                                      bool skip_mask_copy) {
+    if (!handle || !images || !widths || !heights || !results || batch_size <= 0) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        auto* inference = static_cast<YoloInference*>(handle);
+        const int output_size = inference->output_size();
+
+        auto total_start_time = std::chrono::high_resolution_clock::now();
+        if (!run_batch(inference, images, widths, heights, batch_size, results)) {
+            return false;
+        }
+
+        auto postprocess_start = std::chrono::high_resolution_clock::now();
+        std::vector<std::vector<Detection>> res_batch;
+        batch_nms(res_batch, inference->output_buffer_host, batch_size, output_size, kConfThresh, kNmsThresh);
+        auto postprocess_end = std::chrono::high_resolution_clock::now();
+
+        for (int i = 0; i < batch_size; i++) {
+            const float* proto_host = inference->has_proto()
+                                              ? &inference->output_seg_buffer_host[i * inference->proto_size()]
+                                              : nullptr;
+            fill_detections(inference->model, &results[i], res_batch[i], proto_host, skip_mask_copy);
+        }
+
+        auto total_end_time = std::chrono::high_resolution_clock::now();
+        for (int i = 0; i < batch_size; i++) {
+            results[i].inference_time_ms =
+                    std::chrono::duration_cast<std::chrono::microseconds>(total_end_time - total_start_time).count() /
+                    1000.0;
+            results[i].postprocess_time_ms =
+                    std::chrono::duration_cast<std::chrono::microseconds>(postprocess_end - postprocess_start)
+                            .count() /
+                    1000.0;
+        }
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_inference_batch_from_memory", static_cast<YoloInference*>(handle));
+        return false;
+    }
+}
+
+bool yolo_inference_raw_batch_from_memory(YoloInferenceHandle handle,
+                                          const uint8_t* const* images,
+                                          const int* widths,
+                                          const int* heights,
+                                          int batch_size,
+                                          YoloResult* results) {
+    if (!handle || !images || !widths || !heights || !results || batch_size <= 0) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        auto* inference = static_cast<YoloInference*>(handle);
+
+        auto total_start_time = std::chrono::high_resolution_clock::now();
+        if (!run_batch(inference, images, widths, heights, batch_size, results)) {
+            return false;
+        }
+        auto total_end_time = std::chrono::high_resolution_clock::now();
+
+        for (int i = 0; i < batch_size; i++) {
+            results[i].detections = nullptr;
+            results[i].num_detections = 0;
+            results[i].postprocess_time_ms = 0.0;
+            results[i].inference_time_ms =
+                    std::chrono::duration_cast<std::chrono::microseconds>(total_end_time - total_start_time).count() /
+                    1000.0;
+        }
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_inference_raw_batch_from_memory", static_cast<YoloInference*>(handle));
+        return false;
+    }
+}
+
+bool yolo_get_host_outputs(YoloInferenceHandle handle, const float** output, const float** proto) {
+    if (!handle || !output || !proto) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    auto* inference = static_cast<YoloInference*>(handle);
+    if (!inference->initialized) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Inference is not initialized");
+        return false;
+    }
+    *output = inference->output_buffer_host;
+    *proto = inference->output_seg_buffer_host;
+    return true;
+}
+
+bool yolo_decode_mask(const float* proto, int proto_w, int proto_h, int input_w, int input_h,
+                      const float bbox[4], const float* coefficients, float* mask) {
+    if (!proto || !bbox || !coefficients || !mask || proto_w <= 0 || proto_h <= 0 || input_w <= 0 ||
+        input_h <= 0) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        ModelDims model;
+        model.input_w = input_w;
+        model.input_h = input_h;
+        model.proto_w = proto_w;
+        model.proto_h = proto_h;
+
+        std::vector<Detection> dets(1);
+        memcpy(dets[0].bbox, bbox, sizeof(dets[0].bbox));
+        memcpy(dets[0].mask, coefficients, sizeof(dets[0].mask));
+        auto masks = process_mask(model, proto, dets);
+        memcpy(mask, masks[0].data, (size_t)input_w * input_h * sizeof(float));
+        return true;
+    } catch (...) {
+        set_exception_error("yolo_decode_mask", nullptr);
+        return false;
+    }
+}
+
+// 流水线槽位：每个槽位独立持有执行上下文、CUDA流和输入输出缓冲区，
+// 不同槽位的预处理、推理和结果拷贝可以在GPU上重叠执行
+struct PipelineSlot {
+    IExecutionContext* context = nullptr;
+    cudaStream_t stream = nullptr;
+    PreprocessBuffers preprocess_buffers;
+    StageEvents stage_events;
+    float* device_buffers[3] = {nullptr, nullptr, nullptr};
+    float* output_buffer_host = nullptr;      // pinned memory
+    float* output_seg_buffer_host = nullptr;  // pinned memory
+    bool in_flight = false;
+    double preprocess_time_ms = 0.0;
+    double tensorrt_time_ms = 0.0;
+    std::chrono::high_resolution_clock::time_point submit_time;
+};
+
+class YoloPipeline {
+public:
+    YoloInference* inference = nullptr;
+    std::vector<PipelineSlot> slots;
+
+    ~YoloPipeline() {
+        if (inference) {
+            cudaSetDevice(inference->model.gpu_id);
+        }
+        for (auto& slot : slots) {
+            if (slot.stream) {
+                cudaStreamSynchronize(slot.stream);
+                cudaStreamDestroy(slot.stream);
+            }
+            for (auto* buffer : slot.device_buffers) {
+                if (buffer) cudaFree(buffer);
+            }
+            if (slot.output_buffer_host) cudaFreeHost(slot.output_buffer_host);
+            if (slot.output_seg_buffer_host) cudaFreeHost(slot.output_seg_buffer_host);
+            cuda_preprocess_destroy(slot.preprocess_buffers);
+            slot.stage_events.destroy();
+            delete slot.context;
+        }
+    }
+};
+
+YoloPipelineHandle yolo_pipeline_create(YoloInferenceHandle handle, int depth) {
+    if (!handle || depth <= 0) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return nullptr;
+    }
+
+    try {
+        auto* inference = static_cast<YoloInference*>(handle);
+        ensure_usable(inference);
+        auto pipeline = std::make_unique<YoloPipeline>();
+        pipeline->inference = inference;
+        pipeline->slots.resize(depth);
+
+        const int output_size = inference->output_size();
+        const int proto_size = inference->proto_size();
+
+        for (auto& slot : pipeline->slots) {
+            slot.context = inference->engine->createExecutionContext();
+            if (!slot.context || !set_batch_size(slot.context, inference, 1)) {
+                set_error(YOLO_ERROR_TENSORRT, "Failed to create execution context for pipeline");
+                return nullptr;
+            }
+            CUDA_CHECK(cudaStreamCreate(&slot.stream));
+            slot.stage_events.create();
+            cuda_preprocess_init(slot.preprocess_buffers, kMaxInputImageSize);
+            CUDA_CHECK(cudaMalloc((void**)&slot.device_buffers[0], inference->input_size() * sizeof(float)));
+            CUDA_CHECK(cudaMalloc((void**)&slot.device_buffers[1], output_size * sizeof(float)));
+            CUDA_CHECK(cudaMallocHost((void**)&slot.output_buffer_host, output_size * sizeof(float)));
+            if (inference->has_proto()) {
+                CUDA_CHECK(cudaMalloc((void**)&slot.device_buffers[2], proto_size * sizeof(float)));
+                CUDA_CHECK(cudaMallocHost((void**)&slot.output_seg_buffer_host, proto_size * sizeof(float)));
+            }
+
+            bind_tensors(slot.context, inference, slot.device_buffers);
+        }
+
+        return pipeline.release();
+
+    } catch (...) {
+        set_exception_error("yolo_pipeline_create", static_cast<YoloInference*>(handle));
+        return nullptr;
+    }
+}
+
+void yolo_pipeline_destroy(YoloPipelineHandle pipeline) {
+    if (pipeline) {
+        delete static_cast<YoloPipeline*>(pipeline);
+    }
+}
+
+bool yolo_pipeline_submit(YoloPipelineHandle pipeline,
+                          int slot_index,
+                          const uint8_t* image_data,
+                          int width, int height, int channels) {
+    if (!pipeline || !image_data || channels != 3) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        auto* p = static_cast<YoloPipeline*>(pipeline);
+        ensure_usable(p->inference);
+        if (slot_index < 0 || slot_index >= (int)p->slots.size()) {
+            set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid pipeline slot: " + std::to_string(slot_index));
+            return false;
+        }
+        auto& slot = p->slots[slot_index];
+        if (slot.in_flight) {
+            set_error(YOLO_ERROR_INVALID_ARGUMENT, "Pipeline slot is still in flight: " + std::to_string(slot_index));
+            return false;
+        }
+
+        const int output_size = p->inference->output_size();
+
+        slot.submit_time = std::chrono::high_resolution_clock::now();
+
+        // 预处理：图像拷贝到槽位自己的pinned缓冲区后异步上传，不等待流完成
+        auto preprocess_start = std::chrono::high_resolution_clock::now();
+        slot.stage_events.record(StageEvents::kStart, slot.stream);
+        cuda_preprocess(slot.preprocess_buffers, (uint8_t*)image_data, width, height, slot.device_buffers[0], p->inference->model.input_w,
+                        p->inference->model.input_h, slot.stream);
+        slot.stage_events.record(StageEvents::kPreprocessed, slot.stream);
+        auto preprocess_end = std::chrono::high_resolution_clock::now();
+
+        auto tensorrt_start = std::chrono::high_resolution_clock::now();
+        if (!slot.context->enqueueV3(slot.stream)) {
+            set_error(YOLO_ERROR_TENSORRT, "Failed to enqueue pipeline inference");
+            return false;
+        }
+        slot.stage_events.record(StageEvents::kExecuted, slot.stream);
+        auto tensorrt_end = std::chrono::high_resolution_clock::now();
+
+        // 结果异步拷贝回pinned内存，在collect时再同步
+        CUDA_CHECK(cudaMemcpyAsync(slot.output_buffer_host, slot.device_buffers[1], output_size * sizeof(float),
+                                   cudaMemcpyDeviceToHost, slot.stream));
+        if (p->inference->has_proto()) {
+            CUDA_CHECK(cudaMemcpyAsync(slot.output_seg_buffer_host, slot.device_buffers[2],
+                                       p->inference->proto_size() * sizeof(float), cudaMemcpyDeviceToHost,
+                                       slot.stream));
+        }
+        slot.stage_events.record(StageEvents::kCopied, slot.stream);
+
+        slot.preprocess_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(preprocess_end - preprocess_start).count() /
+                1000.0;
+        slot.tensorrt_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(tensorrt_end - tensorrt_start).count() / 1000.0;
+        slot.in_flight = true;
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_pipeline_submit", static_cast<YoloPipeline*>(pipeline)->inference);
+        return false;
+    }
+}
+
+// 等待槽位中的帧完成，写入除后处理外的各阶段耗时。失败时已设置错误信息
+static PipelineSlot* wait_slot(YoloPipeline* p, int slot_index, YoloResult* result) {
+    ensure_usable(p->inference);
+    if (slot_index < 0 || slot_index >= (int)p->slots.size() || !p->slots[slot_index].in_flight) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Pipeline slot has no pending frame: " + std::to_string(slot_index));
+        return nullptr;
+    }
+    auto& slot = p->slots[slot_index];
+    slot.in_flight = false;
+
+    auto copy_start = std::chrono::high_resolution_clock::now();
+    CUDA_CHECK(cudaStreamSynchronize(slot.stream));
+    auto copy_end = std::chrono::high_resolution_clock::now();
+
+    result->image_read_time_ms = 0.0;
+    result->preprocess_time_ms = slot.preprocess_time_ms;
+    result->tensorrt_time_ms = slot.tensorrt_time_ms;
+    result->result_copy_time_ms =
+            std::chrono::duration_cast<std::chrono::microseconds>(copy_end - copy_start).count() / 1000.0;
+    slot.stage_events.fill(result);
+    return &slot;
+}
+
+bool yolo_pipeline_collect(YoloPipelineHandle pipeline, int slot_index, YoloResult* result, bool skip_mask_copy) {
+    if (!pipeline || !result) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        auto* p = static_cast<YoloPipeline*>(pipeline);
+        PipelineSlot* slot = wait_slot(p, slot_index, result);
+        if (!slot) {
+            return false;
+        }
+
+        const int output_size = p->inference->output_size();
+
+        auto postprocess_start = std::chrono::high_resolution_clock::now();
+        std::vector<std::vector<Detection>> res_batch;
+        batch_nms(res_batch, slot->output_buffer_host, 1, output_size, kConfThresh, kNmsThresh);
+        auto postprocess_end = std::chrono::high_resolution_clock::now();
+
+        fill_detections(p->inference->model, result, res_batch[0], slot->output_seg_buffer_host, skip_mask_copy);
+
+        auto total_end = std::chrono::high_resolution_clock::now();
+        result->inference_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(total_end - slot->submit_time).count() / 1000.0;
+        result->postprocess_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(postprocess_end - postprocess_start).count() /
+                1000.0;
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_pipeline_collect", static_cast<YoloPipeline*>(pipeline)->inference);
+        return false;
+    }
+}
+
+bool yolo_pipeline_collect_raw(YoloPipelineHandle pipeline,
+                               int slot_index,
+                               YoloResult* result,
+                               const float** output,
+                               const float** proto) {
+    if (!pipeline || !result || !output || !proto) {
+        set_error(YOLO_ERROR_INVALID_ARGUMENT, "Invalid parameters");
+        return false;
+    }
+
+    try {
+        auto* p = static_cast<YoloPipeline*>(pipeline);
+        PipelineSlot* slot = wait_slot(p, slot_index, result);
+        if (!slot) {
+            return false;
+        }
+
+        auto total_end = std::chrono::high_resolution_clock::now();
+        result->detections = nullptr;
+        result->num_detections = 0;
+        result->postprocess_time_ms = 0.0;
+        result->inference_time_ms =
+                std::chrono::duration_cast<std::chrono::microseconds>(total_end - slot->submit_time).count() / 1000.0;
+        *output = slot->output_buffer_host;
+        *proto = slot->output_seg_buffer_host;
+        return true;
+
+    } catch (...) {
+        set_exception_error("yolo_pipeline_collect_raw", static_cast<YoloPipeline*>(pipeline)->inference);
+        return false;
+    }
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use common::MockBackend;
+use yolo11s_tensorrt_rs::{AsyncYolo, PoolConfig};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// 单个 future 专用的唤醒器，记录自己是否被唤醒过
+struct FlagWaker {
+    woken: AtomicBool,
+    thread: Thread,
+}
+
+impl Wake for FlagWaker {
+    fn wake(self: Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+/// 最小的单线程执行器，避免测试依赖具体的异步运行时
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn infer_resolves_on_completion() {
+    let yolo = AsyncYolo::from_backends(
+        vec![MockBackend::new(Duration::from_millis(10))],
+        PoolConfig::new(1),
+    )
+    .unwrap();
+
+    let result = block_on(yolo.infer("images/test.jpg")).unwrap();
+    assert_eq!(result.detection_count(), 1);
+    assert_eq!(
+        result.detections()[0].class_id(),
+        "images/test.jpg".len() as i32
+    );
+}
+
+#[test]
+fn waits_for_queue_space_instead_of_failing() {
+    let backend = MockBackend::new(Duration::from_millis(50));
+    let stats = backend.stats();
+    let yolo =
+        AsyncYolo::from_backends(vec![backend], PoolConfig::new(1).with_queue_capacity(1)).unwrap();
+
+    // 占住唯一的工作线程，等它从队列中取走任务
+    let running = yolo.pool().submit("running.jpg").unwrap();
+    while yolo.pool().queue_depth() > 0 {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let mut futures: Vec<_> = (0..4).map(|_| Box::pin(yolo.infer("image.jpg"))).collect();
+    let mut outputs: Vec<_> = futures.iter().map(|_| None).collect();
+    let flags: Vec<_> = futures
+        .iter()
+        .map(|_| {
+            Arc::new(FlagWaker {
+                woken: AtomicBool::new(false),
+                thread: thread::current(),
+            })
+        })
+        .collect();
+
+    // 同时轮询四个 future：只有一个能进入容量为 1 的队列，其余因队列已满而挂起
+    for (future, flag) in futures.iter_mut().zip(&flags) {
+        let waker = Waker::from(Arc::clone(flag));
+        assert!(future
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending());
+    }
+    assert_eq!(yolo.pool().queue_depth(), 1);
+
+    // 只重新轮询被唤醒的 future，挂起的 future 必须由队列空出时的唤醒推动
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while outputs.iter().any(Option::is_none) && Instant::now() < deadline {
+        for ((future, output), flag) in futures.iter_mut().zip(&mut outputs).zip(&flags) {
+            if output.is_none() && flag.woken.swap(false, Ordering::SeqCst) {
+                let waker = Waker::from(Arc::clone(flag));
+                if let Poll::Ready(result) = future.as_mut().poll(&mut Context::from_waker(&waker))
+                {
+                    *output = Some(result);
+                }
+            }
+        }
+        thread::park_timeout(Duration::from_millis(10));
+    }
+
+    running.wait().unwrap();
+    assert!(outputs.iter().all(|r| matches!(r, Some(Ok(_)))));
+    assert_eq!(stats.calls(), 5);
+}
+
+#[test]
+fn dropping_queued_future_cancels_job() {
+    let backend = MockBackend::new(Duration::from_millis(100));
+    let stats = backend.stats();
+    let yolo = AsyncYolo::from_backends(vec![backend], PoolConfig::new(1)).unwrap();
+
+    // 占住唯一的工作线程
+    let running = yolo.pool().submit("running.jpg").unwrap();
+
+    // 提交后立即丢弃，任务仍在队列中等待
+    {
+        let mut queued = pin!(yolo.infer("queued.jpg"));
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        assert!(queued.as_mut().poll(&mut cx).is_pending());
+    }
+
+    running.wait().unwrap();
+    yolo.shutdown();
+
+    assert_eq!(stats.calls(), 1);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use common::MockBackend;
+use yolo11s_tensorrt_rs::{BatcherConfig, DynamicBatcher, Frame};
+
+fn frame(width: i32) -> Frame {
+    Frame::from_bgr(vec![0; width as usize * 3], width, 1).unwrap()
+}
+
+#[test]
+fn concurrent_requests_are_grouped_into_batches() {
+    let backend = MockBackend::new(Duration::from_millis(20)).with_max_batch_size(4);
+    let stats = backend.stats();
+    let batcher = Arc::new(
+        DynamicBatcher::from_backend(
+            backend,
+            BatcherConfig::default().with_max_wait(Duration::from_millis(200)),
+        )
+        .unwrap(),
+    );
+
+    let callers: Vec<_> = (1..=8)
+        .map(|width| {
+            let batcher = Arc::clone(&batcher);
+            thread::spawn(move || batcher.infer(frame(width)).unwrap())
+        })
+        .collect();
+
+    for (width, caller) in (1..=8).zip(callers) {
+        let result = caller.join().unwrap();
+        assert_eq!(result.detections[0].class_id, width);
+    }
+
+    let metrics = batcher.metrics();
+    assert_eq!(metrics.requests, 8);
+    assert!(metrics.batches < 8);
+    assert!(metrics.max_batch_size() <= 4);
+    assert_eq!(stats.batch_sizes().iter().sum::<usize>(), 8);
+}
+
+#[test]
+fn lone_request_is_flushed_after_max_wait() {
+    let backend = MockBackend::new(Duration::ZERO).with_max_batch_size(8);
+    let batcher = DynamicBatcher::from_backend(
+        backend,
+        BatcherConfig::default().with_max_wait(Duration::from_millis(10)),
+    )
+    .unwrap();
+
+    let result = batcher.infer(frame(3)).unwrap();
+    assert_eq!(result.detections[0].class_id, 3);
+
+    let metrics = batcher.metrics();
+    assert_eq!(metrics.batches, 1);
+    assert_eq!(metrics.batch_size_histogram, vec![0, 1]);
+}
+
+#[test]
+fn batch_size_is_capped_by_backend() {
+    let backend = MockBackend::new(Duration::ZERO).with_max_batch_size(2);
+    let stats = backend.stats();
+    let batcher = DynamicBatcher::from_backend(
+        backend,
+        BatcherConfig::default()
+            .with_max_batch_size(16)
+            .with_max_wait(Duration::from_millis(50)),
+    )
+    .unwrap();
+
+    let jobs: Vec<_> = (1..=6).map(|w| batcher.submit(frame(w)).unwrap()).collect();
+    for job in jobs {
+        job.wait().unwrap();
+    }
+    batcher.shutdown();
+
+    assert!(stats.batch_sizes().iter().all(|&size| size <= 2));
+}
+
+#[test]
+fn shutdown_drains_pending_requests() {
+    let backend = MockBackend::new(Duration::from_millis(5)).with_max_batch_size(2);
+    let batcher = DynamicBatcher::from_backend(backend, BatcherConfig::default()).unwrap();
+
+    let jobs: Vec<_> = (1..=5).map(|w| batcher.submit(frame(w)).unwrap()).collect();
+    batcher.shutdown();
+
+    for job in jobs {
+        assert!(job.wait().is_ok());
+    }
+}
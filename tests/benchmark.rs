@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use yolo11s_tensorrt_rs::{BenchmarkMode, BenchmarkReport, LatencyStats};
+
+#[test]
+fn latency_stats_use_nearest_rank_percentiles() {
+    let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+    let stats = LatencyStats::from_samples(&samples);
+
+    assert_eq!(stats.samples, 100);
+    assert_eq!(stats.min_ms, 1.0);
+    assert_eq!(stats.max_ms, 100.0);
+    assert_eq!(stats.mean_ms, 50.5);
+    assert_eq!(stats.p50_ms, 50.0);
+    assert_eq!(stats.p95_ms, 95.0);
+    assert_eq!(stats.p99_ms, 99.0);
+}
+
+#[test]
+fn latency_stats_of_no_samples_are_zero() {
+    assert_eq!(LatencyStats::from_samples(&[]), LatencyStats::default());
+}
+
+#[test]
+fn report_serialises_to_json() {
+    let latency = LatencyStats::from_samples(&[2.0, 4.0]);
+    let report = BenchmarkReport {
+        engine_path: "models/\"a\".engine".to_string(),
+        mode: BenchmarkMode::FromMemory,
+        warmup: 1,
+        iterations: 2,
+        images: 1,
+        elapsed: Duration::from_millis(6),
+        latency: latency.clone(),
+        stages: vec![("tensorrt".to_string(), latency)],
+    };
+
+    let json = report.to_json();
+    assert!(json.starts_with(r#"{"engine_path":"models/\"a\".engine","mode":"from_memory""#));
+    assert!(json.contains(r#""iterations":2"#));
+    assert!(json.contains(r#""stages":{"tensorrt":{"samples":2"#));
+    assert!((report.throughput_fps() - 333.333).abs() < 0.01);
+    assert_eq!(report.stage("tensorrt").unwrap().max_ms, 4.0);
+}
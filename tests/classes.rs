@@ -0,0 +1,94 @@
+use yolo11s_tensorrt_rs::{ClassFilter, NmsConfig, NmsMethod, RawCandidate, YoloError};
+
+fn candidates() -> Vec<RawCandidate> {
+    vec![
+        RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+        RawCandidate::new([1.0, 0.0, 11.0, 10.0], 0.8, 1),
+        RawCandidate::new([50.0, 0.0, 60.0, 10.0], 0.3, 2),
+        RawCandidate::new([100.0, 0.0, 110.0, 10.0], 0.6, 3),
+    ]
+}
+
+fn classes(kept: &[RawCandidate]) -> Vec<i32> {
+    kept.iter().map(|c| c.class_id).collect()
+}
+
+#[test]
+fn empty_filter_is_plain_nms() {
+    let config = NmsConfig::default();
+    let kept = ClassFilter::new()
+        .apply(candidates(), None, &config)
+        .unwrap();
+    assert_eq!(classes(&kept), vec![0, 1, 3]);
+}
+
+#[test]
+fn per_class_threshold_can_be_lower_or_higher() {
+    let filter = ClassFilter::new()
+        .with_threshold(2, 0.2)
+        .with_threshold(3, 0.7);
+    let kept = filter
+        .apply(candidates(), None, &NmsConfig::default())
+        .unwrap();
+    assert_eq!(classes(&kept), vec![0, 1, 2]);
+}
+
+#[test]
+fn allow_and_deny_lists_use_names_or_ids() {
+    let filter = ClassFilter::new()
+        .with_names(["crack", "pit", "stain", "scratch"])
+        .with_allowed("crack")
+        .with_allowed("pit")
+        .with_allowed(3)
+        .with_denied("pit");
+    let kept = filter
+        .apply(candidates(), None, &NmsConfig::default())
+        .unwrap();
+    assert_eq!(classes(&kept), vec![0, 3]);
+}
+
+#[test]
+fn merged_classes_suppress_each_other() {
+    let filter = ClassFilter::new()
+        .with_names(["crack", "crack_long", "stain", "scratch"])
+        .with_remap("crack_long", "crack")
+        .with_threshold("crack", 0.7);
+    let kept = filter
+        .apply(candidates(), None, &NmsConfig::default())
+        .unwrap();
+    // 合并后的两个裂纹框互相重叠，只保留置信度更高的一个
+    assert_eq!(classes(&kept), vec![0, 3]);
+    assert_eq!(kept[0].confidence, 0.9);
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    let filter = ClassFilter::new().with_names(["crack"]).with_denied("rust");
+    assert!(matches!(
+        filter.validate(),
+        Err(YoloError::InvalidParameter(_))
+    ));
+    assert!(filter
+        .apply(candidates(), None, &NmsConfig::default())
+        .is_err());
+}
+
+#[test]
+fn count_limits_apply_after_per_class_thresholds() {
+    let candidates = vec![
+        RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+        // 与上一个框重叠 0.5，线性衰减到 0.4，低于类别 0 的默认阈值
+        RawCandidate::new([0.0, 0.0, 10.0, 5.0], 0.8, 0),
+        RawCandidate::new([100.0, 0.0, 110.0, 10.0], 0.35, 3),
+        RawCandidate::new([50.0, 0.0, 60.0, 10.0], 0.3, 2),
+    ];
+    let filter = ClassFilter::new()
+        .with_threshold(2, 0.2)
+        .with_threshold(3, 0.2);
+    let config = NmsConfig::default()
+        .with_method(NmsMethod::Linear)
+        .with_max_detections(2);
+
+    let kept = filter.resolve().unwrap().apply(candidates, None, &config);
+    assert_eq!(classes(&kept), vec![0, 3]);
+}
@@ -0,0 +1,280 @@
+//! 集成测试共用的 mock 推理后端
+
+#![allow(dead_code)]
+
+pub mod reference;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use yolo11s_tensorrt_rs::{
+    Detection, ErrorCode, Frame, InferenceBackend, InferenceResult, NativeError, PerfStats,
+    YoloError, YoloResult,
+};
+
+/// mock 后端的调用统计，克隆后可在测试线程中观察
+#[derive(Clone, Default)]
+pub struct MockStats {
+    calls: Arc<AtomicUsize>,
+    active: Arc<AtomicUsize>,
+    max_active: Arc<AtomicUsize>,
+    batch_sizes: Arc<Mutex<Vec<usize>>>,
+    recoveries: Arc<AtomicUsize>,
+}
+
+impl MockStats {
+    /// 累计推理次数
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// 观察到的最大同时推理数
+    pub fn max_active(&self) -> usize {
+        self.max_active.load(Ordering::SeqCst)
+    }
+
+    /// 每次批量推理的批次大小，按调用顺序排列
+    pub fn batch_sizes(&self) -> Vec<usize> {
+        self.batch_sizes.lock().unwrap().clone()
+    }
+
+    /// 累计恢复次数
+    pub fn recoveries(&self) -> usize {
+        self.recoveries.load(Ordering::SeqCst)
+    }
+}
+
+/// 不依赖 GPU 的推理后端
+///
+/// 每次推理睡眠 `delay`，返回一个置信度为 0.9 的检测框，
+/// 类别 ID 等于图片路径的字节长度，便于测试核对结果归属。
+/// 批量推理时每个批次睡眠一次 `delay`，类别 ID 等于帧宽度。
+/// 推理路径等于 `poison_path` 时模拟 CUDA 错误并失效，直到调用 `recover`。
+/// 设置了 `perf` 时像 [`Yolo`](yolo11s_tensorrt_rs::Yolo) 一样把每次推理记入性能统计。
+pub struct MockBackend {
+    delay: Duration,
+    max_batch_size: usize,
+    poison_path: Option<String>,
+    poisoned: bool,
+    stats: MockStats,
+    perf: Option<Arc<PerfStats>>,
+}
+
+impl MockBackend {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            max_batch_size: 1,
+            poison_path: None,
+            poisoned: false,
+            stats: MockStats::default(),
+            perf: None,
+        }
+    }
+
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    pub fn with_poison_path(mut self, image_path: &str) -> Self {
+        self.poison_path = Some(image_path.to_string());
+        self
+    }
+
+    pub fn with_perf_stats(mut self, perf: Arc<PerfStats>) -> Self {
+        self.perf = Some(perf);
+        self
+    }
+
+    pub fn stats(&self) -> MockStats {
+        self.stats.clone()
+    }
+
+    fn run(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        if self.poisoned || self.poison_path.as_deref() == Some(image_path) {
+            self.poisoned = true;
+            return Err(YoloError::Cuda(
+                NativeError::new(ErrorCode::Cuda, "illegal memory access").with_cuda_error(700),
+            ));
+        }
+
+        let active = self.stats.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.stats.max_active.fetch_max(active, Ordering::SeqCst);
+
+        thread::sleep(self.delay);
+
+        let mut result = InferenceResult::new();
+        result.add_detection(Detection::new(
+            [0.0, 0.0, 10.0, 10.0],
+            0.9,
+            image_path.len() as i32,
+        ));
+        result.total_time_ms = self.delay.as_secs_f64() * 1000.0;
+
+        self.stats.calls.fetch_add(1, Ordering::SeqCst);
+        self.stats.active.fetch_sub(1, Ordering::SeqCst);
+        Ok(result)
+    }
+}
+
+impl InferenceBackend for MockBackend {
+    fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        self.inference_queued(image_path, 0.0)
+    }
+
+    fn inference_queued(
+        &mut self,
+        image_path: &str,
+        queue_wait_ms: f64,
+    ) -> YoloResult<InferenceResult> {
+        let outcome = self.run(image_path).map(|mut result| {
+            result.queue_wait_ms = queue_wait_ms;
+            result
+        });
+        if let Some(perf) = &self.perf {
+            match &outcome {
+                Ok(result) => perf.record(result),
+                Err(e) => perf.record_error(e),
+            }
+        }
+        outcome
+    }
+
+    fn inference_batch(&mut self, frames: &[Frame]) -> YoloResult<Vec<InferenceResult>> {
+        assert!(frames.len() <= self.max_batch_size);
+        self.stats.batch_sizes.lock().unwrap().push(frames.len());
+
+        thread::sleep(self.delay);
+
+        let results = frames
+            .iter()
+            .map(|frame| {
+                let mut result = InferenceResult::new();
+                result.add_detection(Detection::new([0.0, 0.0, 10.0, 10.0], 0.9, frame.width()));
+                result
+            })
+            .collect();
+
+        self.stats.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(results)
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    fn recover(&mut self) -> YoloResult<()> {
+        self.poisoned = false;
+        self.stats.recoveries.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// 按脚本注入故障的推理后端
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// 推理成功
+    Pass,
+    /// 返回指定错误
+    Fail(YoloError),
+    /// 卡住一段时间后成功，用于触发看门狗
+    Hang(Duration),
+    /// 返回 CUDA 错误并失效
+    Poison,
+}
+
+/// 故障脚本，同一脚本的所有后端实例按顺序消费；脚本耗尽后推理总是成功
+#[derive(Clone, Default)]
+pub struct FaultScript {
+    faults: Arc<Mutex<std::collections::VecDeque<Fault>>>,
+    created: Arc<AtomicUsize>,
+    calls: Arc<AtomicUsize>,
+    recoveries: Arc<AtomicUsize>,
+}
+
+impl FaultScript {
+    pub fn new(faults: impl IntoIterator<Item = Fault>) -> Self {
+        let script = Self::default();
+        script.faults.lock().unwrap().extend(faults);
+        script
+    }
+
+    /// 创建一个新的后端实例，可以直接用作监管器的工厂函数
+    pub fn backend(&self) -> YoloResult<FaultyBackend> {
+        self.created.fetch_add(1, Ordering::SeqCst);
+        Ok(FaultyBackend {
+            script: self.clone(),
+            poisoned: false,
+        })
+    }
+
+    /// 累计创建的后端数量
+    pub fn created(&self) -> usize {
+        self.created.load(Ordering::SeqCst)
+    }
+
+    /// 累计推理次数
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// 累计原地恢复次数
+    pub fn recoveries(&self) -> usize {
+        self.recoveries.load(Ordering::SeqCst)
+    }
+}
+
+pub struct FaultyBackend {
+    script: FaultScript,
+    poisoned: bool,
+}
+
+impl InferenceBackend for FaultyBackend {
+    fn inference(&mut self, image_path: &str) -> YoloResult<InferenceResult> {
+        self.script.calls.fetch_add(1, Ordering::SeqCst);
+        if self.poisoned {
+            return Err(YoloError::Cuda(NativeError::new(
+                ErrorCode::Cuda,
+                "inferencer is poisoned",
+            )));
+        }
+
+        let fault = self.script.faults.lock().unwrap().pop_front();
+        match fault.unwrap_or(Fault::Pass) {
+            Fault::Pass => {}
+            Fault::Fail(error) => return Err(error),
+            Fault::Hang(duration) => thread::sleep(duration),
+            Fault::Poison => {
+                self.poisoned = true;
+                return Err(YoloError::Cuda(
+                    NativeError::new(ErrorCode::Cuda, "illegal memory access").with_cuda_error(700),
+                ));
+            }
+        }
+
+        let mut result = InferenceResult::new();
+        result.add_detection(Detection::new(
+            [0.0, 0.0, 10.0, 10.0],
+            0.9,
+            image_path.len() as i32,
+        ));
+        Ok(result)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    fn recover(&mut self) -> YoloResult<()> {
+        self.poisoned = false;
+        self.script.recoveries.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
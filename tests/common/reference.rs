@@ -0,0 +1,46 @@
+//! 掩码解码的参考实现，测试和基准共用
+
+use yolo11s_tensorrt_rs::decode::MASK_COEFFICIENTS;
+use yolo11s_tensorrt_rs::RawCandidate;
+
+/// 逐像素的朴素实现：原型上跨通道求点积和 sigmoid，
+/// 再对整幅图按 `INTER_LINEAR` 放大 `stride` 倍
+pub fn reference_mask(
+    proto: &[f32],
+    size: usize,
+    stride: usize,
+    candidate: &RawCandidate,
+) -> Vec<f32> {
+    let plane = size * size;
+    let scale = stride as f32;
+    let clamp = |v: f32| ((v.max(0.0) / scale) as usize).min(size);
+    let [x1, y1, x2, y2] = candidate.bbox;
+    let mut mask = vec![0.0f32; plane];
+    for y in clamp(y1)..clamp(y2) {
+        for x in clamp(x1)..clamp(x2) {
+            let logit: f32 = (0..MASK_COEFFICIENTS)
+                .map(|c| candidate.mask_coefficients[c] * proto[c * plane + y * size + x])
+                .sum();
+            mask[y * size + x] = 1.0 / (1.0 + (-logit).exp());
+        }
+    }
+
+    let sample = |dst: usize| {
+        let position = ((dst as f32 + 0.5) / scale - 0.5).max(0.0);
+        let low = (position as usize).min(size - 1);
+        (low, (low + 1).min(size - 1), position - low as f32)
+    };
+    let output = size * stride;
+    let mut dst = vec![0.0f32; output * output];
+    for y in 0..output {
+        let (y0, y1, fy) = sample(y);
+        for x in 0..output {
+            let (x0, x1, fx) = sample(x);
+            let at = |yy: usize, xx: usize| mask[yy * size + xx];
+            let upper = at(y0, x0) + (at(y0, x1) - at(y0, x0)) * fx;
+            let lower = at(y1, x0) + (at(y1, x1) - at(y1, x0)) * fx;
+            dst[y * output + x] = upper + (lower - upper) * fy;
+        }
+    }
+    dst
+}
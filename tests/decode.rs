@@ -0,0 +1,404 @@
+mod common;
+
+use yolo11s_tensorrt_rs::decode::{
+    decode_masks, Proto, RawOutput, DETECTION_STRIDE, MASK_COEFFICIENTS, MAX_NUM_OUTPUT_BBOX,
+    OUTPUT_SIZE, PROTO_STRIDE,
+};
+use yolo11s_tensorrt_rs::{
+    postprocess_output, Config, ModelInfo, RawCandidate, Task, TensorRole, YoloError,
+};
+
+use common::reference::reference_mask;
+
+fn candidate(bbox: [f32; 4], confidence: f32, class_id: f32) -> Vec<f32> {
+    let mut values = vec![0.0; DETECTION_STRIDE];
+    values[..4].copy_from_slice(&bbox);
+    values[4] = confidence;
+    values[5] = class_id;
+    values[6] = 0.25; // 第一个掩码系数
+    values[6 + 32] = 100.0; // 第一个关键点 x
+    values[DETECTION_STRIDE - 1] = 0.5; // angle
+    values
+}
+
+fn buffer(count: f32, candidates: &[Vec<f32>]) -> Vec<f32> {
+    let mut data = vec![0.0; OUTPUT_SIZE];
+    data[0] = count;
+    for (i, values) in candidates.iter().enumerate() {
+        let start = 1 + i * DETECTION_STRIDE;
+        data[start..start + DETECTION_STRIDE].copy_from_slice(values);
+    }
+    data
+}
+
+#[test]
+fn parses_packed_detections() {
+    let data = buffer(
+        2.0,
+        &[
+            candidate([10.0, 20.0, 110.0, 220.0], 0.9, 0.0),
+            candidate([5.0, 5.0, 50.0, 50.0], 0.6, 3.0),
+        ],
+    );
+    let output = RawOutput::parse(&data).unwrap();
+    assert_eq!(output.len(), 2);
+    assert!(!output.overflowed());
+
+    let candidates = output.candidates().unwrap();
+    assert_eq!(candidates[0].bbox, [10.0, 20.0, 110.0, 220.0]);
+    assert_eq!(candidates[0].confidence, 0.9);
+    assert_eq!(candidates[1].class_id, 3);
+    assert_eq!(candidates[1].mask_coefficients[0], 0.25);
+    assert_eq!(candidates[1].keypoints[0][0], 100.0);
+    assert_eq!(candidates[1].angle, 0.5);
+}
+
+#[test]
+fn exposes_keypoints_and_rotated_boxes() {
+    let mut values = candidate([320.0, 240.0, 100.0, 40.0], 0.9, 0.0);
+    values[6 + 32 + 2] = 0.8; // 第一个关键点置信度
+    values[6 + 32 + 3..6 + 32 + 6].copy_from_slice(&[-1.0, -1.0, -1.0]);
+    let data = buffer(1.0, &[values]);
+    let candidate = RawOutput::parse(&data)
+        .unwrap()
+        .candidates()
+        .unwrap()
+        .remove(0);
+
+    let keypoints = candidate.keypoints();
+    assert_eq!(keypoints.len(), 17);
+    assert_eq!((keypoints[0].x, keypoints[0].visibility), (100.0, 0.8));
+    assert!(keypoints[0].is_visible(0.5));
+    assert!(!keypoints[1].is_visible(0.0));
+
+    let rotated = candidate.rotated_box();
+    assert_eq!((rotated.cx, rotated.cy, rotated.angle), (320.0, 240.0, 0.5));
+    let [x1, y1, x2, y2] = rotated.bounding_box();
+    assert!(x1 < 270.0 && x2 > 370.0 && y1 < 220.0 && y2 > 260.0);
+    assert!(((x1 + x2) / 2.0 - 320.0).abs() < 1e-3);
+}
+
+#[test]
+fn clamps_overflowing_count() {
+    let data = buffer(1500.0, &[]);
+    let output = RawOutput::parse(&data).unwrap();
+    assert_eq!(output.len(), MAX_NUM_OUTPUT_BBOX);
+    assert_eq!(output.reported_count(), 1500);
+    assert!(output.overflowed());
+}
+
+#[test]
+fn clamps_to_engine_capacity() {
+    // 例如 max_detections 为 300 的引擎
+    let model = ModelInfo {
+        max_detections: 300,
+        ..ModelInfo::default()
+    };
+    let mut data = vec![0.0; model.output_size()];
+    data[0] = 450.0;
+    let output = RawOutput::parse_with_capacity(&data, model.max_detections).unwrap();
+    assert_eq!(output.len(), 300);
+    assert!(output.overflowed());
+    // 按默认容量解析放不下计数对应的候选框
+    assert!(RawOutput::parse(&data).is_err());
+
+    let default = ModelInfo::default();
+    assert_eq!(default.output_size(), OUTPUT_SIZE);
+    assert_eq!(default.proto_size(), MASK_COEFFICIENTS * 160 * 160);
+    assert!(default.has_proto());
+}
+
+#[test]
+fn maps_tensor_roles() {
+    for role in [TensorRole::Input, TensorRole::Output, TensorRole::Proto] {
+        assert_eq!(TensorRole::from_raw(role.as_raw()), Some(role));
+    }
+    assert_eq!(TensorRole::from_raw(2), Some(TensorRole::Proto));
+    assert_eq!(TensorRole::from_raw(3), None);
+    assert_eq!(TensorRole::from_raw(-1), None);
+}
+
+#[test]
+fn postprocesses_engines_without_proto() {
+    let model = ModelInfo {
+        proto_width: 0,
+        proto_height: 0,
+        ..ModelInfo::default()
+    };
+    assert!(!model.has_proto());
+    assert_eq!(model.proto_size(), 0);
+
+    let data = buffer(
+        2.0,
+        &[
+            candidate([0.0, 0.0, 10.0, 10.0], 0.9, 1.0),
+            candidate([100.0, 100.0, 120.0, 120.0], 0.8, 2.0),
+        ],
+    );
+    for task in [Task::Detect, Task::Segment] {
+        let config = Config::new("models/yolo11s.engine").with_task(task);
+        let result = postprocess_output(&data, None, &model, &config).unwrap();
+        assert_eq!(result.detection_count(), 2);
+        assert!(result.detections().iter().all(|d| !d.has_mask()));
+    }
+}
+
+#[test]
+fn rejects_garbage() {
+    for count in [f32::NAN, -1.0, 2.5, f32::INFINITY] {
+        let data = buffer(count, &[]);
+        assert!(matches!(RawOutput::parse(&data), Err(YoloError::Decode(_))));
+    }
+
+    // 缓冲区放不下计数对应的候选框
+    let short = vec![3.0; 1 + 2 * DETECTION_STRIDE];
+    assert!(matches!(
+        RawOutput::parse(&short),
+        Err(YoloError::Decode(_))
+    ));
+    assert!(matches!(RawOutput::parse(&[]), Err(YoloError::Decode(_))));
+
+    let data = buffer(
+        3.0,
+        &[
+            candidate([0.0, 0.0, 1.0, 1.0], 0.9, 0.0),
+            candidate([0.0, f32::NAN, 1.0, 1.0], 0.9, 0.0),
+            candidate([0.0, 0.0, 1.0, 1.0], 0.9, 1.5),
+        ],
+    );
+    let output = RawOutput::parse(&data).unwrap();
+    assert!(output.get(0).unwrap().is_ok());
+    assert!(output.get(1).unwrap().is_err());
+    assert!(output.get(2).unwrap().is_err());
+    assert!(output.get(3).is_none());
+    assert!(output.candidates().is_err());
+}
+
+#[test]
+fn ignores_fields_the_task_does_not_use() {
+    // 检测模型的插件不写入掩码系数、关键点和角度，这些位置是未初始化的显存
+    let mut values = candidate([0.0, 0.0, 10.0, 10.0], 0.9, 1.0);
+    values[6] = f32::NAN;
+    values[6 + 32] = f32::NAN;
+    values[DETECTION_STRIDE - 1] = f32::INFINITY;
+    let data = buffer(1.0, &[values]);
+
+    let model = ModelInfo::default();
+    let config = Config::new("models/yolo11s.engine").with_task(Task::Detect);
+    let result = postprocess_output(&data, None, &model, &config).unwrap();
+    assert_eq!(result.detection_count(), 1);
+
+    let output = RawOutput::parse(&data).unwrap();
+    let candidate = output
+        .with_task(Task::Detect)
+        .candidates()
+        .unwrap()
+        .remove(0);
+    assert_eq!(candidate.mask_coefficients, [0.0; MASK_COEFFICIENTS]);
+    assert_eq!(candidate.keypoints[0], [-1.0; 3]);
+    assert_eq!(candidate.angle, 0.0);
+
+    // 任务用到的字段仍然校验
+    for task in [Task::Segment, Task::Pose, Task::Obb] {
+        assert!(output.with_task(task).candidates().is_err(), "{:?}", task);
+    }
+}
+
+#[test]
+fn splits_batch_outputs() {
+    let mut data = buffer(1.0, &[candidate([0.0, 0.0, 1.0, 1.0], 0.9, 2.0)]);
+    data.extend(buffer(0.0, &[]));
+
+    let model = ModelInfo::default();
+    let outputs = RawOutput::parse_batch(&data, &model, 2).unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].candidates().unwrap()[0].class_id, 2);
+    assert!(outputs[1].is_empty());
+    assert!(RawOutput::parse_batch(&data, &model, 3).is_err());
+
+    // 容量为 100 的引擎，每张图片的输出更短
+    let small = ModelInfo {
+        max_detections: 100,
+        ..ModelInfo::default()
+    };
+    let mut data = vec![0.0; 2 * small.output_size()];
+    data[0] = 1.0;
+    data[1..1 + DETECTION_STRIDE].copy_from_slice(&candidate([0.0, 0.0, 1.0, 1.0], 0.9, 2.0));
+    data[small.output_size()] = 2.0;
+    let outputs = RawOutput::parse_batch(&data, &small, 2).unwrap();
+    assert_eq!(outputs[0].len(), 1);
+    assert_eq!(outputs[1].len(), 2);
+    assert!(RawOutput::parse_batch(&data, &model, 2).is_err());
+}
+
+#[test]
+fn decodes_masks_like_the_reference() {
+    let size = 40;
+    let data: Vec<f32> = (0..MASK_COEFFICIENTS * size * size)
+        .map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0)
+        .collect();
+    let proto = Proto::new(&data, size, size).unwrap();
+    let candidates: Vec<RawCandidate> = [
+        [10.0, 12.0, 90.0, 70.0],
+        [-20.0, -5.0, 30.0, 160.0],
+        [150.0, 140.0, 400.0, 400.0],
+        [50.0, 50.0, 51.0, 52.0],
+        [80.0, 80.0, 20.0, 20.0],
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, &bbox)| {
+        let mut candidate = RawCandidate::new(bbox, 0.9, 0);
+        for (c, coefficient) in candidate.mask_coefficients.iter_mut().enumerate() {
+            *coefficient = ((c + i) % 5) as f32 * 0.1 - 0.2;
+        }
+        candidate
+    })
+    .collect();
+
+    let masks = decode_masks(&candidates, &proto);
+    assert_eq!(masks.len(), candidates.len());
+    for (candidate, mask) in candidates.iter().zip(&masks) {
+        let expected = reference_mask(&data, size, PROTO_STRIDE, candidate);
+        assert_eq!(mask.len(), expected.len());
+        let max_error = mask
+            .iter()
+            .zip(&expected)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_error < 1e-5, "{:?}: {}", candidate.bbox, max_error);
+    }
+}
+
+extern "C" {
+    fn yolo_decode_mask(
+        proto: *const f32,
+        proto_w: i32,
+        proto_h: i32,
+        input_w: i32,
+        input_h: i32,
+        bbox: *const f32,
+        coefficients: *const f32,
+        mask: *mut f32,
+    ) -> bool;
+}
+
+#[test]
+fn native_mask_matches_rust_mask() {
+    // 原生后处理与 Rust 解码对同一个 [x1, y1, x2, y2] 框裁剪同样的原型区域
+    let size = 40;
+    let data: Vec<f32> = (0..MASK_COEFFICIENTS * size * size)
+        .map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0)
+        .collect();
+    let proto = Proto::new(&data, size, size).unwrap();
+    let mut candidate = RawCandidate::new([13.5, 7.9, 97.3, 71.2], 0.9, 0);
+    for (c, coefficient) in candidate.mask_coefficients.iter_mut().enumerate() {
+        *coefficient = (c % 5) as f32 * 0.1 - 0.2;
+    }
+
+    let expected = proto.decode_mask(&candidate);
+    let input = (size * PROTO_STRIDE) as i32;
+    let mut native = vec![0.0f32; expected.len()];
+    let ok = unsafe {
+        yolo_decode_mask(
+            data.as_ptr(),
+            size as i32,
+            size as i32,
+            input,
+            input,
+            candidate.bbox.as_ptr(),
+            candidate.mask_coefficients.as_ptr(),
+            native.as_mut_ptr(),
+        )
+    };
+    assert!(ok);
+    let max_error = native
+        .iter()
+        .zip(&expected)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    assert!(max_error < 1e-4, "{}", max_error);
+}
+
+#[test]
+fn postprocesses_non_default_input_size() {
+    // 320 输入的分割引擎，分割原型为 80×80，输出容量为 100
+    let model = ModelInfo {
+        input_width: 320,
+        input_height: 320,
+        max_detections: 100,
+        proto_width: 80,
+        proto_height: 80,
+        ..ModelInfo::default()
+    };
+    let mut output = vec![0.0; model.output_size()];
+    output[0] = 1.0;
+    output[1..1 + DETECTION_STRIDE].copy_from_slice(&candidate(
+        [40.0, 40.0, 200.0, 160.0],
+        0.9,
+        1.0,
+    ));
+    let proto: Vec<f32> = (0..model.proto_size())
+        .map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0)
+        .collect();
+
+    let config = Config::new("models/yolo11s-seg-320.engine");
+    let result = postprocess_output(&output, Some(&proto), &model, &config).unwrap();
+    assert_eq!(result.detection_count(), 1);
+    let detection = &result.detections()[0];
+    assert_eq!(detection.class_id(), 1);
+    assert_eq!((detection.mask_width, detection.mask_height), (320, 320));
+
+    let mut expected = RawCandidate::new([40.0, 40.0, 200.0, 160.0], 0.9, 1);
+    expected.mask_coefficients[0] = 0.25;
+    let reference = reference_mask(&proto, 80, model.proto_stride(), &expected);
+    let max_error = detection
+        .mask_data()
+        .iter()
+        .zip(&reference)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    assert!(max_error < 1e-5, "{}", max_error);
+
+    // 分割原型长度与模型信息不一致时报错而不是越界
+    assert!(postprocess_output(&output, Some(&proto[..100]), &model, &config).is_err());
+}
+
+#[test]
+fn derives_proto_stride_from_model() {
+    // 640 输入、分割原型 80×80 的引擎，下采样倍数为 8 而不是默认的 4
+    let model = ModelInfo {
+        proto_width: 80,
+        proto_height: 80,
+        ..ModelInfo::default()
+    };
+    assert_eq!(model.proto_stride(), 8);
+    assert_eq!(ModelInfo::default().proto_stride(), PROTO_STRIDE);
+
+    let mut output = vec![0.0; model.output_size()];
+    output[0] = 1.0;
+    output[1..1 + DETECTION_STRIDE].copy_from_slice(&candidate(
+        [80.0, 40.0, 400.0, 320.0],
+        0.9,
+        1.0,
+    ));
+    let proto: Vec<f32> = (0..model.proto_size())
+        .map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0)
+        .collect();
+
+    let config = Config::new("models/yolo11s-seg.engine");
+    let result = postprocess_output(&output, Some(&proto), &model, &config).unwrap();
+    let detection = &result.detections()[0];
+    assert_eq!((detection.mask_width, detection.mask_height), (640, 640));
+
+    let mut expected = RawCandidate::new([80.0, 40.0, 400.0, 320.0], 0.9, 1);
+    expected.mask_coefficients[0] = 0.25;
+    let reference = reference_mask(&proto, 80, 8, &expected);
+    let max_error = detection
+        .mask_data()
+        .iter()
+        .zip(&reference)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    assert!(max_error < 1e-5, "{}", max_error);
+}
@@ -0,0 +1,66 @@
+use yolo11s_tensorrt_rs::{ErrorCode, Frame, NativeError, YoloError};
+
+#[test]
+fn native_errors_map_to_matching_variants() {
+    let error = YoloError::from_native(
+        NativeError::new(
+            ErrorCode::EngineDeserialization,
+            "Failed to deserialize engine",
+        )
+        .with_path("models/broken.engine"),
+        YoloError::Inference,
+    );
+    assert!(matches!(error, YoloError::EngineDeserialization(_)));
+    assert_eq!(error.code(), Some(ErrorCode::EngineDeserialization));
+    assert_eq!(error.path(), Some("models/broken.engine"));
+    assert_eq!(error.kind(), "engine_deserialization");
+    assert!(error.to_string().contains("models/broken.engine"));
+
+    let error = YoloError::from_native(
+        NativeError::new(ErrorCode::Cuda, "cudaStreamSynchronize failed").with_cuda_error(700),
+        YoloError::Inference,
+    );
+    assert_eq!(error.native().and_then(|e| e.cuda_error), Some(700));
+    assert!(error.to_string().contains("700"));
+
+    let error = YoloError::from_native(
+        NativeError::new(ErrorCode::Io, "Failed to write image: out/result.jpg")
+            .with_path("out/result.jpg"),
+        YoloError::File,
+    );
+    assert!(matches!(error, YoloError::Io(_)));
+    assert_eq!(error.kind(), "io");
+    assert_eq!(error.path(), Some("out/result.jpg"));
+
+    let error = YoloError::from_native(
+        NativeError::new(ErrorCode::Unknown, "boom"),
+        YoloError::Inference,
+    );
+    assert!(matches!(error, YoloError::Inference(ref msg) if msg == "boom"));
+    assert_eq!(error.code(), None);
+}
+
+#[test]
+fn raw_codes_round_trip() {
+    assert_eq!(ErrorCode::from_raw(0), None);
+    for code in [
+        ErrorCode::InvalidArgument,
+        ErrorCode::FileNotFound,
+        ErrorCode::ImageDecode,
+        ErrorCode::EngineDeserialization,
+        ErrorCode::Cuda,
+        ErrorCode::TensorRt,
+        ErrorCode::OutOfMemory,
+        ErrorCode::Io,
+        ErrorCode::Unknown,
+    ] {
+        assert_eq!(ErrorCode::from_raw(code.as_raw()), Some(code));
+    }
+}
+
+#[test]
+fn missing_image_reports_path() {
+    let error = Frame::open("images/does-not-exist.jpg").unwrap_err();
+    assert_eq!(error.code(), Some(ErrorCode::FileNotFound));
+    assert_eq!(error.path(), Some("images/does-not-exist.jpg"));
+}
@@ -0,0 +1,211 @@
+use yolo11s_tensorrt_rs::{
+    BinaryMask, Detection, InferenceResult, Kernel, MaskRefinement, YoloError,
+};
+
+/// 用字符画构造掩码，每行一个像素行，`#` 为前景
+fn mask(art: &str) -> BinaryMask {
+    let rows: Vec<&str> = art.split_whitespace().collect();
+    let width = rows[0].len();
+    let pixels = rows
+        .iter()
+        .flat_map(|row| row.chars().map(|c| c == '#'))
+        .collect();
+    BinaryMask::new(width, rows.len(), pixels).unwrap()
+}
+
+#[test]
+fn open_removes_speckle_and_close_bridges_gaps() {
+    let noisy = mask(
+        "
+        #.........
+        ..........
+        ...####...
+        ...####...
+        ...####...
+        ...####...
+        ..........
+        .........#
+        ",
+    );
+    let opened = noisy.open(&Kernel::rect(3, 3));
+    assert_eq!(opened.area(), 16);
+    assert!(!opened.get(0, 0) && !opened.get(9, 7));
+
+    let broken = mask(
+        "
+        ...........
+        ...........
+        ..###.###..
+        ..###.###..
+        ..###.###..
+        ...........
+        ...........
+        ",
+    );
+    let closed = broken.close(&Kernel::rect(3, 3));
+    assert!(closed.get(5, 3));
+    assert_eq!(closed.area(), 21);
+}
+
+#[test]
+fn fill_holes_keeps_border_connected_background() {
+    let ring = mask(
+        "
+        #####...
+        #...#...
+        #...#...
+        #####...
+        ........
+        ",
+    );
+    let filled = ring.fill_holes();
+    assert_eq!(filled.area(), 20);
+    assert!(filled.get(2, 2));
+    assert!(!filled.get(6, 2));
+}
+
+#[test]
+fn component_filters_use_eight_connectivity() {
+    let blobs = mask(
+        "
+        ##.....#
+        ##......
+        ..#.....
+        ........
+        .....###
+        .....###
+        ",
+    );
+    // 对角相连的像素属于同一个连通域
+    let cleaned = blobs.remove_small_components(2);
+    assert_eq!(cleaned.area(), 11);
+    assert!(!cleaned.get(7, 0));
+
+    let largest = blobs.keep_largest_component();
+    assert_eq!(largest.area(), 6);
+    assert!(largest.get(5, 4) && !largest.get(0, 0));
+}
+
+#[test]
+fn ellipse_kernel_matches_opencv_shape() {
+    let point = mask(
+        "
+        .......
+        .......
+        .......
+        ...#...
+        .......
+        .......
+        .......
+        ",
+    );
+    let dilated = point.dilate(&Kernel::ellipse(5, 5));
+    let expected = mask(
+        "
+        .......
+        ...#...
+        .#####.
+        .#####.
+        .#####.
+        ...#...
+        .......
+        ",
+    );
+    assert_eq!(dilated, expected);
+    assert_eq!(point.dilate(&Kernel::cross(3, 3)).area(), 5);
+}
+
+#[test]
+fn refines_detection_masks_in_place() {
+    let probabilities: Vec<f32> = mask(
+        "
+        #.......
+        ........
+        .#####..
+        .#...#..
+        .#####..
+        ........
+        ",
+    )
+    .pixels()
+    .iter()
+    .map(|&p| if p { 0.9 } else { 0.1 })
+    .collect();
+
+    let mut result = InferenceResult::new();
+    result.add_detection(Detection::new([0.0, 0.0, 8.0, 6.0], 0.9, 0).with_mask(
+        probabilities,
+        8,
+        6,
+    ));
+    result.add_detection(Detection::new([0.0, 0.0, 8.0, 6.0], 0.8, 0));
+
+    let refinement = MaskRefinement::default()
+        .with_fill_holes(true)
+        .with_keep_largest(true);
+    result.refine_masks(&refinement);
+
+    let refined = result.detections()[0].binary_mask(0.5).unwrap();
+    assert_eq!(refined.area(), 15);
+    assert!(refined.get(3, 3) && !refined.get(0, 0));
+    assert!(!result.detections()[1].has_mask());
+}
+
+#[test]
+fn tightens_boxes_and_drops_empty_masks() {
+    let probabilities: Vec<f32> = mask(
+        "
+        ........
+        ..###...
+        ..####..
+        ........
+        ",
+    )
+    .pixels()
+    .iter()
+    .map(|&p| if p { 0.7 } else { 0.2 })
+    .collect();
+
+    let mut result = InferenceResult::new();
+    result.add_detection(Detection::new([0.0, 0.0, 8.0, 4.0], 0.9, 0).with_mask(
+        probabilities.clone(),
+        8,
+        4,
+    ));
+    result.add_detection(Detection::new([1.0, 1.0, 7.0, 3.0], 0.8, 1).with_mask(
+        vec![0.3; 32],
+        8,
+        4,
+    ));
+    result.add_detection(Detection::new([1.0, 1.0, 7.0, 3.0], 0.7, 2));
+
+    // 只细化不收紧时保留所有检测和原始边界框
+    let mut untouched = result.clone();
+    untouched.refine_masks(&MaskRefinement::default());
+    assert_eq!(untouched.detection_count(), 3);
+    assert_eq!(untouched.detections()[0].bbox(), [0.0, 0.0, 8.0, 4.0]);
+
+    result.refine_masks(&MaskRefinement::default().with_tighten_boxes(true));
+    let classes: Vec<i32> = result.detections().iter().map(|d| d.class_id()).collect();
+    assert_eq!(classes, vec![0, 2]);
+    assert_eq!(result.detections()[0].bbox(), [2.0, 1.0, 6.0, 3.0]);
+    assert_eq!(result.detections()[1].bbox(), [1.0, 1.0, 7.0, 3.0]);
+}
+
+#[test]
+fn mismatched_sizes_are_rejected() {
+    assert!(matches!(
+        BinaryMask::new(3, 2, vec![true; 5]),
+        Err(YoloError::InvalidParameter(_))
+    ));
+
+    // 检测结果的字段可以被调用方随意修改，尺寸不一致时不应 panic
+    let mut detection = Detection::new([0.0, 0.0, 4.0, 4.0], 0.9, 0).with_mask(vec![1.0; 16], 4, 4);
+    detection.mask_width = 5;
+    assert!(detection.binary_mask(0.5).is_none());
+    detection.mask_width = -4;
+    detection.mask_height = -4;
+    assert!(detection.binary_mask(0.5).is_none());
+    assert!(detection.refine_mask(&MaskRefinement::default()));
+    assert_eq!(detection.mask_data().len(), 16);
+}
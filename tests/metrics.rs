@@ -0,0 +1,62 @@
+use yolo11s_tensorrt_rs::{render_metrics, Detection, InferenceResult, PerfStats, YoloError};
+
+fn populated_stats() -> PerfStats {
+    let stats = PerfStats::new();
+    let mut result = InferenceResult::new();
+    result.total_time_ms = 3.0;
+    result.add_detection(Detection::new([0.0, 0.0, 1.0, 1.0], 0.9, 7));
+    stats.record(&result);
+    stats.record_error(&YoloError::File("missing.jpg".to_string()));
+    stats
+}
+
+#[test]
+fn renders_prometheus_text_format() {
+    let text = render_metrics(&populated_stats(), Some(4));
+
+    assert!(text.contains("# TYPE yolo_inferences_total counter\nyolo_inferences_total 1\n"));
+    assert!(text.contains("yolo_errors_total{kind=\"file\"} 1\n"));
+    assert!(text.contains("yolo_detections_total{class_id=\"7\"} 1\n"));
+    assert!(text.contains("yolo_stage_duration_seconds_bucket{stage=\"total\",le=\"0.0025\"} 0\n"));
+    assert!(text.contains("yolo_stage_duration_seconds_bucket{stage=\"total\",le=\"0.005\"} 1\n"));
+    assert!(text.contains("yolo_stage_duration_seconds_bucket{stage=\"total\",le=\"+Inf\"} 1\n"));
+    assert!(text.contains("yolo_stage_duration_seconds_sum{stage=\"total\"} 0.003\n"));
+    assert!(text.contains("yolo_stage_duration_seconds_count{stage=\"queue_wait\"} 1\n"));
+    assert!(text.contains("yolo_queue_depth 4\n"));
+}
+
+#[test]
+fn queue_depth_is_optional() {
+    let text = render_metrics(&PerfStats::new(), None);
+    assert!(!text.contains("yolo_queue_depth"));
+    assert!(text.contains("yolo_errors_total 0\n"));
+}
+
+#[cfg(feature = "metrics-server")]
+#[test]
+fn server_serves_metrics_endpoint() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Arc;
+    use yolo11s_tensorrt_rs::MetricsServer;
+
+    let server = MetricsServer::builder(Arc::new(populated_stats()))
+        .with_queue_depth(|| 2)
+        .serve("127.0.0.1:0")
+        .unwrap();
+
+    let get = |path: &str| {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    let response = get("/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("yolo_queue_depth 2\n"));
+    assert!(get("/other").starts_with("HTTP/1.1 404"));
+
+    server.shutdown();
+}
@@ -0,0 +1,364 @@
+use std::cmp::Ordering;
+
+use proptest::prelude::*;
+use yolo11s_tensorrt_rs::decode::MASK_COEFFICIENTS;
+use yolo11s_tensorrt_rs::nms::{
+    diou, iou, nms, nms_with_proto, rotated_iou, NmsConfig, NmsMethod, Overlap,
+};
+use yolo11s_tensorrt_rs::{
+    postprocess_output, Config, ModelInfo, Proto, RawCandidate, RotatedBox, YoloError,
+};
+
+/// 暴力参考实现：全局排序后逐个判断，不分组、不提前截断
+fn reference(candidates: &[RawCandidate], config: &NmsConfig) -> Vec<RawCandidate> {
+    // 没有分割原型时掩码交并比退化为交并比
+    let overlap = |a: &RawCandidate, b: &RawCandidate| match config.overlap {
+        Overlap::Iou | Overlap::Mask => iou(&a.bbox, &b.bbox),
+        Overlap::Diou => diou(&a.bbox, &b.bbox),
+        Overlap::Rotated => rotated_iou(&a.rotated_box(), &b.rotated_box()),
+    };
+    let same_group =
+        |a: &RawCandidate, b: &RawCandidate| config.class_agnostic || a.class_id == b.class_id;
+    let rank = |a: &(usize, RawCandidate), b: &(usize, RawCandidate)| -> Ordering {
+        b.1.confidence
+            .total_cmp(&a.1.confidence)
+            .then(a.1.bbox[0].total_cmp(&b.1.bbox[0]))
+            .then(a.0.cmp(&b.0))
+    };
+
+    let mut remaining: Vec<(usize, RawCandidate)> = candidates
+        .iter()
+        .filter(|c| c.confidence > config.confidence_threshold)
+        .cloned()
+        .enumerate()
+        .collect();
+    remaining.sort_by(rank);
+    if let Some(top_k) = config.top_k {
+        remaining.truncate(top_k);
+    }
+
+    let mut kept: Vec<(usize, RawCandidate)> = Vec::new();
+    while !remaining.is_empty() {
+        let best = (0..remaining.len())
+            .min_by(|&a, &b| rank(&remaining[a], &remaining[b]))
+            .unwrap();
+        let entry = remaining.remove(best);
+        for other in remaining.iter_mut() {
+            if !same_group(&entry.1, &other.1) {
+                continue;
+            }
+            let o = overlap(&entry.1, &other.1).clamp(0.0, 1.0);
+            match config.method {
+                NmsMethod::Hard if o > config.iou_threshold => other.1.confidence = 0.0,
+                NmsMethod::Linear if o > config.iou_threshold => other.1.confidence *= 1.0 - o,
+                NmsMethod::Gaussian { sigma } => other.1.confidence *= (-o * o / sigma).exp(),
+                _ => {}
+            }
+        }
+        remaining.retain(|other| other.1.confidence > config.confidence_threshold);
+        kept.push(entry);
+    }
+
+    kept.sort_by(rank);
+    let mut result: Vec<RawCandidate> = Vec::new();
+    for (_, candidate) in kept {
+        let class_count = result
+            .iter()
+            .filter(|c| c.class_id == candidate.class_id)
+            .count();
+        if config.max_per_class.is_none_or(|max| class_count < max) {
+            result.push(candidate);
+        }
+    }
+    if let Some(max_detections) = config.max_detections {
+        result.truncate(max_detections);
+    }
+    result
+}
+
+fn candidate() -> impl Strategy<Value = RawCandidate> {
+    // 整数坐标和粗粒度的置信度，让重叠和并列都经常出现
+    (0..60u8, 0..60u8, 1..40u8, 1..40u8, 0..=20u8, 0..3i32).prop_map(|(x, y, w, h, conf, class)| {
+        let (x, y) = (x as f32, y as f32);
+        RawCandidate::new(
+            [x, y, x + w as f32, y + h as f32],
+            conf as f32 / 20.0,
+            class,
+        )
+    })
+}
+
+fn config() -> impl Strategy<Value = NmsConfig> {
+    let method = prop_oneof![
+        Just(NmsMethod::Hard),
+        Just(NmsMethod::Linear),
+        (1..10u8).prop_map(|s| NmsMethod::Gaussian {
+            sigma: s as f32 / 10.0
+        }),
+    ];
+    let overlap = prop_oneof![
+        Just(Overlap::Iou),
+        Just(Overlap::Diou),
+        Just(Overlap::Mask),
+        Just(Overlap::Rotated),
+    ];
+    (
+        method,
+        overlap,
+        any::<bool>(),
+        0..10u8,
+        1..10u8,
+        proptest::option::of(1..30usize),
+        proptest::option::of(1..5usize),
+        proptest::option::of(1..20usize),
+    )
+        .prop_map(
+            |(method, overlap, agnostic, conf, iou, top_k, per_class, max)| NmsConfig {
+                method,
+                overlap,
+                class_agnostic: agnostic,
+                confidence_threshold: conf as f32 / 20.0,
+                iou_threshold: iou as f32 / 10.0,
+                top_k,
+                max_per_class: per_class,
+                max_detections: max,
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn matches_reference(
+        candidates in proptest::collection::vec(candidate(), 0..40),
+        config in config(),
+    ) {
+        prop_assert_eq!(nms(candidates.clone(), &config), reference(&candidates, &config));
+    }
+
+    #[test]
+    fn hard_nms_leaves_no_overlapping_pairs(
+        candidates in proptest::collection::vec(candidate(), 0..40),
+        agnostic in any::<bool>(),
+    ) {
+        let config = NmsConfig::default()
+            .with_confidence_threshold(0.0)
+            .with_class_agnostic(agnostic);
+        let kept = nms(candidates.clone(), &config);
+
+        for (i, a) in kept.iter().enumerate() {
+            for b in &kept[i + 1..] {
+                if agnostic || a.class_id == b.class_id {
+                    prop_assert!(iou(&a.bbox, &b.bbox) <= config.iou_threshold);
+                }
+            }
+        }
+        // 每个被丢弃的候选框都被某个置信度不低于它的检测抑制
+        for c in candidates.iter().filter(|c| c.confidence > 0.0 && !kept.contains(c)) {
+            let suppressed = kept.iter().any(|k| {
+                (agnostic || k.class_id == c.class_id)
+                    && k.confidence >= c.confidence
+                    && iou(&k.bbox, &c.bbox) > config.iou_threshold
+            });
+            prop_assert!(suppressed);
+        }
+    }
+}
+
+#[test]
+fn class_aware_keeps_overlapping_classes() {
+    let candidates = vec![
+        RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+        RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.8, 1),
+        RawCandidate::new([1.0, 0.0, 11.0, 10.0], 0.7, 0),
+        RawCandidate::new([50.0, 50.0, 60.0, 60.0], 0.4, 0),
+    ];
+
+    let kept = nms(candidates.clone(), &NmsConfig::default());
+    let classes: Vec<i32> = kept.iter().map(|c| c.class_id).collect();
+    assert_eq!(classes, vec![0, 1]);
+
+    let agnostic = nms(candidates, &NmsConfig::default().with_class_agnostic(true));
+    assert_eq!(agnostic.len(), 1);
+    assert_eq!(agnostic[0].confidence, 0.9);
+}
+
+#[test]
+fn soft_nms_decays_instead_of_dropping() {
+    let candidates = vec![
+        RawCandidate::new([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+        RawCandidate::new([0.0, 0.0, 10.0, 5.0], 0.8, 0),
+    ];
+    let config = NmsConfig::default()
+        .with_method(NmsMethod::Linear)
+        .with_confidence_threshold(0.1);
+
+    let kept = nms(candidates, &config);
+    assert_eq!(kept.len(), 2);
+    assert!((kept[1].confidence - 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn rejects_invalid_gaussian_sigma() {
+    for sigma in [0.0, -0.5, f32::NAN, f32::INFINITY] {
+        let nms = NmsConfig::default().with_method(NmsMethod::Gaussian { sigma });
+        assert!(matches!(
+            nms.validate(),
+            Err(YoloError::InvalidParameter(_))
+        ));
+
+        let config = Config::new("models/yolo11s-seg.engine").with_nms(nms);
+        let output = vec![0.0; ModelInfo::default().output_size()];
+        assert!(matches!(
+            postprocess_output(&output, None, &ModelInfo::default(), &config),
+            Err(YoloError::InvalidParameter(_))
+        ));
+    }
+    assert!(NmsConfig::default()
+        .with_method(NmsMethod::Gaussian { sigma: 0.5 })
+        .validate()
+        .is_ok());
+}
+
+#[test]
+fn diou_keeps_distant_centers() {
+    // IoU 为 0.5，但中心相距较远
+    let candidates = vec![
+        RawCandidate::new([0.0, 0.0, 30.0, 10.0], 0.9, 0),
+        RawCandidate::new([10.0, 0.0, 40.0, 10.0], 0.8, 0),
+    ];
+    let config = NmsConfig::default().with_iou_threshold(0.45);
+    assert_eq!(nms(candidates.clone(), &config).len(), 1);
+    assert_eq!(
+        nms(candidates, &config.with_overlap(Overlap::Diou)).len(),
+        2
+    );
+}
+
+#[test]
+fn limits_are_applied_after_suppression() {
+    let candidates: Vec<RawCandidate> = (0..10)
+        .map(|i| {
+            let x = i as f32 * 20.0;
+            RawCandidate::new([x, 0.0, x + 10.0, 10.0], 0.9 - i as f32 * 0.01, i % 2)
+        })
+        .collect();
+
+    let config = NmsConfig::default().with_max_per_class(2);
+    let kept = nms(candidates.clone(), &config);
+    assert_eq!(kept.len(), 4);
+
+    let kept = nms(candidates.clone(), &config.with_max_detections(3));
+    assert_eq!(kept.len(), 3);
+
+    let kept = nms(candidates, &NmsConfig::default().with_top_k(5));
+    assert_eq!(kept.len(), 5);
+    assert_eq!(kept[4].bbox[0], 80.0);
+}
+
+/// 通道 0 为主对角线、通道 1 为副对角线的细长掩码，其余位置为强背景
+fn crossing_proto(size: usize) -> Vec<f32> {
+    let mut proto = vec![0.0f32; MASK_COEFFICIENTS * size * size];
+    for y in 0..size {
+        for x in 0..size {
+            let index = y * size + x;
+            proto[index] = if x.abs_diff(y) <= 1 { 10.0 } else { -10.0 };
+            proto[size * size + index] = if (x + y).abs_diff(size - 1) <= 1 {
+                10.0
+            } else {
+                -10.0
+            };
+        }
+    }
+    proto
+}
+
+fn with_channel(bbox: [f32; 4], confidence: f32, channel: usize) -> RawCandidate {
+    let mut candidate = RawCandidate::new(bbox, confidence, 0);
+    candidate.mask_coefficients[channel] = 1.0;
+    candidate
+}
+
+#[test]
+fn mask_overlap_keeps_crossing_cracks() {
+    let data = crossing_proto(40);
+    let proto = Proto::new(&data, 40, 40).unwrap();
+    // 两条裂纹的边界框完全重合，掩码只在交叉处重叠
+    let candidates = vec![
+        with_channel([0.0, 0.0, 160.0, 160.0], 0.9, 0),
+        with_channel([0.0, 0.0, 160.0, 160.0], 0.8, 1),
+        with_channel([4.0, 4.0, 156.0, 156.0], 0.7, 0),
+    ];
+
+    let boxes = NmsConfig::default();
+    assert_eq!(nms_with_proto(candidates.clone(), &proto, &boxes).len(), 1);
+
+    let masks = boxes.with_overlap(Overlap::Mask);
+    let kept = nms_with_proto(candidates.clone(), &proto, &masks);
+    let confidences: Vec<f32> = kept.iter().map(|c| c.confidence).collect();
+    assert_eq!(confidences, vec![0.9, 0.8]);
+
+    // 没有分割原型时退化为边界框交并比
+    assert_eq!(nms(candidates, &masks).len(), 1);
+}
+
+#[test]
+fn proto_rejects_mismatched_length() {
+    let data = vec![0.0f32; MASK_COEFFICIENTS * 40 * 40];
+    assert!(Proto::new(&data, 40, 40).is_ok());
+    assert!(Proto::new(&data, 40, 39).is_err());
+    assert!(Proto::new(&data[1..], 40, 40).is_err());
+}
+
+fn rotated(cx: f32, cy: f32, width: f32, height: f32, angle: f32) -> RotatedBox {
+    RotatedBox {
+        cx,
+        cy,
+        width,
+        height,
+        angle,
+    }
+}
+
+proptest! {
+    #[test]
+    fn rotated_iou_matches_axis_aligned_iou(
+        x in 0..50u8, y in 0..50u8, w in 1..30u8, h in 1..30u8,
+        dx in 0..40u8, dy in 0..40u8, quarter_turns in 0..4u8,
+    ) {
+        let (x, y, w, h) = (x as f32, y as f32, w as f32, h as f32);
+        let a = [x, y, x + w, y + h];
+        let b = [x + dx as f32 - 20.0, y + dy as f32 - 20.0, x + w + 5.0, y + h];
+        let to_rotated = |r: [f32; 4], turns: u8| {
+            // 旋转 90° 的整数倍并交换宽高，得到同一个矩形
+            let (width, height) = (r[2] - r[0], r[3] - r[1]);
+            let (width, height) = if turns.is_multiple_of(2) { (width, height) } else { (height, width) };
+            let angle = turns as f32 * std::f32::consts::FRAC_PI_2;
+            rotated((r[0] + r[2]) / 2.0, (r[1] + r[3]) / 2.0, width, height, angle)
+        };
+        let expected = iou(&a, &b);
+        let actual = rotated_iou(&to_rotated(a, quarter_turns), &to_rotated(b, 0));
+        prop_assert!((expected - actual).abs() < 1e-3, "{} vs {}", expected, actual);
+    }
+}
+
+#[test]
+fn rotated_iou_of_crossing_boxes() {
+    let horizontal = rotated(0.0, 0.0, 10.0, 2.0, 0.0);
+    let vertical = rotated(0.0, 0.0, 10.0, 2.0, std::f32::consts::FRAC_PI_2);
+    // 交集为 2x2，并集为 20 + 20 - 4
+    assert!((rotated_iou(&horizontal, &vertical) - 4.0 / 36.0).abs() < 1e-5);
+    assert!((rotated_iou(&horizontal, &horizontal) - 1.0).abs() < 1e-5);
+    assert_eq!(
+        rotated_iou(&horizontal, &rotated(20.0, 0.0, 10.0, 2.0, 0.3)),
+        0.0
+    );
+
+    // 外接矩形完全重合的两个交叉旋转框：轴对齐交并比会抑制，旋转框交并比不会
+    let mut a = RawCandidate::new([50.0, 50.0, 80.0, 6.0], 0.9, 0);
+    a.angle = std::f32::consts::FRAC_PI_4;
+    let mut b = a.clone();
+    b.confidence = 0.8;
+    b.angle = -std::f32::consts::FRAC_PI_4;
+    let config = NmsConfig::default().with_overlap(Overlap::Rotated);
+    assert_eq!(nms(vec![a, b], &config).len(), 2);
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use common::MockBackend;
+use yolo11s_tensorrt_rs::{
+    InferenceBackend, InferenceResult, PerfStats, PoolConfig, Scheduling, Stage, YoloError,
+    YoloPool, YoloResult,
+};
+
+#[test]
+fn round_robin_spreads_jobs_evenly() {
+    let backends: Vec<_> = (0..3).map(|_| MockBackend::new(Duration::ZERO)).collect();
+    let stats: Vec<_> = backends.iter().map(|b| b.stats()).collect();
+    let pool = YoloPool::from_backends(
+        backends,
+        PoolConfig::new(3).with_scheduling(Scheduling::RoundRobin),
+    )
+    .unwrap();
+
+    let jobs: Vec<_> = (0..9).map(|_| pool.submit("image.jpg").unwrap()).collect();
+    for job in jobs {
+        assert_eq!(job.wait().unwrap().detection_count(), 1);
+    }
+    pool.shutdown();
+
+    for s in &stats {
+        assert_eq!(s.calls(), 3);
+    }
+}
+
+#[test]
+fn least_loaded_avoids_busy_worker() {
+    let slow = MockBackend::new(Duration::from_millis(200));
+    let fast = MockBackend::new(Duration::from_millis(1));
+    let (slow_stats, fast_stats) = (slow.stats(), fast.stats());
+    let pool = YoloPool::from_backends(
+        vec![slow, fast],
+        PoolConfig::new(2).with_scheduling(Scheduling::LeastLoaded),
+    )
+    .unwrap();
+
+    let jobs: Vec<_> = (0..10)
+        .map(|_| {
+            let job = pool.submit("image.jpg").unwrap();
+            thread::sleep(Duration::from_millis(5));
+            job
+        })
+        .collect();
+    for job in jobs {
+        job.wait().unwrap();
+    }
+    pool.shutdown();
+
+    assert!(fast_stats.calls() > slow_stats.calls());
+    assert_eq!(fast_stats.calls() + slow_stats.calls(), 10);
+}
+
+#[test]
+fn try_submit_reports_full_queue() {
+    let pool = YoloPool::from_backends(
+        vec![MockBackend::new(Duration::from_millis(200))],
+        PoolConfig::new(1).with_queue_capacity(1),
+    )
+    .unwrap();
+
+    let running = pool.submit("a.jpg").unwrap();
+    let queued = pool.submit("b.jpg").unwrap();
+    assert_eq!(pool.queue_depth(), 1);
+
+    match pool.try_submit("c.jpg") {
+        Err(YoloError::QueueFull(_)) => {}
+        other => panic!("expected QueueFull, got {:?}", other.map(|_| ())),
+    }
+
+    assert!(running.wait().is_ok());
+    assert!(queued.wait().is_ok());
+}
+
+#[test]
+fn shutdown_drains_outstanding_jobs() {
+    let completed = Arc::new(AtomicUsize::new(0));
+    let pool = YoloPool::from_backends(
+        vec![MockBackend::new(Duration::from_millis(5))],
+        PoolConfig::new(1).with_queue_capacity(16),
+    )
+    .unwrap();
+
+    for _ in 0..10 {
+        let completed = Arc::clone(&completed);
+        pool.submit_with("image.jpg", move |result| {
+            assert!(result.is_ok());
+            completed.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+    pool.shutdown();
+
+    assert_eq!(completed.load(Ordering::SeqCst), 10);
+}
+
+struct PanicBackend;
+
+impl InferenceBackend for PanicBackend {
+    fn inference(&mut self, _image_path: &str) -> YoloResult<InferenceResult> {
+        panic!("backend failure");
+    }
+}
+
+#[test]
+fn worker_survives_backend_panic() {
+    let pool = YoloPool::from_backends(vec![PanicBackend], PoolConfig::new(1)).unwrap();
+
+    for _ in 0..2 {
+        match pool.submit("image.jpg").unwrap().wait() {
+            Err(YoloError::Inference(_)) => {}
+            other => panic!("expected Inference error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn worker_survives_callback_panic() {
+    let pool = YoloPool::from_backends(vec![MockBackend::new(Duration::ZERO)], PoolConfig::new(1))
+        .unwrap();
+
+    pool.submit_with("image.jpg", |_| panic!("callback failure"))
+        .unwrap();
+    assert!(pool.submit("image.jpg").unwrap().wait().is_ok());
+}
+
+#[test]
+fn worker_recovers_poisoned_backend() {
+    let backend = MockBackend::new(Duration::ZERO).with_poison_path("bad.jpg");
+    let stats = backend.stats();
+    let pool = YoloPool::from_backends(vec![backend], PoolConfig::new(1)).unwrap();
+
+    match pool.submit("bad.jpg").unwrap().wait() {
+        Err(YoloError::Cuda(err)) => assert_eq!(err.cuda_error, Some(700)),
+        other => panic!("expected Cuda error, got {:?}", other.map(|_| ())),
+    }
+    assert!(pool.submit("good.jpg").unwrap().wait().is_ok());
+    pool.shutdown();
+
+    assert_eq!(stats.recoveries(), 1);
+    assert_eq!(stats.calls(), 1);
+}
+
+#[test]
+fn rejects_empty_pool() {
+    let result = YoloPool::from_backends(Vec::<MockBackend>::new(), PoolConfig::default());
+    assert!(matches!(result, Err(YoloError::InvalidParameter(_))));
+}
+
+#[test]
+fn queued_jobs_report_queue_wait() {
+    let pool = YoloPool::from_backends(
+        vec![MockBackend::new(Duration::from_millis(20))],
+        PoolConfig::new(1),
+    )
+    .unwrap();
+
+    let first = pool.submit("a.jpg").unwrap();
+    let second = pool.submit("b.jpg").unwrap();
+
+    assert!(first.wait().unwrap().queue_wait_ms < 20.0);
+    assert!(second.wait().unwrap().queue_wait_ms >= 15.0);
+}
+
+#[test]
+fn queued_jobs_record_queue_wait() {
+    let perf = Arc::new(PerfStats::new());
+    let backend = MockBackend::new(Duration::from_millis(20)).with_perf_stats(Arc::clone(&perf));
+    let pool = YoloPool::from_backends(vec![backend], PoolConfig::new(1)).unwrap();
+
+    let jobs: Vec<_> = (0..4).map(|_| pool.submit("image.jpg").unwrap()).collect();
+    for job in jobs {
+        job.wait().unwrap();
+    }
+    pool.shutdown();
+
+    // 后面的任务至少排队等待了前一个任务的推理时间
+    let snapshot = perf.snapshot();
+    let queue_wait = snapshot.stage(Stage::QueueWait);
+    assert_eq!(queue_wait.count, 4);
+    assert!(queue_wait.max_ms >= 20.0);
+    assert!(queue_wait.quantile_ms(0.99) > 0.0);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use common::MockBackend;
+use yolo11s_tensorrt_rs::{SharedYolo, Yolo};
+
+fn assert_send<T: Send>() {}
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn thread_safety_markers() {
+    assert_send::<Yolo>();
+    assert_send_sync::<SharedYolo>();
+    assert_send_sync::<SharedYolo<MockBackend>>();
+}
+
+#[test]
+fn serialises_concurrent_inference() {
+    let backend = MockBackend::new(Duration::from_millis(5));
+    let stats = backend.stats();
+    let yolo = SharedYolo::from_backend(backend);
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let yolo = yolo.clone();
+            thread::spawn(move || {
+                let path = "x".repeat(i + 1);
+                for _ in 0..4 {
+                    let result = yolo.inference(&path).unwrap();
+                    assert_eq!(result.detections()[0].class_id(), path.len() as i32);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(stats.calls(), 32);
+    assert_eq!(stats.max_active(), 1);
+}
+
+#[test]
+fn shared_through_arc() {
+    let yolo = Arc::new(SharedYolo::from_backend(MockBackend::new(Duration::ZERO)));
+    let worker = Arc::clone(&yolo);
+    let result = thread::spawn(move || worker.inference("images/test.jpg"))
+        .join()
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.detection_count(), 1);
+}
+
+#[test]
+fn recovers_from_panicking_caller() {
+    let yolo = SharedYolo::from_backend(MockBackend::new(Duration::ZERO));
+    let poisoner = yolo.clone();
+    let _ = thread::spawn(move || poisoner.with_backend(|_| panic!("boom"))).join();
+
+    assert!(yolo.inference("images/test.jpg").is_ok());
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use yolo11s_tensorrt_rs::{
+    Detection, ErrorCode, Histogram, InferenceResult, NativeError, PerfStats, Stage, YoloError,
+};
+
+fn result(total_ms: f64, classes: &[i32]) -> InferenceResult {
+    let mut result = InferenceResult::new();
+    result.total_time_ms = total_ms;
+    result.gpu_tensorrt_time_ms = total_ms / 2.0;
+    for &class_id in classes {
+        result.add_detection(Detection::new([0.0, 0.0, 1.0, 1.0], 0.9, class_id));
+    }
+    result
+}
+
+#[test]
+fn records_stages_detections_and_errors() {
+    let stats = PerfStats::new();
+    stats.record(&result(4.0, &[1, 2, 2]));
+    stats.record(&result(40.0, &[2]));
+    stats.record_error(&YoloError::Cuda(
+        NativeError::new(ErrorCode::Cuda, "boom").with_cuda_error(2),
+    ));
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.inferences, 2);
+    assert_eq!(snapshot.errors, 1);
+    assert_eq!(snapshot.errors_by_kind["cuda"], 1);
+    assert_eq!(snapshot.detections, 4);
+    assert_eq!(snapshot.detections_per_class[&2], 3);
+    assert!((snapshot.error_rate() - 1.0 / 3.0).abs() < 1e-9);
+
+    let total = snapshot.stage(Stage::Total);
+    assert_eq!(total.count, 2);
+    assert_eq!(total.sum_ms, 44.0);
+    assert_eq!(total.max_ms, 40.0);
+    assert_eq!(snapshot.stage(Stage::GpuTensorrt).sum_ms, 22.0);
+}
+
+#[test]
+fn histogram_buckets_are_cumulative() {
+    let mut histogram = Histogram::default();
+    for value in [0.1, 3.0, 3.0, 7.0, 10_000.0] {
+        histogram.observe(value);
+    }
+
+    let cumulative = histogram.cumulative();
+    assert_eq!(cumulative.first(), Some(&(0.5, 1)));
+    assert_eq!(cumulative.last(), Some(&(f64::INFINITY, 5)));
+    assert_eq!(histogram.quantile_ms(0.5), 5.0);
+    assert_eq!(histogram.quantile_ms(1.0), 10_000.0);
+}
+
+#[test]
+fn reset_clears_everything() {
+    let stats = PerfStats::new();
+    stats.record(&result(1.0, &[0]));
+    assert!(stats.throughput(Duration::from_secs(10)) > 0.0);
+
+    stats.reset();
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.inferences, 0);
+    assert!(snapshot.detections_per_class.is_empty());
+    assert_eq!(snapshot.stage(Stage::Total).count, 0);
+    assert_eq!(stats.throughput(Duration::from_secs(10)), 0.0);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::{Fault, FaultScript};
+use yolo11s_tensorrt_rs::{
+    ErrorCode, HealthState, NativeError, Supervisor, SupervisorConfig, YoloError,
+};
+
+fn config() -> SupervisorConfig {
+    SupervisorConfig::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+fn inference_error() -> YoloError {
+    YoloError::Inference("transient".to_string())
+}
+
+#[test]
+fn retries_transient_failures_and_reports_health() {
+    let script = FaultScript::new([Fault::Fail(inference_error())]);
+    let factory = script.clone();
+    let mut supervisor = Supervisor::from_factory(move || factory.backend(), config()).unwrap();
+
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&transitions);
+    supervisor.on_health_change(move |t| seen.lock().unwrap().push((t.from, t.to)));
+
+    assert!(supervisor.inference("image.jpg").is_ok());
+    assert_eq!(supervisor.stats().retries, 1);
+    assert_eq!(supervisor.health(), HealthState::Healthy);
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (HealthState::Healthy, HealthState::Degraded),
+            (HealthState::Degraded, HealthState::Healthy),
+        ]
+    );
+}
+
+#[test]
+fn recreates_after_repeated_failures() {
+    let script = FaultScript::new([
+        Fault::Fail(inference_error()),
+        Fault::Fail(inference_error()),
+    ]);
+    let factory = script.clone();
+    let mut supervisor =
+        Supervisor::from_factory(move || factory.backend(), config().with_recreate_after(2))
+            .unwrap();
+
+    assert!(supervisor.inference("image.jpg").is_ok());
+    assert_eq!(script.created(), 2);
+    assert_eq!(supervisor.stats().recreations, 1);
+    assert_eq!(supervisor.stats().retries, 2);
+}
+
+#[test]
+fn watchdog_abandons_hung_inference() {
+    let script = FaultScript::new([Fault::Hang(Duration::from_millis(300))]);
+    let factory = script.clone();
+    let mut supervisor = Supervisor::from_factory(
+        move || factory.backend(),
+        config().with_watchdog(Some(Duration::from_millis(30))),
+    )
+    .unwrap();
+
+    assert!(supervisor.inference("image.jpg").is_ok());
+    assert_eq!(supervisor.stats().timeouts, 1);
+    assert_eq!(script.created(), 2);
+
+    // 不重试时把超时返回给调用方
+    let script = FaultScript::new([Fault::Hang(Duration::from_millis(300))]);
+    let factory = script.clone();
+    let mut supervisor = Supervisor::from_factory(
+        move || factory.backend(),
+        config()
+            .with_max_retries(0)
+            .with_watchdog(Some(Duration::from_millis(30))),
+    )
+    .unwrap();
+    assert!(matches!(
+        supervisor.inference("image.jpg"),
+        Err(YoloError::Timeout(_))
+    ));
+}
+
+#[test]
+fn recovers_poisoned_backend_in_place() {
+    let script = FaultScript::new([Fault::Poison]);
+    let factory = script.clone();
+    let mut supervisor = Supervisor::from_factory(move || factory.backend(), config()).unwrap();
+
+    assert!(supervisor.inference("image.jpg").is_ok());
+    assert_eq!(script.recoveries(), 1);
+    assert_eq!(script.created(), 1);
+    assert_eq!(supervisor.stats().recoveries, 1);
+}
+
+#[test]
+fn input_errors_are_not_retried() {
+    let missing = YoloError::FileNotFound(
+        NativeError::new(ErrorCode::FileNotFound, "Failed to read image").with_path("missing.jpg"),
+    );
+    let script = FaultScript::new([Fault::Fail(missing)]);
+    let factory = script.clone();
+    let mut supervisor = Supervisor::from_factory(move || factory.backend(), config()).unwrap();
+
+    assert!(matches!(
+        supervisor.inference("missing.jpg"),
+        Err(YoloError::FileNotFound(_))
+    ));
+    assert_eq!(script.calls(), 1);
+    assert_eq!(supervisor.health(), HealthState::Healthy);
+}